@@ -0,0 +1,77 @@
+//! Parsing throughput of large responses: `cargo bench --bench parsing`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rust_connector_api::connector_response::{CsvChunkParser, ResponseBody};
+use rust_connector_api::grid::Grid;
+
+/// A grid CSV of `size` × `size` cells.
+fn grid_csv(size: usize) -> String {
+    let mut csv = String::from("Valid date = 2023-11-20T12:00:00Z\nParameter = t_2m:C\ndata");
+    for lon in 0..size {
+        csv.push_str(&format!(";{:.3}", 5.0 + lon as f64 * 0.01));
+    }
+    for lat in 0..size {
+        csv.push_str(&format!("\n{:.3}", 48.0 - lat as f64 * 0.01));
+        for lon in 0..size {
+            csv.push_str(&format!(
+                ";{:.2}",
+                ((lat * size + lon) % 4000) as f64 / 100.0 - 10.0
+            ));
+        }
+    }
+    csv.push('\n');
+    csv
+}
+
+/// A time series CSV of `rows` hourly records with `columns` parameters.
+fn time_series_csv(rows: usize, columns: usize) -> String {
+    let mut csv = String::from("validdate");
+    for column in 0..columns {
+        csv.push_str(&format!(";p{}:C", column));
+    }
+    for row in 0..rows {
+        csv.push_str(&format!(
+            "\n2023-{:02}-{:02}T{:02}:00:00Z",
+            row / 672 % 12 + 1,
+            row / 24 % 28 + 1,
+            row % 24
+        ));
+        for column in 0..columns {
+            csv.push_str(&format!(";{:.1}", ((row + column) % 500) as f64 / 10.0));
+        }
+    }
+    csv.push('\n');
+    csv
+}
+
+fn parse_grid(c: &mut Criterion) {
+    let csv = grid_csv(500);
+    let mut group = c.benchmark_group("grid");
+    group.throughput(Throughput::Bytes(csv.len() as u64));
+    group.bench_function("from_csv 500x500", |b| {
+        b.iter(|| Grid::from_csv(black_box(&csv)).unwrap())
+    });
+    group.finish();
+}
+
+fn parse_time_series(c: &mut Criterion) {
+    let columns = 20;
+    let csv = time_series_csv(8064, columns);
+    let mut group = c.benchmark_group("time_series");
+    group.throughput(Throughput::Bytes(csv.len() as u64));
+    group.bench_function("csv 8064x20", |b| {
+        b.iter(|| {
+            let mut response_body = ResponseBody::new();
+            let mut parser = CsvChunkParser::new();
+            for chunk in black_box(csv.as_bytes()).chunks(64 * 1024) {
+                parser.feed(&mut response_body, chunk).unwrap();
+            }
+            parser.finish(&mut response_body).unwrap();
+            response_body
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse_grid, parse_time_series);
+criterion_main!(benches);
@@ -0,0 +1,291 @@
+//! Command line client for quick data checks and cron jobs, enabled by the `cli` feature.
+//!
+//! ```text
+//! meteomatics --parameters t_2m:C,precip_1h:mm --coordinates 47.42,9.37 \
+//!     --from 2023-11-20T00:00:00Z --to 2023-11-21T00:00:00Z --step PT1H --output out.csv
+//! ```
+//!
+//! The credentials are read from the `METEOMATICS_*` environment variables, or from the
+//! configuration file given with `--config`, see `Config`.
+
+use chrono::DateTime;
+use rust_connector_api::connector_error::ConnectorError;
+use rust_connector_api::valid_date_time::{PeriodTime, RelativeDateTime, VDTOffset};
+use rust_connector_api::MeteomaticsConnector;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: meteomatics --parameters <P1,P2,...> --coordinates <LAT,LON[+LAT,LON...]> --from <DATE>
+                   [--to <DATE>] [--step <DURATION>] [--option <KEY=VALUE>]...
+                   [--format csv|json] [--output <PATH>] [--config <PATH>]
+
+Dates are RFC 3339 date times such as 2023-11-20T00:00:00Z or relative ones such as now or
+today+1D, durations ISO 8601 durations such as PT1H. The records are printed as CSV unless
+--format json is given, or saved to --output.
+
+Credentials are read from METEOMATICS_USERNAME and METEOMATICS_PASSWORD, or from --config.";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum OutputFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    /// `-h` or `--help` was given, asking for the usage instead of a query.
+    help: bool,
+    parameters: Vec<String>,
+    coordinates: Vec<(f64, f64)>,
+    from: Option<VDTOffset>,
+    to: Option<VDTOffset>,
+    step: Option<PeriodTime>,
+    options: Vec<(String, String)>,
+    format: OutputFormat,
+    output: Option<PathBuf>,
+    config: Option<PathBuf>,
+}
+
+impl Args {
+    /// Parses the arguments after the program name, failing with a message that names the
+    /// offending argument.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        while let Some(flag) = args.next() {
+            if flag == "-h" || flag == "--help" {
+                return Ok(Args {
+                    help: true,
+                    ..Default::default()
+                });
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", flag))?;
+            match flag.as_str() {
+                "--parameters" => {
+                    for parameter in value.split(',') {
+                        if parameter.trim().is_empty() {
+                            return Err(format!("Empty parameter in: {}", value));
+                        }
+                        parsed.parameters.push(parameter.trim().to_string());
+                    }
+                }
+                "--coordinates" => {
+                    for coordinate in value.split('+') {
+                        parsed.coordinates.push(parse_coordinate(coordinate)?);
+                    }
+                }
+                "--from" => parsed.from = Some(parse_date_time(&value)?),
+                "--to" => parsed.to = Some(parse_date_time(&value)?),
+                "--step" => {
+                    parsed.step = Some(
+                        value
+                            .parse()
+                            .map_err(|error: ConnectorError| format!("Invalid step: {}", error))?,
+                    )
+                }
+                "--option" => {
+                    let (key, value) = value
+                        .split_once('=')
+                        .ok_or_else(|| format!("Invalid option: {}", value))?;
+                    parsed.options.push((key.to_string(), value.to_string()));
+                }
+                "--format" => {
+                    parsed.format = match value.to_ascii_lowercase().as_str() {
+                        "csv" => OutputFormat::Csv,
+                        "json" => OutputFormat::Json,
+                        _ => return Err(format!("Unsupported format: {}", value)),
+                    }
+                }
+                "--output" => parsed.output = Some(PathBuf::from(value)),
+                "--config" => parsed.config = Some(PathBuf::from(value)),
+                _ => return Err(format!("Unknown argument: {}", flag)),
+            }
+        }
+        for (missing, flag) in [
+            (parsed.parameters.is_empty(), "--parameters"),
+            (parsed.coordinates.is_empty(), "--coordinates"),
+            (parsed.from.is_none(), "--from"),
+        ] {
+            if missing {
+                return Err(format!("Missing required argument {}", flag));
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+fn parse_coordinate(coordinate: &str) -> Result<(f64, f64), String> {
+    let invalid = || format!("Invalid coordinate: {}", coordinate);
+    let (lat, lon) = coordinate.split_once(',').ok_or_else(invalid)?;
+    let lat: f64 = lat.trim().parse().map_err(|_| invalid())?;
+    let lon: f64 = lon.trim().parse().map_err(|_| invalid())?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(format!("Coordinate out of range: {}", coordinate));
+    }
+    Ok((lat, lon))
+}
+
+fn parse_date_time(date_time: &str) -> Result<VDTOffset, String> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(date_time) {
+        return Ok(date_time.into());
+    }
+    date_time
+        .parse::<RelativeDateTime>()
+        .map(VDTOffset::from)
+        .map_err(|_| format!("Invalid date time: {}", date_time))
+}
+
+async fn run(args: Args) -> Result<(), ConnectorError> {
+    let connector = match &args.config {
+        Some(config) => MeteomaticsConnector::from_config_file(config)?,
+        None => MeteomaticsConnector::from_env()?,
+    };
+    let mut query = connector.time_series();
+    if let Some(from) = args.from {
+        query = query.from(from);
+    }
+    if let Some(to) = args.to {
+        query = query.to(to);
+    }
+    if let Some(step) = args.step {
+        query = query.step(step);
+    }
+    for parameter in args.parameters.iter() {
+        query = query.parameter(parameter);
+    }
+    for (lat, lon) in args.coordinates.iter() {
+        query = query.at(*lat, *lon);
+    }
+    for (key, value) in args.options.iter() {
+        query = query.option(key, value);
+    }
+    let response = query.run().await?;
+
+    match (args.format, &args.output) {
+        (OutputFormat::Csv, Some(output)) => response.write_csv(output),
+        (OutputFormat::Json, Some(output)) => response.write_json(output),
+        (OutputFormat::Csv, None) => response.write_csv_to(std::io::stdout().lock()),
+        (OutputFormat::Json, None) => {
+            response.write_json_to(std::io::stdout().lock())?;
+            println!();
+            Ok(())
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) if args.help => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            let mut source = std::error::Error::source(&error);
+            while let Some(cause) = source {
+                eprintln!("Caused by: {}", cause);
+                source = cause.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{Args, OutputFormat};
+    use chrono::{TimeZone, Utc};
+    use rust_connector_api::valid_date_time::{PeriodTime, VDTOffset};
+    use std::path::PathBuf;
+
+    fn parse(args: &str) -> Result<Args, String> {
+        Args::parse(args.split_whitespace().map(str::to_string))
+    }
+
+    #[tokio::test]
+    async fn parse_valid_arguments() {
+        println!("\n##### parse_valid_arguments:");
+
+        let args = parse(
+            "--parameters t_2m:C,precip_1h:mm --coordinates 47.42,9.37+-33.9,18.4 \
+             --from 2023-11-20T00:00:00Z --to 2023-11-21T00:00:00+01:00 --step PT1H \
+             --option model=mix --format JSON --output out.json --config meteomatics.toml",
+        )
+        .unwrap();
+        println!(">>>>>>>>>> args: {:?}", args);
+
+        assert!(!args.help);
+        assert_eq!(args.parameters, vec!["t_2m:C", "precip_1h:mm"]);
+        assert_eq!(args.coordinates, vec![(47.42, 9.37), (-33.9, 18.4)]);
+        assert_eq!(
+            args.from,
+            Some(VDTOffset::from(
+                Utc.with_ymd_and_hms(2023, 11, 20, 0, 0, 0)
+                    .unwrap()
+                    .fixed_offset()
+            ))
+        );
+        assert!(args.to.is_some());
+        assert_eq!(args.step, Some(PeriodTime::Hours(1)));
+        assert_eq!(args.options, vec![("model".to_string(), "mix".to_string())]);
+        assert_eq!(args.format, OutputFormat::Json);
+        assert_eq!(args.output, Some(PathBuf::from("out.json")));
+        assert_eq!(args.config, Some(PathBuf::from("meteomatics.toml")));
+
+        let args = parse("--parameters t_2m:C --coordinates 47.42,9.37 --from now").unwrap();
+        assert_eq!(args.format, OutputFormat::Csv);
+        assert_eq!((args.to, args.step, args.output), (None, None, None));
+
+        assert!(parse("--coordinates 1,2 --help").unwrap().help);
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_arguments() {
+        println!("\n##### reject_invalid_arguments:");
+
+        let valid = "--parameters t_2m:C --coordinates 47.42,9.37 --from now";
+        let cases = [
+            ("--parameters", "Missing value for --parameters"),
+            ("--verbose yes", "Unknown argument: --verbose"),
+            ("--parameters t_2m:C,,precip_1h:mm", "Empty parameter in"),
+            ("--coordinates 47.42", "Invalid coordinate: 47.42"),
+            ("--coordinates 47.42,east", "Invalid coordinate: 47.42,east"),
+            ("--coordinates 47.42,9.37+", "Invalid coordinate: "),
+            ("--coordinates 91,9.37", "Coordinate out of range: 91,9.37"),
+            ("--from 2023-11-20", "Invalid date time: 2023-11-20"),
+            ("--to 20.11.2023", "Invalid date time: 20.11.2023"),
+            ("--step 1h", "Invalid step: "),
+            ("--option model", "Invalid option: model"),
+            ("--format xml", "Unsupported format: xml"),
+        ];
+        for (args, message) in cases {
+            let error = parse(&format!("{} {}", valid, args)).unwrap_err();
+            println!(">>>>>>>>>> {}: {}", args, error);
+            assert!(error.contains(message), "{}: {}", args, error);
+        }
+
+        for (args, flag) in [
+            ("--coordinates 47.42,9.37 --from now", "--parameters"),
+            ("--parameters t_2m:C --from now", "--coordinates"),
+            ("--parameters t_2m:C --coordinates 47.42,9.37", "--from"),
+        ] {
+            assert_eq!(
+                parse(args).unwrap_err(),
+                format!("Missing required argument {}", flag)
+            );
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use crate::connector_error::ConnectorError;
+use bytes::Bytes;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// Settings of the response cache, which answers repeated identical requests without asking the
+/// API again. A cached response is served for `ttl_seconds`; after that it is revalidated with a
+/// conditional request if the API sent an `ETag` or `Last-Modified` header. Off by default and
+/// not available on wasm targets.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct CacheOptions {
+    /// Maximum number of responses kept in memory; the least recently used one is evicted first.
+    pub max_entries: usize,
+    /// Seconds during which a cached response is served without sending a request.
+    pub ttl_seconds: u64,
+    /// Directory in which responses are also stored, so that they outlive the process.
+    pub directory: Option<PathBuf>,
+}
+
+impl CacheOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.max_entries > 0 || self.directory.is_some()
+    }
+}
+
+/// A response kept by the `ResponseCache` or a recording, with its body already decompressed.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct CachedResponse {
+    pub url: String,
+    #[serde(default = "ok_status")]
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    #[serde(skip)]
+    pub body: Bytes,
+    /// Seconds since the Unix epoch at which the response was received or last revalidated.
+    pub stored_at: u64,
+}
+
+impl CachedResponse {
+    /// Reads the body of `response`, consuming it.
+    pub async fn read(url: &Url, response: Response) -> Result<Self, reqwest::Error> {
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                )
+            })
+            .collect();
+        let body = response.bytes().await?;
+        Ok(Self {
+            url: url.to_string(),
+            status,
+            headers,
+            body,
+            stored_at: now(),
+        })
+    }
+
+    /// This response, confirmed unchanged by a `304 Not Modified`.
+    pub fn revalidated(self) -> Self {
+        Self {
+            stored_at: now(),
+            ..self
+        }
+    }
+
+    pub fn is_fresh(&self, ttl_seconds: u64) -> bool {
+        now().saturating_sub(self.stored_at) < ttl_seconds
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// `If-None-Match` and `If-Modified-Since` headers from the validators of this response.
+    pub fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let validators = [(ETAG, IF_NONE_MATCH), (LAST_MODIFIED, IF_MODIFIED_SINCE)];
+        for (validator, conditional) in validators {
+            if let Some(value) = self
+                .header(validator.as_str())
+                .and_then(|value| HeaderValue::from_str(value).ok())
+            {
+                headers.insert(conditional, value);
+            }
+        }
+        headers
+    }
+
+    /// A response to `url` with the cached status, headers and body.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_response(&self, url: &Url) -> Response {
+        use reqwest::ResponseBuilderExt;
+
+        let mut builder = http::Response::builder()
+            .status(self.status)
+            .url(url.clone());
+        for (name, value) in self.headers.iter() {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        builder
+            .body(self.body.clone())
+            .expect("Cached headers were valid when received")
+            .into()
+    }
+}
+
+/// Responses cached by request URL, shared by all clones of a connector.
+#[derive(Clone, Default)]
+pub(crate) struct ResponseCache {
+    options: CacheOptions,
+    entries: Arc<Mutex<Entries>>,
+}
+
+/// The in-memory entries with the tick at which each was last used.
+#[derive(Default)]
+struct Entries {
+    tick: u64,
+    by_url: HashMap<String, (u64, CachedResponse)>,
+}
+
+impl Debug for ResponseCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("options", &self.options)
+            .field("entries", &self.entries.lock().unwrap().by_url.len())
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    pub fn new(options: CacheOptions) -> Result<Self, ConnectorError> {
+        if let Some(directory) = &options.directory {
+            std::fs::create_dir_all(directory)
+                .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        }
+        Ok(Self {
+            options,
+            entries: Default::default(),
+        })
+    }
+
+    pub fn options(&self) -> &CacheOptions {
+        &self.options
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.options.is_enabled()
+    }
+
+    /// The response cached for `url` in memory or, failing that, on disk.
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            entries.tick += 1;
+            let tick = entries.tick;
+            if let Some((last_used, cached)) = entries.by_url.get_mut(url) {
+                *last_used = tick;
+                return Some(cached.clone());
+            }
+        }
+        let cached = read_stored(self.options.directory.as_ref()?, url)?;
+        self.insert_in_memory(cached.clone());
+        Some(cached)
+    }
+
+    /// Stores `cached`; failing to write it to disk only costs the next cache hit and is merely
+    /// logged.
+    pub fn put(&self, cached: CachedResponse) {
+        if let Some(directory) = &self.options.directory {
+            if let Err(error) = store(directory, &cached) {
+                tracing::warn!(%error, "Failed to write cached response");
+            }
+        }
+        self.insert_in_memory(cached);
+    }
+
+    fn insert_in_memory(&self, cached: CachedResponse) {
+        if self.options.max_entries == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.by_url.insert(cached.url.clone(), (tick, cached));
+        while entries.by_url.len() > self.options.max_entries {
+            let least_recently_used = entries
+                .by_url
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(url, _)| url.clone())
+                .expect("Entries are not empty");
+            entries.by_url.remove(&least_recently_used);
+        }
+    }
+}
+
+fn paths(directory: &Path, url: &str) -> (PathBuf, PathBuf) {
+    let name = format!("{:016x}", fnv1a(url.as_bytes()));
+    (
+        directory.join(format!("{}.json", name)),
+        directory.join(format!("{}.body", name)),
+    )
+}
+
+/// The response stored for `url` in `directory`, if any.
+pub(crate) fn read_stored(directory: &Path, url: &str) -> Option<CachedResponse> {
+    let (meta_path, body_path) = paths(directory, url);
+    let meta = std::fs::read(meta_path).ok()?;
+    let mut cached: CachedResponse = serde_json::from_slice(&meta).ok()?;
+    if cached.url != url {
+        return None;
+    }
+    cached.body = std::fs::read(body_path).ok()?.into();
+    Some(cached)
+}
+
+/// Stores `cached` in `directory` as a JSON file of the status and headers and a file of the
+/// body.
+pub(crate) fn store(directory: &Path, cached: &CachedResponse) -> std::io::Result<()> {
+    let (meta_path, body_path) = paths(directory, &cached.url);
+    std::fs::write(&body_path, &cached.body)?;
+    std::fs::write(&meta_path, serde_json::to_vec_pretty(cached)?)
+}
+
+fn ok_status() -> u16 {
+    200
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust releases, so that file
+/// names stay valid for later builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::cache::{CacheOptions, CachedResponse, ResponseCache};
+    use bytes::Bytes;
+
+    fn cached(url: &str) -> CachedResponse {
+        CachedResponse {
+            url: url.to_string(),
+            status: 200,
+            headers: vec![("etag".to_string(), "\"v1\"".to_string())],
+            body: Bytes::copy_from_slice(url.as_bytes()),
+            stored_at: super::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn evict_least_recently_used() {
+        println!("\n##### evict_least_recently_used:");
+
+        let cache = ResponseCache::new(CacheOptions {
+            max_entries: 2,
+            ttl_seconds: 60,
+            directory: None,
+        })
+        .unwrap();
+        cache.put(cached("a"));
+        cache.put(cached("b"));
+        assert!(cache.get("a").is_some());
+        cache.put(cached("c"));
+        println!(">>>>>>>>>> cache: {:?}", cache);
+
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap().body, &b"a"[..]);
+        assert!(cache.get("c").unwrap().is_fresh(60));
+        assert!(!cache.get("c").unwrap().is_fresh(0));
+        assert_eq!(
+            cache.get("c").unwrap().conditional_headers()["if-none-match"],
+            "\"v1\""
+        );
+    }
+
+    #[tokio::test]
+    async fn read_back_from_directory() {
+        println!("\n##### read_back_from_directory:");
+
+        let directory = std::env::temp_dir().join("read_back_from_directory");
+        let options = CacheOptions {
+            max_entries: 0,
+            ttl_seconds: 60,
+            directory: Some(directory.clone()),
+        };
+        ResponseCache::new(options.clone())
+            .unwrap()
+            .put(cached("https://api.meteomatics.com/now/t_2m:C/47,9/csv"));
+
+        let cache = ResponseCache::new(options).unwrap();
+        let cached = cache
+            .get("https://api.meteomatics.com/now/t_2m:C/47,9/csv")
+            .unwrap();
+        assert_eq!(
+            cached.body,
+            &b"https://api.meteomatics.com/now/t_2m:C/47,9/csv"[..]
+        );
+        assert!(cache.get("https://api.meteomatics.com/other").is_none());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
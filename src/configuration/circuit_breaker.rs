@@ -0,0 +1,161 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// Settings of the circuit breaker, which stops sending requests for `cool_down_seconds` after
+/// `failure_threshold` consecutive failed requests: connection errors, timeouts and 5xx
+/// responses. Requests meanwhile fail fast with `ConnectorError::CircuitOpen`. After the
+/// cool-down one failure opens it again, one success closes it. Off by default and not
+/// available on wasm targets.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct CircuitBreakerOptions {
+    /// Consecutive failures that open the circuit; `0` leaves it off.
+    pub failure_threshold: u32,
+    pub cool_down_seconds: u64,
+}
+
+impl CircuitBreakerOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.failure_threshold > 0
+    }
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    /// Consecutive failures before the last success, restored if that response then stalls.
+    failures_before_success: u32,
+    #[cfg(not(target_arch = "wasm32"))]
+    open_until: Option<Instant>,
+}
+
+/// The circuit breaker of a connector, shared by all its clones.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CircuitBreaker {
+    options: CircuitBreakerOptions,
+    state: Arc<Mutex<CircuitState>>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(options: CircuitBreakerOptions) -> Self {
+        Self {
+            options,
+            state: Default::default(),
+        }
+    }
+
+    /// Fails while the circuit is open.
+    pub(crate) fn check(&self) -> Result<(), ConnectorError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(open_until) = self.lock().open_until {
+            let now = Instant::now();
+            if now < open_until {
+                return Err(ConnectorError::CircuitOpen {
+                    retry_after: open_until - now,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the outcome of a request, opening the circuit at the failure threshold.
+    pub(crate) fn record(&self, success: bool) {
+        if !self.options.is_enabled() {
+            return;
+        }
+        let mut state = self.lock();
+        if success {
+            *state = CircuitState {
+                failures_before_success: state.consecutive_failures,
+                ..Default::default()
+            };
+            return;
+        }
+        self.count_failure(&mut state);
+    }
+
+    /// Counts a response whose body stalled past the read timeout as a failure, in place of the
+    /// success it was recorded as when it arrived.
+    pub(crate) fn record_stalled(&self) {
+        if !self.options.is_enabled() {
+            return;
+        }
+        let mut state = self.lock();
+        state.consecutive_failures = state
+            .consecutive_failures
+            .max(state.failures_before_success);
+        self.count_failure(&mut state);
+    }
+
+    fn count_failure(&self, state: &mut CircuitState) {
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.consecutive_failures >= self.options.failure_threshold {
+            let cool_down = Duration::from_secs(self.options.cool_down_seconds);
+            tracing::warn!(
+                failures = state.consecutive_failures,
+                ?cool_down,
+                "Opening circuit"
+            );
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                state.open_until = Some(Instant::now() + cool_down);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CircuitState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::circuit_breaker::{CircuitBreaker, CircuitBreakerOptions};
+    use crate::connector_error::ConnectorError;
+
+    #[tokio::test]
+    async fn open_after_consecutive_failures() {
+        println!("\n##### open_after_consecutive_failures:");
+
+        let circuit_breaker = CircuitBreaker::new(CircuitBreakerOptions {
+            failure_threshold: 2,
+            cool_down_seconds: 60,
+        });
+        circuit_breaker.record(false);
+        circuit_breaker.record(true);
+        circuit_breaker.record(false);
+        assert!(circuit_breaker.check().is_ok());
+
+        circuit_breaker.record(false);
+        let result = circuit_breaker.check();
+        println!(">>>>>>>>>> result: {:?}", result);
+        match result {
+            Err(ConnectorError::CircuitOpen { retry_after }) => {
+                assert!(retry_after.as_secs() > 50)
+            }
+            _ => panic!("Expected an open circuit"),
+        }
+
+        let stalling = CircuitBreaker::new(CircuitBreakerOptions {
+            failure_threshold: 2,
+            cool_down_seconds: 60,
+        });
+        stalling.record(true);
+        stalling.record_stalled();
+        stalling.record(true);
+        assert!(stalling.check().is_ok());
+        stalling.record_stalled();
+        assert!(stalling.check().is_err());
+
+        let disabled = CircuitBreaker::default();
+        for _ in 0..10 {
+            disabled.record(false);
+        }
+        assert!(disabled.check().is_ok());
+    }
+}
@@ -0,0 +1,274 @@
+use crate::configuration::cache::CacheOptions;
+use crate::configuration::circuit_breaker::CircuitBreakerOptions;
+use crate::configuration::connector_builder::ConnectorBuilder;
+use crate::configuration::pool::PoolOptions;
+use crate::configuration::rate_limiter::Priority;
+use crate::configuration::recording::RecordingOptions;
+use crate::configuration::redirect::RedirectOptions;
+use crate::configuration::request_limits::RequestLimits;
+use crate::configuration::retry::RetryOptions;
+use crate::configuration::secret::SecretString;
+use crate::configuration::timeouts::TimeoutOptions;
+use crate::connector_error::ConnectorError;
+use crate::missing_values::MissingValuePolicy;
+use crate::parameters::ParameterValidation;
+use crate::valid_date_time::DatePrecision;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Connector settings as kept in a TOML (feature `toml`) or YAML (feature `yaml`) file.
+///
+/// ```toml
+/// username = "python-community"
+/// password = "..."
+/// timeout = 30
+/// retries = 3
+///
+/// [default_options]
+/// model = "mix"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Config {
+    pub username: String,
+    pub password: SecretString,
+    pub base_url: Option<String>,
+    /// Path between the base URL and queries, e.g. an API version.
+    pub path_prefix: Option<String>,
+    /// Total request timeout in seconds; `0` leaves it off.
+    pub timeout: Option<u64>,
+    /// Connect and read timeouts besides the total timeout.
+    #[serde(default)]
+    pub timeouts: TimeoutOptions,
+    pub retries: Option<u32>,
+    #[serde(default)]
+    pub retry: RetryOptions,
+    pub requests_per_second: Option<f64>,
+    pub max_concurrent_requests: Option<usize>,
+    pub priority: Option<Priority>,
+    pub missing_value_policy: Option<MissingValuePolicy>,
+    pub date_precision: Option<DatePrecision>,
+    pub parameter_validation: Option<ParameterValidation>,
+    /// Query options added to every request that does not set them itself.
+    #[serde(default)]
+    pub default_options: BTreeMap<String, String>,
+    pub user_agent: Option<String>,
+    /// Headers added to every request.
+    #[serde(default)]
+    pub default_headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub pool: PoolOptions,
+    #[serde(default)]
+    pub redirect: RedirectOptions,
+    #[serde(default)]
+    pub cache: CacheOptions,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerOptions,
+    pub recording: Option<RecordingOptions>,
+    #[serde(default)]
+    pub request_limits: RequestLimits,
+}
+
+impl Config {
+    /// Reads the file at `path`, choosing the format by its `toml`, `yaml` or `yml` extension.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConnectorError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_str(&content),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml_str(&content),
+            _ => {
+                let _ = content;
+                Err(ConnectorError::LibraryError(format!(
+                    "Unsupported configuration file: {}",
+                    path.display()
+                )))
+            }
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(content: &str) -> Result<Self, ConnectorError> {
+        toml::from_str(content).map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(content: &str) -> Result<Self, ConnectorError> {
+        serde_yaml::from_str(content).map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+
+    /// A `ConnectorBuilder` preset with these settings, which can still be overridden.
+    pub fn builder(&self) -> ConnectorBuilder {
+        let mut builder = ConnectorBuilder::default();
+        builder
+            .username(self.username.clone())
+            .password(self.password.clone())
+            .default_options(self.default_options.clone().into_iter().collect())
+            .default_headers(self.default_headers.clone().into_iter().collect())
+            .timeouts(self.timeouts)
+            .retry(self.retry)
+            .pool(self.pool.clone())
+            .redirect(self.redirect.clone())
+            .cache(self.cache.clone())
+            .circuit_breaker(self.circuit_breaker)
+            .request_limits(self.request_limits);
+        if let Some(base_url) = &self.base_url {
+            builder.base_url(base_url.clone());
+        }
+        if let Some(path_prefix) = &self.path_prefix {
+            builder.path_prefix(path_prefix.clone());
+        }
+        if let Some(recording) = &self.recording {
+            builder.recording(recording.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            builder.timeout(timeout);
+        }
+        if let Some(retries) = self.retries {
+            builder.retries(retries);
+        }
+        if let Some(requests_per_second) = self.requests_per_second {
+            builder.requests_per_second(requests_per_second);
+        }
+        if let Some(max_concurrent_requests) = self.max_concurrent_requests {
+            builder.max_concurrent_requests(max_concurrent_requests);
+        }
+        if let Some(priority) = self.priority {
+            builder.priority(priority);
+        }
+        if let Some(missing_value_policy) = self.missing_value_policy {
+            builder.missing_value_policy(missing_value_policy);
+        }
+        if let Some(date_precision) = self.date_precision {
+            builder.date_precision(date_precision);
+        }
+        if let Some(parameter_validation) = self.parameter_validation {
+            builder.parameter_validation(parameter_validation);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            builder.user_agent(user_agent.clone());
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::config::Config;
+    use crate::missing_values::MissingValuePolicy;
+
+    #[cfg(feature = "toml")]
+    #[tokio::test]
+    async fn load_toml() {
+        println!("\n##### load_toml:");
+
+        let config = Config::from_toml_str(
+            r#"
+username = "python-community"
+password = "Umivipawe179"
+base_url = "https://gateway.example.com/meteomatics"
+path_prefix = "v2"
+timeout = 30
+missing_value_policy = "Missing"
+date_precision = "Seconds"
+parameter_validation = "Known"
+user_agent = "dashboard/1.0"
+
+[default_options]
+model = "mix"
+
+[default_headers]
+X-Trace-Id = "abc123"
+
+[pool]
+max_idle_per_host = 16
+idle_timeout_seconds = 30
+
+[redirect]
+max_redirects = 2
+
+[cache]
+max_entries = 100
+ttl_seconds = 300
+"#,
+        )
+        .unwrap();
+        println!(">>>>>>>>>> config: {:?}", config);
+
+        assert_eq!(config.timeout, Some(30));
+        assert_eq!(config.path_prefix.as_deref(), Some("v2"));
+        assert_eq!(config.retries, None);
+        assert_eq!(config.user_agent.as_deref(), Some("dashboard/1.0"));
+        assert_eq!(config.default_headers["X-Trace-Id"], "abc123");
+        assert_eq!(config.pool.max_idle_per_host, Some(16));
+        assert_eq!(config.pool.tcp_keepalive_seconds, None);
+        assert_eq!(config.redirect.max_redirects, 2);
+        assert_eq!(
+            config.date_precision,
+            Some(crate::valid_date_time::DatePrecision::Seconds)
+        );
+        assert_eq!(
+            config.parameter_validation,
+            Some(crate::parameters::ParameterValidation::Known)
+        );
+        assert!(!config.redirect.forward_credentials);
+        assert_eq!(config.cache.max_entries, 100);
+        assert_eq!(config.cache.directory, None);
+        assert_eq!(
+            config.missing_value_policy,
+            Some(MissingValuePolicy::Missing)
+        );
+        assert_eq!(config.default_options["model"], "mix");
+        assert!(config.builder().build().is_ok());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[tokio::test]
+    async fn load_yaml() {
+        println!("\n##### load_yaml:");
+
+        let config = Config::from_yaml_str(
+            "username: python-community\npassword: Umivipawe179\nretries: 2\n",
+        )
+        .unwrap();
+        println!(">>>>>>>>>> config: {:?}", config);
+
+        assert_eq!(config.retries, Some(2));
+        assert!(config.default_options.is_empty());
+    }
+
+    #[tokio::test]
+    async fn preset_builder() {
+        println!("\n##### preset_builder:");
+
+        let config = Config {
+            username: "python-community".to_string(),
+            password: "Umivipawe179".into(),
+            base_url: Some("not a url".to_string()),
+            missing_value_policy: Some(MissingValuePolicy::Keep),
+            ..Default::default()
+        };
+
+        assert!(config.builder().build().is_err());
+        assert!(config
+            .builder()
+            .base_url("https://gateway.example.com")
+            .build()
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn reject_unknown_file_type() {
+        println!("\n##### reject_unknown_file_type:");
+
+        let path = std::env::temp_dir().join("reject_unknown_file_type.ini");
+        std::fs::write(&path, "username = python-community").unwrap();
+
+        assert!(Config::from_file(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,281 @@
+use crate::configuration::api_client::APIClient;
+use crate::configuration::cache::CacheOptions;
+use crate::configuration::circuit_breaker::CircuitBreakerOptions;
+use crate::configuration::pool::PoolOptions;
+use crate::configuration::rate_limiter::Priority;
+use crate::configuration::recording::RecordingOptions;
+use crate::configuration::redirect::RedirectOptions;
+use crate::configuration::request_limits::RequestLimits;
+use crate::configuration::retry::RetryOptions;
+use crate::configuration::secret::SecretString;
+use crate::configuration::timeouts::TimeoutOptions;
+use crate::configuration::tls::TlsOptions;
+use crate::connector_error::ConnectorError;
+use crate::missing_values::MissingValuePolicy;
+use crate::parameters::ParameterValidation;
+use crate::valid_date_time::DatePrecision;
+use crate::MeteomaticsConnector;
+
+/// Settings of a `MeteomaticsConnector`, assembled through `ConnectorBuilder`.
+#[derive(Builder, Clone, Debug)]
+#[builder(
+    name = "ConnectorBuilder",
+    public,
+    build_fn(private, name = "build_settings")
+)]
+struct ConnectorSettings {
+    #[builder(setter(into))]
+    username: String,
+
+    #[builder(setter(into))]
+    password: SecretString,
+
+    /// Total request timeout in seconds; `0` leaves it off, e.g. for large downloads guarded by
+    /// a read timeout.
+    #[builder(default = "10")]
+    timeout: u64,
+
+    #[builder(setter(into, strip_option), default)]
+    base_url: Option<String>,
+
+    /// Path between the base URL and queries, e.g. an API version.
+    #[builder(setter(into, strip_option), default)]
+    path_prefix: Option<String>,
+
+    /// Number of times a request is repeated after a connection error, a timeout or an HTTP
+    /// 429 or 5xx response.
+    #[builder(default)]
+    retries: u32,
+
+    /// Which failures are retried and for how long, see `RetryOptions`.
+    #[builder(default)]
+    retry: RetryOptions,
+
+    /// Maximum request rate, unlimited by default.
+    #[builder(setter(strip_option), default)]
+    requests_per_second: Option<f64>,
+
+    /// Maximum number of requests in flight, unlimited by default.
+    #[builder(setter(strip_option), default)]
+    max_concurrent_requests: Option<usize>,
+
+    /// Priority of the requests while the limits above hold them back.
+    #[builder(default)]
+    priority: Priority,
+
+    #[builder(default)]
+    missing_value_policy: MissingValuePolicy,
+
+    #[builder(default)]
+    date_precision: DatePrecision,
+
+    #[builder(default)]
+    parameter_validation: ParameterValidation,
+
+    /// Query options added to every request that does not set them itself.
+    #[builder(default)]
+    default_options: Vec<(String, String)>,
+
+    #[builder(setter(into, strip_option), default)]
+    user_agent: Option<String>,
+
+    /// Headers added to every request, e.g. for a proxy.
+    #[builder(default)]
+    default_headers: Vec<(String, String)>,
+
+    #[builder(default)]
+    tls: TlsOptions,
+
+    /// Connect and read timeouts besides the total timeout.
+    #[builder(default)]
+    timeouts: TimeoutOptions,
+
+    #[builder(default)]
+    pool: PoolOptions,
+
+    #[builder(default)]
+    redirect: RedirectOptions,
+
+    /// Response cache, off by default.
+    #[builder(default)]
+    cache: CacheOptions,
+
+    /// Circuit breaker, off by default.
+    #[builder(default)]
+    circuit_breaker: CircuitBreakerOptions,
+
+    /// Record-and-replay mode, off by default.
+    #[builder(setter(strip_option), default)]
+    recording: Option<RecordingOptions>,
+
+    /// Limits above which time series queries are split, none by default.
+    #[builder(default)]
+    request_limits: RequestLimits,
+}
+
+/// Environment variables read by `ConnectorBuilder::from_env`.
+pub const ENV_USERNAME: &str = "METEOMATICS_USERNAME";
+pub const ENV_PASSWORD: &str = "METEOMATICS_PASSWORD";
+pub const ENV_BASE_URL: &str = "METEOMATICS_BASE_URL";
+pub const ENV_TIMEOUT: &str = "METEOMATICS_TIMEOUT";
+
+impl ConnectorBuilder {
+    /// Starts a builder from `METEOMATICS_USERNAME` and `METEOMATICS_PASSWORD`, plus
+    /// `METEOMATICS_BASE_URL` and `METEOMATICS_TIMEOUT` (in seconds) when set.
+    pub fn from_env() -> Result<Self, ConnectorError> {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConnectorError> {
+        let required = |key: &str| {
+            var(key).ok_or_else(|| {
+                ConnectorError::LibraryError(format!("Environment variable {} is not set.", key))
+            })
+        };
+        let mut builder = ConnectorBuilder::default();
+        builder
+            .username(required(ENV_USERNAME)?)
+            .password(required(ENV_PASSWORD)?);
+        if let Some(base_url) = var(ENV_BASE_URL) {
+            builder.base_url(base_url);
+        }
+        if let Some(timeout) = var(ENV_TIMEOUT) {
+            builder.timeout(timeout.trim().parse().map_err(|_| {
+                ConnectorError::LibraryError(format!("Invalid {}: {}", ENV_TIMEOUT, timeout))
+            })?);
+        }
+        Ok(builder)
+    }
+
+    pub fn build(&self) -> Result<MeteomaticsConnector, ConnectorError> {
+        let settings = self
+            .build_settings()
+            .map_err(|error| ConnectorError::LibraryError(error.to_string()))?;
+        let mut api_client = APIClient::new(settings.username, settings.password, settings.timeout)
+            .with_retries(settings.retries)
+            .with_retry_options(settings.retry)
+            .with_rate_limit(
+                settings.requests_per_second,
+                settings.max_concurrent_requests,
+            )
+            .with_priority(settings.priority)
+            .with_missing_value_policy(settings.missing_value_policy)
+            .with_date_precision(settings.date_precision)
+            .with_parameter_validation(settings.parameter_validation)
+            .with_default_options(settings.default_options)
+            .with_redirects(settings.redirect)
+            .with_request_limits(settings.request_limits)
+            .with_default_headers(settings.default_headers)?;
+        if settings.tls != TlsOptions::default() {
+            api_client = api_client.with_tls(settings.tls)?;
+        }
+        if settings.timeouts != TimeoutOptions::default() {
+            api_client = api_client.with_timeouts(settings.timeouts)?;
+        }
+        if settings.pool != PoolOptions::default() {
+            api_client = api_client.with_pool(settings.pool)?;
+        }
+        if settings.cache.is_enabled() {
+            api_client = api_client.with_cache(settings.cache)?;
+        }
+        if settings.circuit_breaker.is_enabled() {
+            api_client = api_client.with_circuit_breaker(settings.circuit_breaker)?;
+        }
+        if let Some(recording) = settings.recording {
+            api_client = api_client.with_recording(recording)?;
+        }
+        if let Some(base_url) = settings.base_url {
+            api_client = api_client.with_base_url(&base_url)?;
+        }
+        if let Some(path_prefix) = settings.path_prefix {
+            api_client = api_client.with_path_prefix(&path_prefix)?;
+        }
+        if let Some(user_agent) = settings.user_agent {
+            api_client = api_client.with_user_agent(&user_agent)?;
+        }
+        Ok(MeteomaticsConnector { api_client })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::connector_builder::ConnectorBuilder;
+    use crate::configuration::pool::PoolOptions;
+    use crate::missing_values::MissingValuePolicy;
+    use crate::MeteomaticsConnector;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn build_connector() {
+        println!("\n##### build_connector:");
+
+        let meteomatics_connector = MeteomaticsConnector::builder()
+            .username("python-community")
+            .password("Umivipawe179")
+            .timeout(30)
+            .base_url("https://gateway.example.com/meteomatics")
+            .path_prefix("v2")
+            .retries(3)
+            .requests_per_second(5.0)
+            .max_concurrent_requests(4)
+            .missing_value_policy(MissingValuePolicy::Missing)
+            .user_agent("dashboard/1.0")
+            .default_headers(vec![("X-Request-Source".to_string(), "egress".to_string())])
+            .pool(PoolOptions {
+                max_idle_per_host: Some(16),
+                ..Default::default()
+            })
+            .build();
+        println!(
+            ">>>>>>>>>> meteomatics_connector: {:?}",
+            meteomatics_connector
+        );
+        assert!(meteomatics_connector.is_ok());
+
+        assert!(MeteomaticsConnector::builder()
+            .username("python-community")
+            .build()
+            .is_err());
+        assert!(MeteomaticsConnector::builder()
+            .username("python-community")
+            .password("Umivipawe179")
+            .base_url("not a url")
+            .build()
+            .is_err());
+        assert!(MeteomaticsConnector::builder()
+            .username("python-community")
+            .password("Umivipawe179")
+            .path_prefix("v2//beta")
+            .build()
+            .is_err());
+        assert!(MeteomaticsConnector::builder()
+            .username("python-community")
+            .password("Umivipawe179")
+            .default_headers(vec![("X Bad Name".to_string(), "value".to_string())])
+            .build()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn build_from_vars() {
+        println!("\n##### build_from_vars:");
+
+        let vars = HashMap::from([
+            ("METEOMATICS_USERNAME", "python-community"),
+            ("METEOMATICS_PASSWORD", "Umivipawe179"),
+            ("METEOMATICS_TIMEOUT", "30"),
+        ]);
+        let builder =
+            ConnectorBuilder::from_vars(|key| vars.get(key).map(|value| value.to_string()))
+                .unwrap();
+        assert_eq!(builder.timeout, Some(30));
+        assert!(builder.build().is_ok());
+
+        let vars = HashMap::from([("METEOMATICS_USERNAME", "python-community")]);
+        assert!(
+            ConnectorBuilder::from_vars(|key| vars.get(key).map(|value| value.to_string()))
+                .is_err()
+        );
+    }
+}
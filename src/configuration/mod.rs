@@ -1 +1,15 @@
 pub mod api_client;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod config;
+pub mod connector_builder;
+pub mod pool;
+pub mod progress;
+pub mod rate_limiter;
+pub mod recording;
+pub mod redirect;
+pub mod request_limits;
+pub mod retry;
+pub mod secret;
+pub mod timeouts;
+pub mod tls;
@@ -0,0 +1,74 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+
+/// Connection pool settings of the HTTP client; `None` keeps the reqwest default. Not available
+/// on wasm targets, where the browser manages connections.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PoolOptions {
+    /// Maximum number of idle connections kept open per host.
+    pub max_idle_per_host: Option<usize>,
+    /// Seconds after which an idle connection is closed.
+    pub idle_timeout_seconds: Option<u64>,
+    /// Interval in seconds of TCP keep-alive probes on open connections.
+    pub tcp_keepalive_seconds: Option<u64>,
+}
+
+impl PoolOptions {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn apply(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, ConnectorError> {
+        use std::time::Duration;
+
+        if let Some(max_idle_per_host) = self.max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle_per_host);
+        }
+        if let Some(idle_timeout_seconds) = self.idle_timeout_seconds {
+            builder = builder.pool_idle_timeout(Duration::from_secs(idle_timeout_seconds));
+        }
+        if let Some(tcp_keepalive_seconds) = self.tcp_keepalive_seconds {
+            builder = builder.tcp_keepalive(Duration::from_secs(tcp_keepalive_seconds));
+        }
+        Ok(builder)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn apply(
+        &self,
+        builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, ConnectorError> {
+        if *self != PoolOptions::default() {
+            return Err(ConnectorError::LibraryError(
+                "Pool options are not supported on wasm targets.".to_string(),
+            ));
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::pool::PoolOptions;
+
+    #[tokio::test]
+    async fn apply_pool_options() {
+        println!("\n##### apply_pool_options:");
+
+        let pool_options = PoolOptions {
+            max_idle_per_host: Some(16),
+            idle_timeout_seconds: Some(30),
+            tcp_keepalive_seconds: Some(60),
+        };
+        let builder = pool_options.apply(reqwest::Client::builder()).unwrap();
+        assert!(builder.build().is_ok());
+
+        let pool_options: PoolOptions =
+            serde_json::from_str(r#"{"max_idle_per_host": 4}"#).unwrap();
+        println!(">>>>>>>>>> pool_options: {:?}", pool_options);
+        assert_eq!(pool_options.max_idle_per_host, Some(4));
+        assert_eq!(pool_options.idle_timeout_seconds, None);
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// Progress of downloading and parsing one response body.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Progress {
+    pub bytes_downloaded: u64,
+    /// The `Content-Length` of the response, unknown for compressed or chunked bodies.
+    pub total_bytes: Option<u64>,
+    /// Records parsed so far; stays zero for binary downloads such as NetCDF or PNG.
+    pub records_parsed: usize,
+}
+
+impl Progress {
+    /// The downloaded fraction between 0 and 1, if the total is known.
+    pub fn fraction(&self) -> Option<f64> {
+        self.total_bytes
+            .filter(|total_bytes| *total_bytes > 0)
+            .map(|total_bytes| (self.bytes_downloaded as f64 / total_bytes as f64).min(1.0))
+    }
+}
+
+/// Callback invoked as a response body arrives, e.g. to drive a progress bar or reset a
+/// watchdog timer. On wasm targets the body arrives in one piece, so it is invoked once.
+#[derive(Clone)]
+pub struct ProgressHook(Arc<dyn Fn(Progress) + Send + Sync>);
+
+impl ProgressHook {
+    pub fn new(callback: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    pub(crate) fn report(&self, progress: Progress) {
+        (self.0)(progress)
+    }
+}
+
+impl Debug for ProgressHook {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressHook")
+    }
+}
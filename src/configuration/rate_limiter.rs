@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Arc, PoisonError};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+use tokio::sync::oneshot;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::{sync::Mutex, time::Instant};
+
+/// Whose requests go first while the rate or concurrency limit holds requests back. Requests of
+/// the same priority are served in order of arrival.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+pub enum Priority {
+    /// Bulk work, which waits while interactive requests are queued.
+    Batch,
+    #[default]
+    Interactive,
+}
+
+/// Client-side limit on the request rate and the number of requests in flight, shared by all
+/// clones of a connector. Waiting requests are let through by `Priority`. On wasm targets there
+/// is no timer, so only the concurrency limit is enforced.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    #[cfg(not(target_arch = "wasm32"))]
+    interval: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    next_slot: Arc<Mutex<Option<Instant>>>,
+    /// Hands out the rate slots one at a time, so that a later interactive request takes the
+    /// next slot ahead of queued batch requests.
+    #[cfg(not(target_arch = "wasm32"))]
+    slot_queue: Arc<PriorityQueue>,
+    permits: Option<Arc<PriorityQueue>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: Option<f64>, max_concurrent_requests: Option<usize>) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        let _ = requests_per_second;
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            interval: requests_per_second
+                .filter(|rps| *rps > 0.0)
+                .map(|rps| Duration::from_secs_f64(1.0 / rps)),
+            #[cfg(not(target_arch = "wasm32"))]
+            next_slot: Arc::new(Mutex::new(None)),
+            #[cfg(not(target_arch = "wasm32"))]
+            slot_queue: Arc::new(PriorityQueue::new(1)),
+            permits: max_concurrent_requests.map(|max| Arc::new(PriorityQueue::new(max.max(1)))),
+        }
+    }
+
+    /// Waits for a free request slot, behind all waiting requests of a higher priority. The
+    /// returned permit counts as in flight until dropped.
+    pub async fn acquire(&self, priority: Priority) -> Option<QueuePermit> {
+        let permit = match &self.permits {
+            Some(permits) => Some(permits.acquire(priority).await),
+            None => None,
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(interval) = self.interval {
+            let turn = self.slot_queue.acquire(priority).await;
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot.map_or(now, |next_slot| next_slot.max(now));
+            *next_slot = Some(slot + interval);
+            drop(next_slot);
+            tokio::time::sleep_until(slot).await;
+            drop(turn);
+        }
+        permit
+    }
+}
+
+/// A counting semaphore whose waiters are served by priority, then in order of arrival.
+#[derive(Debug)]
+struct PriorityQueue {
+    state: std::sync::Mutex<QueueState>,
+}
+
+#[derive(Debug)]
+struct QueueState {
+    available: usize,
+    waiting: BinaryHeap<Waiter>,
+    arrivals: u64,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    priority: Priority,
+    arrival: u64,
+    sender: oneshot::Sender<()>,
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then(other.arrival.cmp(&self.arrival))
+    }
+}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PriorityQueue {
+    fn new(permits: usize) -> Self {
+        Self {
+            state: std::sync::Mutex::new(QueueState {
+                available: permits,
+                waiting: BinaryHeap::new(),
+                arrivals: 0,
+            }),
+        }
+    }
+
+    async fn acquire(self: &Arc<Self>, priority: Priority) -> QueuePermit {
+        let receiver = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.available > 0 {
+                state.available -= 1;
+                return QueuePermit(self.clone());
+            }
+            let (sender, receiver) = oneshot::channel();
+            let arrival = state.arrivals;
+            state.arrivals += 1;
+            state.waiting.push(Waiter {
+                priority,
+                arrival,
+                sender,
+            });
+            receiver
+        };
+        let mut pending = PendingPermit {
+            queue: self.clone(),
+            receiver: Some(receiver),
+        };
+        if let Some(receiver) = pending.receiver.as_mut() {
+            // The sender is only dropped after sending, as the queue outlives this future.
+            let _ = receiver.await;
+        }
+        pending.receiver = None;
+        QueuePermit(self.clone())
+    }
+
+    /// Passes a permit on to the first waiter still waiting, or makes it available.
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        while let Some(waiter) = state.waiting.pop() {
+            if waiter.sender.send(()).is_ok() {
+                return;
+            }
+        }
+        state.available += 1;
+    }
+}
+
+/// A permit of a `PriorityQueue`, released when dropped.
+#[derive(Debug)]
+pub struct QueuePermit(Arc<PriorityQueue>);
+
+impl Drop for QueuePermit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+/// A waiter whose future may be dropped before or just after it was handed a permit, which must
+/// then be passed on rather than lost.
+struct PendingPermit {
+    queue: Arc<PriorityQueue>,
+    receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for PendingPermit {
+    fn drop(&mut self) {
+        if let Some(mut receiver) = self.receiver.take() {
+            receiver.close();
+            if receiver.try_recv().is_ok() {
+                self.queue.release();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::rate_limiter::{Priority, RateLimiter};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[tokio::test]
+    async fn space_requests() {
+        println!("\n##### space_requests:");
+
+        let rate_limiter = RateLimiter::new(Some(20.0), None);
+        let started = Instant::now();
+        for _ in 0..3 {
+            rate_limiter.acquire(Priority::Interactive).await;
+        }
+        println!(">>>>>>>>>> elapsed: {:?}", started.elapsed());
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn limit_concurrent_requests() {
+        println!("\n##### limit_concurrent_requests:");
+
+        let rate_limiter = RateLimiter::new(None, Some(1));
+        let permit = rate_limiter.acquire(Priority::Interactive).await;
+        assert!(permit.is_some());
+        assert!(tokio::time::timeout(
+            Duration::from_millis(50),
+            rate_limiter.acquire(Priority::Interactive)
+        )
+        .await
+        .is_err());
+        drop(permit);
+        assert!(rate_limiter.acquire(Priority::Interactive).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn serve_interactive_requests_first() {
+        println!("\n##### serve_interactive_requests_first:");
+
+        let rate_limiter = RateLimiter::new(None, Some(1));
+        let permit = rate_limiter.acquire(Priority::Interactive).await;
+        let order = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let mut waiters = vec![];
+        for (n, priority) in [Priority::Batch, Priority::Batch, Priority::Interactive]
+            .into_iter()
+            .enumerate()
+        {
+            let (rate_limiter, order) = (rate_limiter.clone(), order.clone());
+            waiters.push(tokio::spawn(async move {
+                let _permit = rate_limiter.acquire(priority).await;
+                order.lock().unwrap().push(n);
+            }));
+            // Queues the waiters in a known order.
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // A cancelled waiter gives up its place without losing a permit.
+        assert!(tokio::time::timeout(
+            Duration::from_millis(10),
+            rate_limiter.acquire(Priority::Interactive)
+        )
+        .await
+        .is_err());
+        drop(permit);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        println!(">>>>>>>>>> order: {:?}", order.lock().unwrap());
+
+        assert_eq!(*order.lock().unwrap(), vec![2, 0, 1]);
+        assert!(rate_limiter.acquire(Priority::Batch).await.is_some());
+    }
+}
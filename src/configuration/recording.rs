@@ -0,0 +1,58 @@
+use crate::configuration::cache::{read_stored, store, CachedResponse};
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Whether responses are recorded to or replayed from the recording directory.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingMode {
+    /// Sends every request and records its response, replacing an earlier recording.
+    Record,
+    /// Answers every request from its recording without using the network; a request without a
+    /// recording fails.
+    #[default]
+    Replay,
+    /// Replays recorded responses and records the responses of the other requests.
+    ReplayOrRecord,
+}
+
+/// Settings of the record-and-replay mode, in which responses are recorded to disk so that tests
+/// can later replay them deterministically without network access or credentials. Responses are
+/// keyed by request URL with any credentials in it left out, and stored with their status, so
+/// that error responses are replayed too. Not available on wasm targets.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct RecordingOptions {
+    #[serde(default)]
+    pub mode: RecordingMode,
+    pub directory: PathBuf,
+}
+
+impl RecordingOptions {
+    pub fn new(mode: RecordingMode, directory: impl Into<PathBuf>) -> Self {
+        Self {
+            mode,
+            directory: directory.into(),
+        }
+    }
+
+    /// The recorded response to the request for `url`, unless recording regardless.
+    pub(crate) fn replay(&self, url: &str) -> Result<Option<CachedResponse>, ConnectorError> {
+        if self.mode == RecordingMode::Record {
+            return Ok(None);
+        }
+        match read_stored(&self.directory, url) {
+            Some(recorded) => Ok(Some(recorded)),
+            None if self.mode == RecordingMode::Replay => Err(ConnectorError::LibraryError(
+                format!("No recorded response for {}", url),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn record(&self, recorded: &CachedResponse) -> Result<(), ConnectorError> {
+        std::fs::create_dir_all(&self.directory)
+            .and_then(|_| store(&self.directory, recorded))
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+}
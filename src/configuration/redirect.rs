@@ -0,0 +1,50 @@
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// How redirects, e.g. of a reverse proxy to region-specific hosts, are followed. On wasm
+/// targets the browser follows redirects itself and these options have no effect.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct RedirectOptions {
+    /// Maximum number of redirects followed per request; `0` returns redirect responses as they
+    /// are.
+    pub max_redirects: usize,
+    /// Sends the credentials to redirect targets on a different scheme, host or port than the
+    /// original request too. Off by default, so that they only go to the original origin.
+    pub forward_credentials: bool,
+}
+
+impl Default for RedirectOptions {
+    fn default() -> Self {
+        Self {
+            max_redirects: 10,
+            forward_credentials: false,
+        }
+    }
+}
+
+impl RedirectOptions {
+    /// The URL to request next if `response` to a request for `url` is a redirect that may still
+    /// be followed after `hops` redirects.
+    pub(crate) fn next_url(&self, response: &Response, url: &Url, hops: usize) -> Option<Url> {
+        let redirect = matches!(
+            response.status(),
+            StatusCode::MOVED_PERMANENTLY
+                | StatusCode::FOUND
+                | StatusCode::SEE_OTHER
+                | StatusCode::TEMPORARY_REDIRECT
+                | StatusCode::PERMANENT_REDIRECT
+        );
+        if !redirect || hops >= self.max_redirects {
+            return None;
+        }
+        let location = response.headers().get(reqwest::header::LOCATION)?;
+        url.join(location.to_str().ok()?).ok()
+    }
+
+    /// Whether a request for `url` that started at `original_url` carries the credentials.
+    pub(crate) fn sends_credentials(&self, original_url: &Url, url: &Url) -> bool {
+        self.forward_credentials || original_url.origin() == url.origin()
+    }
+}
@@ -0,0 +1,394 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{ConnectorResponse, ResponseBody, ResponseLocation};
+use crate::locations::{Coordinate, Locations, Point};
+use crate::parameters::Parameters;
+use crate::valid_date_time::ValidDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Per-request limits of the API account. Time series queries exceeding them are split into
+/// several requests, whose responses are stitched back into one. `None` leaves the respective
+/// limit off.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct RequestLimits {
+    /// Maximum number of parameters per request.
+    pub max_parameters: Option<usize>,
+    /// Maximum number of points per request; only point lists are split.
+    pub max_points: Option<usize>,
+    /// Maximum number of date times per request; only time lists and absolute ranges with a
+    /// time step are split.
+    pub max_time_steps: Option<usize>,
+    /// Maximum number of data points, locations × parameters × time steps, of a whole query.
+    /// Not split, but checked by `estimate_cost` before sending.
+    pub max_data_points: Option<u64>,
+}
+
+/// The size of a query, counted before sending it. `None` for counts that cannot be known
+/// locally, e.g. the cells of a named area or the date times of a relative range.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CostEstimate {
+    pub locations: Option<u64>,
+    pub parameters: u64,
+    pub time_steps: Option<u64>,
+    /// Locations × parameters × time steps.
+    pub data_points: Option<u64>,
+    /// Number of requests the query is split into by the request limits.
+    pub requests: usize,
+}
+
+/// Requests answering the same records, one per chunk of parameters.
+pub(crate) struct RecordGroup<'a> {
+    /// The requested point, if the group asks for one of several points. Single-point responses
+    /// carry no location column, so their records are indexed by it when stitched.
+    pub location: Option<Coordinate>,
+    pub parts: Vec<(Vec<ValidDateTime>, Parameters<'a>, Locations<'a>)>,
+}
+
+impl RequestLimits {
+    /// Counts the data points of a query and checks them against `max_data_points`, failing
+    /// with `ConnectorError::InvalidQuery` above it. Warns if the count is unknown while a
+    /// maximum is set, or if the query will be split into several requests.
+    pub(crate) fn estimate(
+        &self,
+        vdt: &ValidDateTime,
+        parameters: &Parameters<'_>,
+        locations: &Locations<'_>,
+    ) -> Result<CostEstimate, ConnectorError> {
+        let locations_count = locations.num_locations();
+        let parameters_count = parameters.p_values.len() as u64;
+        let time_steps = vdt.num_time_steps();
+        let data_points = locations_count
+            .zip(time_steps)
+            .and_then(|(l, t)| l.checked_mul(t)?.checked_mul(parameters_count));
+        let requests = self
+            .split(std::slice::from_ref(vdt), parameters, locations)?
+            .iter()
+            .map(|group| group.parts.len())
+            .sum();
+        let estimate = CostEstimate {
+            locations: locations_count,
+            parameters: parameters_count,
+            time_steps,
+            data_points,
+            requests,
+        };
+
+        match (self.max_data_points, data_points) {
+            (Some(max_data_points), Some(data_points)) if data_points > max_data_points => {
+                return Err(ConnectorError::InvalidQuery(format!(
+                    "Query of {} data points exceeds the limit of {}.",
+                    data_points, max_data_points
+                )));
+            }
+            (Some(max_data_points), None) => {
+                tracing::warn!(max_data_points, "Cannot count the data points of the query")
+            }
+            _ => {}
+        }
+        if requests > 1 {
+            tracing::warn!(
+                requests,
+                "Query exceeds the request limits and will be split"
+            );
+        }
+        Ok(estimate)
+    }
+
+    /// Splits a query into groups of requests within the limits, points first, then date times.
+    pub(crate) fn split<'a>(
+        &self,
+        vdts: &[ValidDateTime],
+        parameters: &Parameters<'a>,
+        locations: &Locations<'a>,
+    ) -> Result<Vec<RecordGroup<'a>>, ConnectorError> {
+        let location_chunks: Vec<(Option<Coordinate>, Locations<'a>)> =
+            match (self.max_points, split_points(locations)?) {
+                (Some(max_points), Some(points)) if points.len() > max_points => points
+                    .chunks(max_points.max(1))
+                    .map(|chunk| {
+                        let location = match chunk {
+                            [point] => Some((*point).into()),
+                            _ => None,
+                        };
+                        (location, Locations::Points(chunk.to_vec()))
+                    })
+                    .collect(),
+                _ => vec![(None, locations.clone())],
+            };
+        let vdt_chunks: Vec<Vec<ValidDateTime>> = match (self.max_time_steps, vdts) {
+            (Some(max_time_steps), [vdt]) => match vdt.split_time_steps(max_time_steps) {
+                Some(vdts) => vdts.into_iter().map(|vdt| vec![vdt]).collect(),
+                None => vec![vdts.to_vec()],
+            },
+            _ => vec![vdts.to_vec()],
+        };
+        let parameter_chunks: Vec<Parameters<'a>> = match self.max_parameters {
+            Some(max_parameters) => parameters
+                .p_values
+                .chunks(max_parameters.max(1))
+                .map(|chunk| Parameters {
+                    p_values: chunk.to_vec(),
+                })
+                .collect(),
+            None => vec![parameters.clone()],
+        };
+
+        let mut groups: Vec<RecordGroup<'a>> = vec![];
+        for (location, locations) in location_chunks.iter() {
+            for vdts in vdt_chunks.iter() {
+                groups.push(RecordGroup {
+                    location: *location,
+                    parts: parameter_chunks
+                        .iter()
+                        .map(|parameters| (vdts.clone(), parameters.clone(), locations.clone()))
+                        .collect(),
+                });
+            }
+        }
+        Ok(groups)
+    }
+}
+
+/// The points of a point list, or `None` for other locations.
+fn split_points(locations: &Locations<'_>) -> Result<Option<Vec<Point>>, ConnectorError> {
+    match locations {
+        Locations::Points(points) => Ok(Some(points.clone())),
+        Locations::Coordinates(_) => match locations.points() {
+            Some(coordinates) => coordinates
+                .into_iter()
+                .map(Point::try_from)
+                .collect::<Result<Vec<Point>, ConnectorError>>()
+                .map(Some),
+            None => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+impl ConnectorResponse {
+    /// Joins the columns of responses for the same records but different parameters.
+    pub(crate) fn join_columns(
+        responses: Vec<ConnectorResponse>,
+    ) -> Result<ConnectorResponse, ConnectorError> {
+        let mut responses = responses.into_iter();
+        let mut joined = responses
+            .next()
+            .ok_or_else(|| ConnectorError::LibraryError("No responses to join.".to_string()))?;
+        for response in responses {
+            let body = response.response_body;
+            let records = &mut joined.response_body.response_records;
+            if body.response_records.len() != records.len()
+                || body
+                    .response_records
+                    .iter()
+                    .zip(records.iter())
+                    .any(|((index, _), (joined_index, _))| index != joined_index)
+            {
+                return Err(ConnectorError::LibraryError(
+                    "Cannot join responses with different records.".to_string(),
+                ));
+            }
+            for ((_, values), (_, joined_values)) in
+                body.response_records.into_iter().zip(records.iter_mut())
+            {
+                joined_values.extend(values);
+            }
+            joined
+                .response_body
+                .response_headers
+                .extend(body.response_headers.into_iter().skip(1));
+            joined.metadata.elapsed += response.metadata.elapsed;
+        }
+        Ok(joined)
+    }
+
+    /// Concatenates the records of responses with the same columns, keeping the records of every
+    /// location together in order of first appearance.
+    pub(crate) fn concat_records(
+        responses: Vec<ConnectorResponse>,
+    ) -> Result<ConnectorResponse, ConnectorError> {
+        let mut responses = responses.into_iter();
+        let mut concatenated = responses
+            .next()
+            .ok_or_else(|| ConnectorError::LibraryError("No responses to join.".to_string()))?;
+        for response in responses {
+            if response.response_body.response_headers
+                != concatenated.response_body.response_headers
+            {
+                return Err(ConnectorError::LibraryError(
+                    "Cannot join responses with different columns.".to_string(),
+                ));
+            }
+            concatenated
+                .response_body
+                .response_records
+                .extend(response.response_body.response_records);
+            concatenated.metadata.elapsed += response.metadata.elapsed;
+        }
+        let response_body = &concatenated.response_body;
+        let mut response_records = vec![];
+        for (_, body) in response_body.split_by_location() {
+            response_records.extend(body.response_records);
+        }
+        concatenated.response_body = ResponseBody {
+            response_headers: response_body.response_headers.clone(),
+            response_records,
+        };
+        Ok(concatenated)
+    }
+
+    /// Indexes records without a location by `coordinate`.
+    pub(crate) fn with_location(mut self, coordinate: Option<Coordinate>) -> Self {
+        if let Some(coordinate) = coordinate {
+            for (index, _) in self.response_body.response_records.iter_mut() {
+                index
+                    .location
+                    .get_or_insert(ResponseLocation::Coordinate(coordinate));
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::request_limits::{CostEstimate, RequestLimits};
+    use crate::connector_error::ConnectorError;
+    use crate::locations::{BoundingBox, Locations, NamedArea, Point};
+    use crate::parameters::Parameters;
+    use crate::valid_date_time::{PeriodTime, VDTOffset, ValidDateTime, ValidDateTimeBuilder};
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn split_within_limits() {
+        println!("\n##### split_within_limits:");
+
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2023, 11, 20, 0, 0, 0).unwrap(),
+            ))
+            .end_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2023, 11, 20, 4, 30, 0).unwrap(),
+            ))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .unwrap();
+        let parameters = Parameters::try_from("t_2m:C,precip_1h:mm,wind_speed_10m:ms").unwrap();
+        let locations = Locations::Points(vec![
+            Point::new(47.41, 9.35).unwrap(),
+            Point::new(47.51, 8.74).unwrap(),
+            Point::new(46.95, 7.45).unwrap(),
+        ]);
+        let request_limits = RequestLimits {
+            max_parameters: Some(2),
+            max_points: Some(2),
+            max_time_steps: Some(3),
+            max_data_points: None,
+        };
+
+        let groups = request_limits
+            .split(std::slice::from_ref(&vdt), &parameters, &locations)
+            .unwrap();
+        let formatted: Vec<Vec<String>> = groups
+            .iter()
+            .map(|group| {
+                group
+                    .parts
+                    .iter()
+                    .map(|(vdts, parameters, locations)| {
+                        format!(
+                            "{}/{}/{}",
+                            ValidDateTime::format_list(vdts).unwrap(),
+                            parameters,
+                            locations
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        println!(">>>>>>>>>> formatted: {:#?}", formatted);
+
+        assert_eq!(groups.len(), 4);
+        assert_eq!(groups[0].location, None);
+        assert_eq!(groups[3].location, Some((46.95, 7.45)));
+        assert_eq!(
+            formatted[0],
+            vec![
+                "2023-11-20T00:00:00+00:00--2023-11-20T02:00:00+00:00:PT1H\
+                 /t_2m:C,precip_1h:mm/47.41,9.35+47.51,8.74",
+                "2023-11-20T00:00:00+00:00--2023-11-20T02:00:00+00:00:PT1H\
+                 /wind_speed_10m:ms/47.41,9.35+47.51,8.74",
+            ]
+        );
+        assert_eq!(
+            formatted[3][1],
+            "2023-11-20T03:00:00+00:00--2023-11-20T04:30:00+00:00:PT1H\
+             /wind_speed_10m:ms/46.95,7.45"
+        );
+
+        let groups = RequestLimits::default()
+            .split(&[vdt], &parameters, &locations)
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].parts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn estimate_query_cost() {
+        println!("\n##### estimate_query_cost:");
+
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2023, 11, 20, 0, 0, 0).unwrap(),
+            ))
+            .end_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2023, 11, 21, 0, 0, 0).unwrap(),
+            ))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .unwrap();
+        let parameters = Parameters::try_from("t_2m:C,precip_1h:mm").unwrap();
+        let bounding_box = Locations::BoundingBox(BoundingBox {
+            north: 48.0,
+            west: 7.0,
+            south: 46.0,
+            east: 9.0,
+            lat_step: 0.1,
+            lon_step: 0.5,
+        });
+        let request_limits = RequestLimits {
+            max_parameters: Some(1),
+            max_data_points: Some(10_000),
+            ..Default::default()
+        };
+
+        let estimate = request_limits
+            .estimate(&vdt, &parameters, &bounding_box)
+            .unwrap();
+        println!(">>>>>>>>>> estimate: {:?}", estimate);
+
+        assert_eq!(
+            estimate,
+            CostEstimate {
+                locations: Some(21 * 5),
+                parameters: 2,
+                time_steps: Some(25),
+                data_points: Some(21 * 5 * 2 * 25),
+                requests: 2,
+            }
+        );
+        let area = Locations::Area {
+            area: NamedArea::Europe,
+            resolution: "0.1,0.1",
+        };
+        let estimate = request_limits.estimate(&vdt, &parameters, &area).unwrap();
+        assert_eq!(estimate.data_points, None);
+
+        let points = Locations::Points(vec![Point::new(47.41, 9.35).unwrap(); 201]);
+        assert!(matches!(
+            request_limits.estimate(&vdt, &parameters, &points),
+            Err(ConnectorError::InvalidQuery(_))
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+/// Which failed requests are repeated, up to the number of retries, and for how long. A request
+/// that failed to connect or was answered with `429 Too Many Requests` has not been processed, so
+/// repeating it cannot count twice against a quota; after a timeout or a 5xx response it may
+/// have been. All classes are retried by default, see `RetryOptions::never_duplicate`. On wasm
+/// targets, which have no timer to back off with, requests are not retried.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct RetryOptions {
+    /// Retries requests that could not connect.
+    pub connect_errors: bool,
+    /// Retries requests without a response in time, or whose body stalls past the read timeout.
+    pub timeouts: bool,
+    /// Retries `5xx` responses.
+    pub server_errors: bool,
+    /// Retries `429 Too Many Requests` responses.
+    pub rate_limited: bool,
+    /// Seconds after the first attempt within which a retry must start; `None` leaves the
+    /// budget off.
+    pub max_elapsed_seconds: Option<u64>,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            connect_errors: true,
+            timeouts: true,
+            server_errors: true,
+            rate_limited: true,
+            max_elapsed_seconds: None,
+        }
+    }
+}
+
+impl RetryOptions {
+    /// Retries only requests that have certainly not been processed by the API: connection
+    /// errors and `429 Too Many Requests` responses.
+    pub fn never_duplicate() -> Self {
+        Self {
+            timeouts: false,
+            server_errors: false,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a request failed in a way that is retried.
+    pub(crate) fn is_retryable(&self, result: &Result<Response, reqwest::Error>) -> bool {
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                (self.rate_limited && status == StatusCode::TOO_MANY_REQUESTS)
+                    || (self.server_errors && status.is_server_error())
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Err(error) => {
+                (self.connect_errors && error.is_connect()) || (self.timeouts && error.is_timeout())
+            }
+            #[cfg(target_arch = "wasm32")]
+            Err(error) => self.timeouts && error.is_timeout(),
+        }
+    }
+
+    /// Whether a retry after `elapsed` since the first attempt and a further `delay` is still
+    /// within the time budget.
+    pub(crate) fn within_budget(
+        &self,
+        elapsed: std::time::Duration,
+        delay: std::time::Duration,
+    ) -> bool {
+        match self.max_elapsed_seconds {
+            Some(seconds) => elapsed + delay <= std::time::Duration::from_secs(seconds),
+            None => true,
+        }
+    }
+}
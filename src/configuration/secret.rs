@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// A credential such as the API password. `Debug` never shows the value, so that connectors and
+/// settings can be logged safely; it is only readable through `expose_secret`.
+#[derive(Clone, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self(secret.to_string())
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretString([redacted])")
+    }
+}
+
+/// The username and password of a client, shared with its clones and replaceable while
+/// requests are in flight. Every request reads both at once, so it never sends a mix of old and
+/// new credentials.
+#[derive(Clone)]
+pub(crate) struct SharedCredentials(Arc<RwLock<(String, SecretString)>>);
+
+impl SharedCredentials {
+    pub(crate) fn new(username: String, password: SecretString) -> Self {
+        Self(Arc::new(RwLock::new((username, password))))
+    }
+
+    pub(crate) fn get(&self) -> (String, SecretString) {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn replace(&self, username: String, password: SecretString) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = (username, password);
+    }
+}
+
+impl Debug for SharedCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (username, password) = self.get();
+        f.debug_struct("Credentials")
+            .field("username", &username)
+            .field("password", &password)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::secret::SecretString;
+
+    #[tokio::test]
+    async fn redact_secret_in_debug_output() {
+        println!("\n##### redact_secret_in_debug_output:");
+
+        let secret = SecretString::from("Umivipawe179");
+        let debug = format!("{:?}", secret);
+        println!(">>>>>>>>>> secret: {}", debug);
+
+        assert!(!debug.contains("Umivipawe179"));
+        assert_eq!(secret.expose_secret(), "Umivipawe179");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"Umivipawe179\"");
+    }
+}
@@ -0,0 +1,41 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+
+/// Timeouts besides the total request timeout, in seconds; `None` leaves them off. A read
+/// timeout without a total timeout lets a large download run as long as data keeps arriving.
+/// Not available on wasm targets, where the browser manages connections.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TimeoutOptions {
+    /// Seconds to establish a connection, including the TLS handshake.
+    pub connect_seconds: Option<u64>,
+    /// Seconds to wait for each piece of the response body, failing with
+    /// `ConnectorError::ReadTimeout`.
+    pub read_seconds: Option<u64>,
+}
+
+impl TimeoutOptions {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn apply(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, ConnectorError> {
+        if let Some(connect_seconds) = self.connect_seconds {
+            builder = builder.connect_timeout(std::time::Duration::from_secs(connect_seconds));
+        }
+        Ok(builder)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn apply(
+        &self,
+        builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, ConnectorError> {
+        if *self != TimeoutOptions::default() {
+            return Err(ConnectorError::LibraryError(
+                "Timeout options are not supported on wasm targets.".to_string(),
+            ));
+        }
+        Ok(builder)
+    }
+}
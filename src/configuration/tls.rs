@@ -0,0 +1,130 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+
+/// TLS implementation for HTTPS connections, each available with the crate feature of the same
+/// name (`rustls-tls`, the default, or `native-tls`).
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TlsBackend {
+    Rustls,
+    NativeTls,
+}
+
+/// TLS settings of the HTTP client, e.g. for TLS-intercepting proxies or private deployments.
+/// Not available on wasm targets, where the browser handles TLS.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TlsOptions {
+    /// The TLS implementation, or `None` for the one enabled by the crate features.
+    pub backend: Option<TlsBackend>,
+    /// PEM-encoded certificates trusted in addition to the built-in root certificates.
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Trusts only `root_certificates`, pinning connections to servers whose chain ends in one
+    /// of them.
+    pub pin_root_certificates: bool,
+}
+
+impl TlsOptions {
+    /// Adds the PEM-encoded certificate read from `path` to `root_certificates`.
+    pub fn with_root_certificate_file<P: AsRef<std::path::Path>>(
+        mut self,
+        path: P,
+    ) -> Result<Self, ConnectorError> {
+        let pem =
+            std::fs::read(path).map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        self.root_certificates.push(pem);
+        Ok(self)
+    }
+
+    #[cfg(all(
+        not(target_arch = "wasm32"),
+        any(feature = "rustls-tls", feature = "native-tls")
+    ))]
+    pub(crate) fn apply(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, ConnectorError> {
+        builder = match self.backend {
+            None => builder,
+            #[cfg(feature = "rustls-tls")]
+            Some(TlsBackend::Rustls) => builder.use_rustls_tls(),
+            #[cfg(feature = "native-tls")]
+            Some(TlsBackend::NativeTls) => builder.use_native_tls(),
+            #[allow(unreachable_patterns)]
+            Some(backend) => {
+                return Err(ConnectorError::LibraryError(format!(
+                    "TLS backend {:?} is not enabled by the crate features.",
+                    backend
+                )))
+            }
+        };
+        for pem in self.root_certificates.iter() {
+            if !String::from_utf8_lossy(pem).contains("-----BEGIN CERTIFICATE-----") {
+                return Err(ConnectorError::LibraryError(
+                    "Root certificate is not PEM-encoded.".to_string(),
+                ));
+            }
+            let certificate = reqwest::Certificate::from_pem(pem)
+                .map_err(|error| ConnectorError::ApiError { source: error })?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        if self.pin_root_certificates {
+            if self.root_certificates.is_empty() {
+                return Err(ConnectorError::LibraryError(
+                    "Pinning requires at least one root certificate.".to_string(),
+                ));
+            }
+            builder = builder.tls_built_in_root_certs(false);
+        }
+        Ok(builder)
+    }
+
+    #[cfg(not(all(
+        not(target_arch = "wasm32"),
+        any(feature = "rustls-tls", feature = "native-tls")
+    )))]
+    pub(crate) fn apply(
+        &self,
+        builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, ConnectorError> {
+        if *self != TlsOptions::default() {
+            return Err(ConnectorError::LibraryError(
+                "TLS options are not supported without a TLS backend or on wasm targets."
+                    .to_string(),
+            ));
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::configuration::tls::TlsOptions;
+    use crate::test_support::ROOT_CERTIFICATE_PEM;
+
+    #[tokio::test]
+    async fn apply_tls_options() {
+        println!("\n##### apply_tls_options:");
+
+        let tls_options = TlsOptions {
+            backend: None,
+            root_certificates: vec![ROOT_CERTIFICATE_PEM.as_bytes().to_vec()],
+            pin_root_certificates: true,
+        };
+        let builder = tls_options.apply(reqwest::Client::builder()).unwrap();
+        assert!(builder.build().is_ok());
+
+        let tls_options = TlsOptions {
+            root_certificates: vec![b"not a certificate".to_vec()],
+            ..Default::default()
+        };
+        let result = tls_options.apply(reqwest::Client::builder());
+        println!(">>>>>>>>>> result: {:?}", result.as_ref().err());
+        assert!(result.is_err());
+
+        let tls_options = TlsOptions {
+            pin_root_certificates: true,
+            ..Default::default()
+        };
+        assert!(tls_options.apply(reqwest::Client::builder()).is_err());
+    }
+}
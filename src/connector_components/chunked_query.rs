@@ -0,0 +1,84 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::ConnectorResponse;
+use crate::query_spec::OwnedQuerySpec;
+use crate::valid_date_time::{PeriodDate, ValidDateTime};
+use serde::{Deserialize, Serialize};
+
+/// A long-range time series query, e.g. a multi-year archive extraction, split into chunks of a
+/// calendar period that are requested separately by `MeteomaticsConnector::query_chunked` and
+/// concatenated into one response.
+///
+/// The responses of completed chunks are kept, so that after a failed chunk running the query
+/// again only requests the missing ones. Being serializable, the progress can also be saved to
+/// resume in a later process.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ChunkedQuery {
+    query_spec: OwnedQuerySpec,
+    chunks: Vec<ValidDateTime>,
+    responses: Vec<Option<ConnectorResponse>>,
+}
+
+impl ChunkedQuery {
+    /// Splits `query_spec` into chunks spanning `chunk` each, e.g. `PeriodDate::Months(1)`. The
+    /// valid date time must be an absolute range with a time step.
+    pub fn new(query_spec: OwnedQuerySpec, chunk: PeriodDate) -> Result<Self, ConnectorError> {
+        let chunks = query_spec.vdt.split_by_period(chunk).ok_or_else(|| {
+            ConnectorError::InvalidQuery(
+                "Only absolute ranges with a time step can be split into chunks of a positive \
+                 period."
+                    .to_string(),
+            )
+        })?;
+        Ok(Self {
+            query_spec,
+            responses: vec![None; chunks.len()],
+            chunks,
+        })
+    }
+
+    pub fn chunks(&self) -> &[ValidDateTime] {
+        &self.chunks
+    }
+
+    pub fn num_completed(&self) -> usize {
+        self.responses.iter().flatten().count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.responses.iter().all(Option::is_some)
+    }
+
+    /// The query of every chunk that has not been completed yet, with its index.
+    pub(crate) fn pending(&self) -> Vec<(usize, OwnedQuerySpec)> {
+        self.chunks
+            .iter()
+            .zip(self.responses.iter())
+            .enumerate()
+            .filter(|(_, (_, response))| response.is_none())
+            .map(|(index, (vdt, _))| {
+                (
+                    index,
+                    OwnedQuerySpec {
+                        vdt: vdt.clone(),
+                        ..self.query_spec.clone()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn complete(&mut self, index: usize, response: ConnectorResponse) {
+        self.responses[index] = Some(response);
+    }
+
+    /// The records of all chunks in one response, once all of them are completed.
+    pub(crate) fn concatenated(&self) -> Result<ConnectorResponse, ConnectorError> {
+        let responses = self
+            .responses
+            .iter()
+            .cloned()
+            .collect::<Option<Vec<ConnectorResponse>>>()
+            .ok_or_else(|| ConnectorError::LibraryError("Chunks are missing.".to_string()))?;
+        ConnectorResponse::concat_records(responses)
+    }
+}
@@ -0,0 +1,207 @@
+use crate::connector_error::ConnectorError;
+use crate::parameters::Parameters;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A single entry of the `ens_select` option.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Ens {
+    Member(u32),
+    MemberRange(u32, u32),
+    Median,
+    Mean,
+    Quantile(f64),
+}
+
+/// A column of an ensemble response, i.e. an `Ens` with member ranges expanded.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum EnsMember {
+    Member(u32),
+    Median,
+    Mean,
+    Quantile(f64),
+}
+
+pub type EnsSet = Vec<Ens>;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EnsSelect {
+    pub ens_values: EnsSet,
+}
+
+impl EnsSelect {
+    /// Query option key under which the selection is sent.
+    pub const KEY: &'static str = "ens_select";
+
+    /// Expands member ranges into one entry per response column, in request order.
+    pub fn members(&self) -> Vec<EnsMember> {
+        let mut members = vec![];
+        for ens in self.ens_values.iter() {
+            match *ens {
+                Ens::Member(n) => members.push(EnsMember::Member(n)),
+                Ens::MemberRange(from, to) => {
+                    members.extend((from..=to).map(EnsMember::Member));
+                }
+                Ens::Median => members.push(EnsMember::Median),
+                Ens::Mean => members.push(EnsMember::Mean),
+                Ens::Quantile(q) => members.push(EnsMember::Quantile(q)),
+            }
+        }
+        members
+    }
+
+    /// Response headers for `parameters`, one column per parameter and member.
+    pub fn headers(&self, parameters: &Parameters<'_>) -> Vec<String> {
+        let members = self.members();
+        let mut headers = vec![];
+        for p in parameters.p_values.iter() {
+            for member in members.iter() {
+                headers.push(format!("{}-{}", p, member));
+            }
+        }
+        headers
+    }
+}
+
+impl Display for Ens {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Ens::Member(n) => write!(f, "member:{}", n),
+            Ens::MemberRange(from, to) => write!(f, "member:{}-{}", from, to),
+            Ens::Median => write!(f, "median"),
+            Ens::Mean => write!(f, "mean"),
+            Ens::Quantile(q) => write!(f, "quantile{}", q),
+        }
+    }
+}
+
+impl Display for EnsMember {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnsMember::Member(n) => write!(f, "m{}", n),
+            EnsMember::Median => write!(f, "median"),
+            EnsMember::Mean => write!(f, "mean"),
+            EnsMember::Quantile(q) => write!(f, "quantile{}", q),
+        }
+    }
+}
+
+impl Display for EnsSelect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.ens_values
+                .iter()
+                .map(|ens| ens.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl FromStr for Ens {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConnectorError::InvalidQuery(format!("Invalid ens_select entry: {}", s));
+        match s {
+            "median" => Ok(Ens::Median),
+            "mean" => Ok(Ens::Mean),
+            _ => {
+                if let Some(q) = s.strip_prefix("quantile") {
+                    return q.parse().map(Ens::Quantile).map_err(|_| invalid());
+                }
+                let member = s.strip_prefix("member:").ok_or_else(invalid)?;
+                match member.split_once('-') {
+                    None => member.parse().map(Ens::Member).map_err(|_| invalid()),
+                    Some((from, to)) => {
+                        let from = from.parse().map_err(|_| invalid())?;
+                        let to = to.parse().map_err(|_| invalid())?;
+                        if from > to {
+                            return Err(invalid());
+                        }
+                        Ok(Ens::MemberRange(from, to))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for EnsSelect {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ens_values = s
+            .split(',')
+            .map(str::trim)
+            .map(Ens::from_str)
+            .collect::<Result<EnsSet, ConnectorError>>()?;
+        Ok(EnsSelect { ens_values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::ens_select::{Ens, EnsMember, EnsSelect, EnsSet};
+    use crate::parameters::{PSet, Parameters, P};
+    use std::iter::FromIterator;
+
+    #[tokio::test]
+    async fn render_and_expand() {
+        println!("\n##### render_and_expand:");
+
+        let ens_select = EnsSelect {
+            ens_values: EnsSet::from_iter([
+                Ens::MemberRange(1, 3),
+                Ens::Median,
+                Ens::Quantile(0.9),
+            ]),
+        };
+
+        println!(">>>>>>>>>> ens_select: {}", ens_select);
+        assert_eq!(ens_select.to_string(), "member:1-3,median,quantile0.9");
+
+        assert_eq!(
+            ens_select.members(),
+            vec![
+                EnsMember::Member(1),
+                EnsMember::Member(2),
+                EnsMember::Member(3),
+                EnsMember::Median,
+                EnsMember::Quantile(0.9)
+            ]
+        );
+
+        let parameters: Parameters = Parameters {
+            p_values: PSet::from_iter([P {
+                k: "t_2m",
+                v: Some("C"),
+                source: None,
+            }]),
+        };
+        let headers = ens_select.headers(&parameters);
+        println!(">>>>>>>>>> headers: {:?}", headers);
+        assert_eq!(headers[0], "t_2m:C-m1");
+        assert_eq!(headers[3], "t_2m:C-median");
+        assert_eq!(headers.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn parse_from_option_value() {
+        println!("\n##### parse_from_option_value:");
+
+        let ens_select: EnsSelect = "member:0,member:5-10,mean".parse().unwrap();
+        println!(">>>>>>>>>> ens_select: {:?}", ens_select);
+
+        assert_eq!(
+            ens_select.ens_values,
+            EnsSet::from_iter([Ens::Member(0), Ens::MemberRange(5, 10), Ens::Mean])
+        );
+        assert!("member:3-1".parse::<EnsSelect>().is_err());
+        assert!("members".parse::<EnsSelect>().is_err());
+    }
+}
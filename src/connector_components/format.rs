@@ -1,8 +1,22 @@
+use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
 #[allow(dead_code)]
-#[derive(Clone, Display, Debug)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Display, Eq, PartialEq, Serialize)]
 pub enum Format {
+    #[default]
     #[strum(serialize = "csv")]
     CSV,
+    #[strum(serialize = "xml")]
+    XML,
+    #[strum(serialize = "json")]
+    JSON,
+    #[strum(serialize = "bin")]
+    BIN,
+    #[strum(serialize = "png")]
+    PNG,
+    #[strum(serialize = "netcdf")]
+    NetCDF,
+    #[strum(serialize = "grads")]
+    Grads,
 }
@@ -1,22 +1,360 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use strum_macros::Display;
 
 pub type Coordinates<'a> = Vec<&'a str>;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Locations<'a> {
-    pub coordinates: Coordinates<'a>,
+/// A single point as `(lat, lon)`.
+pub type Coordinate = (f64, f64);
+
+/// A single point with validated latitude and longitude, for locations computed at runtime.
+/// Use `Locations::Coordinates` for identifiers like postal codes or station ids.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "PointFields")]
+pub struct Point {
+    lat: f64,
+    lon: f64,
+    precision: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct PointFields {
+    lat: f64,
+    lon: f64,
+    #[serde(default)]
+    precision: Option<usize>,
+}
+
+impl Point {
+    /// Fails unless `lat` is within -90..=90 and `lon` within -180..=180.
+    pub fn new(lat: f64, lon: f64) -> Result<Self, ConnectorError> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(ConnectorError::InvalidQuery(format!(
+                "Latitude {} is not within -90 and 90.",
+                lat
+            )));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(ConnectorError::InvalidQuery(format!(
+                "Longitude {} is not within -180 and 180.",
+                lon
+            )));
+        }
+        Ok(Point {
+            lat,
+            lon,
+            precision: None,
+        })
+    }
+
+    /// Writes the coordinates with `decimals` digits after the decimal point instead of as many
+    /// as needed.
+    pub fn with_precision(self, decimals: usize) -> Self {
+        Point {
+            precision: Some(decimals),
+            ..self
+        }
+    }
+
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    pub fn lon(&self) -> f64 {
+        self.lon
+    }
+}
+
+impl TryFrom<PointFields> for Point {
+    type Error = ConnectorError;
+
+    fn try_from(fields: PointFields) -> Result<Self, Self::Error> {
+        let point = Point::new(fields.lat, fields.lon)?;
+        Ok(Point {
+            precision: fields.precision,
+            ..point
+        })
+    }
+}
+
+impl TryFrom<Coordinate> for Point {
+    type Error = ConnectorError;
+
+    fn try_from((lat, lon): Coordinate) -> Result<Self, Self::Error> {
+        Point::new(lat, lon)
+    }
+}
+
+impl From<Point> for Coordinate {
+    fn from(point: Point) -> Self {
+        (point.lat, point.lon)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.precision {
+            Some(decimals) => write!(f, "{:.*},{:.*}", decimals, self.lat, decimals, self.lon),
+            None => write!(f, "{},{}", self.lat, self.lon),
+        }
+    }
+}
+
+/// Rectangular area sampled every `lat_step` and `lon_step` degrees, e.g. for grid queries.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BoundingBox {
+    pub north: f64,
+    pub west: f64,
+    pub south: f64,
+    pub east: f64,
+    pub lat_step: f64,
+    pub lon_step: f64,
+}
+
+impl BoundingBox {
+    /// Fails unless the corners are valid points, `north` is not below `south` and both steps
+    /// are positive.
+    pub fn validate(&self) -> Result<(), ConnectorError> {
+        Point::new(self.north, self.west)?;
+        Point::new(self.south, self.east)?;
+        if self.north < self.south {
+            return Err(ConnectorError::InvalidQuery(format!(
+                "North {} is below south {}.",
+                self.north, self.south
+            )));
+        }
+        if !(self.lat_step > 0.0 && self.lon_step > 0.0) {
+            return Err(ConnectorError::InvalidQuery(
+                "Bounding box steps must be positive.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Display for BoundingBox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{},{}_{},{}:{},{}",
+            self.north, self.west, self.south, self.east, self.lat_step, self.lon_step
+        )
+    }
+}
+
+/// `n_points` points evenly spaced from `start` to `end`, e.g. for cross sections.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Line {
+    pub start: Point,
+    pub end: Point,
+    pub n_points: u32,
+}
+
+impl Line {
+    /// Fails unless the line has at least two points.
+    pub fn validate(&self) -> Result<(), ConnectorError> {
+        if self.n_points < 2 {
+            return Err(ConnectorError::InvalidQuery(
+                "Line must have at least two points.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}_{}:{}", self.start, self.end, self.n_points)
+    }
+}
+
+/// Area keywords predefined by the Meteomatics API.
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+pub enum NamedArea {
+    #[strum(serialize = "world")]
+    World,
+    #[strum(serialize = "europe")]
+    Europe,
+    #[strum(serialize = "africa")]
+    Africa,
+    #[strum(serialize = "asia")]
+    Asia,
+    #[strum(serialize = "australia")]
+    Australia,
+    #[strum(serialize = "north-america")]
+    NorthAmerica,
+    #[strum(serialize = "south-america")]
+    SouthAmerica,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Locations<'a> {
+    /// Raw location strings, e.g. coordinates, postal codes or station ids.
+    Coordinates(#[serde(borrow)] Coordinates<'a>),
+    Point(Point),
+    /// Several points queried at once, answered with one record per point and valid date.
+    Points(Vec<Point>),
+    BoundingBox(BoundingBox),
+    Line(Line),
+    /// Predefined area sampled at `resolution`, either `lat_step,lon_step` or `<width>x<height>`.
+    Area {
+        area: NamedArea,
+        resolution: &'a str,
+    },
+}
+
+impl<'a> Locations<'a> {
+    /// The points of a `+`-separated point list, or `None` for areas, lines, postal codes and
+    /// other locations that are not plain coordinates.
+    pub fn points(&self) -> Option<Vec<Coordinate>> {
+        match self {
+            Locations::Coordinates(_) => self
+                .to_string()
+                .split('+')
+                .map(|point| {
+                    let (lat, lon) = point.split_once(',')?;
+                    Some((lat.trim().parse().ok()?, lon.trim().parse().ok()?))
+                })
+                .collect(),
+            Locations::Point(point) => Some(vec![(*point).into()]),
+            Locations::Points(points) => Some(points.iter().map(|&point| point.into()).collect()),
+            Locations::BoundingBox(_) | Locations::Line(_) | Locations::Area { .. } => None,
+        }
+    }
+
+    /// The number of locations requested, or `None` if it cannot be counted, e.g. for named
+    /// areas or grids written as raw strings.
+    pub fn num_locations(&self) -> Option<u64> {
+        match self {
+            Locations::Coordinates(_) => {
+                let locations = self.to_string();
+                // A `:` marks the resolution of a grid or line.
+                (!locations.contains(':')).then(|| locations.split('+').count() as u64)
+            }
+            Locations::Point(_) => Some(1),
+            Locations::Points(points) => Some(points.len() as u64),
+            Locations::BoundingBox(bounding_box) => {
+                bounding_box.validate().ok()?;
+                // Tolerates steps that do not divide the extent exactly in floating point.
+                let count = |extent: f64, step: f64| (extent / step + 1e-9).floor() as u64 + 1;
+                Some(
+                    count(
+                        bounding_box.north - bounding_box.south,
+                        bounding_box.lat_step,
+                    ) * count(bounding_box.east - bounding_box.west, bounding_box.lon_step),
+                )
+            }
+            Locations::Line(line) => Some(u64::from(line.n_points)),
+            Locations::Area { .. } => None,
+        }
+    }
+
+    /// Whether these locations describe an area sampled on a grid: a bounding box, a named area
+    /// or a raw area with a `lat_step,lon_step` or `<width>x<height>` resolution, unlike the
+    /// number of points that follows the `:` of a line.
+    pub fn is_grid(&self) -> bool {
+        match self {
+            Locations::Coordinates(_) => match self.to_string().split_once(':') {
+                Some((area, resolution)) => {
+                    !area.contains('+') && (resolution.contains(',') || resolution.contains('x'))
+                }
+                None => false,
+            },
+            Locations::BoundingBox(_) | Locations::Area { .. } => true,
+            Locations::Point(_) | Locations::Points(_) | Locations::Line(_) => false,
+        }
+    }
+
+    /// Checks bounding boxes and lines, which unlike points can be built with invalid fields.
+    pub fn validate(&self) -> Result<(), ConnectorError> {
+        match self {
+            Locations::BoundingBox(bounding_box) => bounding_box.validate(),
+            Locations::Line(line) => line.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Owned counterpart of `Locations`, which is `'static` and can be moved into spawned tasks.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum OwnedLocations {
+    Coordinates(Vec<String>),
+    Point(Point),
+    Points(Vec<Point>),
+    BoundingBox(BoundingBox),
+    Line(Line),
+    Area { area: NamedArea, resolution: String },
+}
+
+impl OwnedLocations {
+    /// Borrows the locations for a query.
+    pub fn as_locations(&self) -> Locations<'_> {
+        match self {
+            OwnedLocations::Coordinates(coordinates) => {
+                Locations::Coordinates(coordinates.iter().map(String::as_str).collect())
+            }
+            OwnedLocations::Point(point) => Locations::Point(*point),
+            OwnedLocations::Points(points) => Locations::Points(points.clone()),
+            OwnedLocations::BoundingBox(bounding_box) => Locations::BoundingBox(*bounding_box),
+            OwnedLocations::Line(line) => Locations::Line(*line),
+            OwnedLocations::Area { area, resolution } => Locations::Area {
+                area: *area,
+                resolution,
+            },
+        }
+    }
+}
+
+impl<'a> From<&Locations<'a>> for OwnedLocations {
+    fn from(locations: &Locations<'a>) -> Self {
+        match locations {
+            Locations::Coordinates(coordinates) => {
+                OwnedLocations::Coordinates(coordinates.iter().map(|c| c.to_string()).collect())
+            }
+            Locations::Point(point) => OwnedLocations::Point(*point),
+            Locations::Points(points) => OwnedLocations::Points(points.clone()),
+            Locations::BoundingBox(bounding_box) => OwnedLocations::BoundingBox(*bounding_box),
+            Locations::Line(line) => OwnedLocations::Line(*line),
+            Locations::Area { area, resolution } => OwnedLocations::Area {
+                area: *area,
+                resolution: resolution.to_string(),
+            },
+        }
+    }
+}
+
+impl Display for OwnedLocations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_locations())
+    }
 }
 
 impl<'a> Display for Locations<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.coordinates.to_vec().join(","))
+        match self {
+            Locations::Coordinates(coordinates) => write!(f, "{}", coordinates.to_vec().join(",")),
+            Locations::Point(point) => write!(f, "{}", point),
+            Locations::Points(points) => write!(
+                f,
+                "{}",
+                points
+                    .iter()
+                    .map(|point| point.to_string())
+                    .collect::<Vec<String>>()
+                    .join("+")
+            ),
+            Locations::BoundingBox(bounding_box) => write!(f, "{}", bounding_box),
+            Locations::Line(line) => write!(f, "{}", line),
+            Locations::Area { area, resolution } => write!(f, "{}:{}", area, resolution),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::locations::{Coordinates, Locations};
+    use crate::locations::{BoundingBox, Coordinates, Line, Locations, NamedArea, Point};
 
     #[tokio::test]
     async fn with_some_values() {
@@ -24,28 +362,33 @@ mod tests {
 
         // Single point
         let coordinates = Coordinates::from(["47.419708", "9.358478"]);
-        let locations: Locations = Locations { coordinates };
+        let locations: Locations = Locations::Coordinates(coordinates);
 
         println!(">>>>>>>>>> single_point_loc: {}", locations);
         assert_eq!(locations.to_string(), "47.419708,9.358478");
 
         // Point list
-        let locations: Locations = Locations {
-            coordinates: Coordinates::from(["47.41", "9.35+47.51", "8.74+47.13", "8.22"]),
-        };
+        let locations: Locations = Locations::Coordinates(Coordinates::from([
+            "47.41",
+            "9.35+47.51",
+            "8.74+47.13",
+            "8.22",
+        ]));
 
         println!(">>>>>>>>>> point_list_loc: {}", locations);
         assert_eq!(locations.to_string(), "47.41,9.35+47.51,8.74+47.13,8.22");
+        assert_eq!(
+            locations.points(),
+            Some(vec![(47.41, 9.35), (47.51, 8.74), (47.13, 8.22)])
+        );
 
         // Postal codes
-        let locations: Locations = Locations {
-            coordinates: Coordinates::from([
-                "postal_CH9014",
-                "postal_CH9000",
-                "postal_US10001",
-                "postal_GBW2",
-            ]),
-        };
+        let locations: Locations = Locations::Coordinates(Coordinates::from([
+            "postal_CH9014",
+            "postal_CH9000",
+            "postal_US10001",
+            "postal_GBW2",
+        ]));
 
         println!(">>>>>>>>>> postal_codes_loc: {}", locations);
 
@@ -55,12 +398,153 @@ mod tests {
         );
 
         // Line
-        let locations: Locations = Locations {
-            coordinates: Coordinates::from(["50", "10_50", "20:100"]),
-        };
+        let locations: Locations =
+            Locations::Coordinates(Coordinates::from(["50", "10_50", "20:100"]));
 
         println!(">>>>>>>>>> line_loc: {}", locations);
 
         assert_eq!(locations.to_string(), "50,10_50,20:100");
+        assert_eq!(locations.points(), None);
+    }
+
+    #[tokio::test]
+    async fn with_named_area() {
+        println!("\n##### with_named_area:");
+
+        let locations: Locations = Locations::Area {
+            area: NamedArea::Europe,
+            resolution: "0.1,0.1",
+        };
+
+        println!(">>>>>>>>>> europe_loc: {}", locations);
+        assert_eq!(locations.to_string(), "europe:0.1,0.1");
+
+        let locations: Locations = Locations::Area {
+            area: NamedArea::World,
+            resolution: "600x400",
+        };
+
+        println!(">>>>>>>>>> world_loc: {}", locations);
+        assert_eq!(locations.to_string(), "world:600x400");
+    }
+
+    #[tokio::test]
+    async fn with_validated_point() {
+        println!("\n##### with_validated_point:");
+
+        let point = Point::new(47.419708, 9.358478).unwrap();
+        let locations: Locations = Locations::Point(point);
+
+        println!(">>>>>>>>>> point_loc: {}", locations);
+        assert_eq!(locations.to_string(), "47.419708,9.358478");
+        assert_eq!(locations.points(), Some(vec![(47.419708, 9.358478)]));
+        assert_eq!(
+            Locations::Point(point.with_precision(2)).to_string(),
+            "47.42,9.36"
+        );
+        assert_eq!(Point::new(-90.0, 180.0).unwrap().to_string(), "-90,180");
+
+        assert!(Point::new(90.5, 0.0).is_err());
+        assert!(Point::new(0.0, -180.5).is_err());
+        assert!(Point::new(f64::NAN, 0.0).is_err());
+
+        let json = serde_json::to_string(&point).unwrap();
+        println!(">>>>>>>>>> json: {}", json);
+        assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), point);
+        assert!(serde_json::from_str::<Point>(r#"{"lat":91.0,"lon":0.0}"#).is_err());
+
+        let locations: Locations = Locations::Points(vec![
+            point.with_precision(2),
+            Point::new(47.51, 8.74).unwrap(),
+        ]);
+        println!(">>>>>>>>>> points_loc: {}", locations);
+        assert_eq!(locations.to_string(), "47.42,9.36+47.51,8.74");
+        assert_eq!(
+            locations.points(),
+            Some(vec![(47.419708, 9.358478), (47.51, 8.74)])
+        );
+    }
+
+    #[tokio::test]
+    async fn with_bounding_box_and_line() {
+        println!("\n##### with_bounding_box_and_line:");
+
+        let bounding_box = BoundingBox {
+            north: 48.0,
+            west: 5.5,
+            south: 45.5,
+            east: 10.5,
+            lat_step: 0.1,
+            lon_step: 0.25,
+        };
+        let locations: Locations = Locations::BoundingBox(bounding_box);
+
+        println!(">>>>>>>>>> bounding_box_loc: {}", locations);
+        assert_eq!(locations.to_string(), "48,5.5_45.5,10.5:0.1,0.25");
+        assert!(locations.validate().is_ok());
+        assert_eq!(locations.points(), None);
+        assert!(Locations::BoundingBox(BoundingBox {
+            north: 45.5,
+            south: 48.0,
+            ..bounding_box
+        })
+        .validate()
+        .is_err());
+        assert!(Locations::BoundingBox(BoundingBox {
+            lat_step: 0.0,
+            ..bounding_box
+        })
+        .validate()
+        .is_err());
+
+        let line = Line {
+            start: Point::new(50.0, 10.0).unwrap(),
+            end: Point::new(50.0, 20.0).unwrap(),
+            n_points: 100,
+        };
+        let locations: Locations = Locations::Line(line);
+
+        println!(">>>>>>>>>> line_loc: {}", locations);
+        assert_eq!(locations.to_string(), "50,10_50,20:100");
+        assert!(locations.validate().is_ok());
+        assert!(Locations::Line(Line {
+            n_points: 1,
+            ..line
+        })
+        .validate()
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn recognize_grid_areas() {
+        println!("\n##### recognize_grid_areas:");
+
+        let grids = [
+            Locations::Coordinates(Coordinates::from(["47.5,9.3_47.4,9.4:0.1,0.1"])),
+            Locations::Coordinates(Coordinates::from(["europe:400x300"])),
+            Locations::Area {
+                area: NamedArea::Europe,
+                resolution: "0.5,0.5",
+            },
+        ];
+        for locations in grids {
+            println!(">>>>>>>>>> grid: {}", locations);
+            assert!(locations.is_grid());
+        }
+
+        let others = [
+            Locations::Coordinates(Coordinates::from(["47.41", "9.35"])),
+            Locations::Coordinates(Coordinates::from(["50,10_50,20:100"])),
+            Locations::Point(Point::new(47.41, 9.35).unwrap()),
+            Locations::Line(Line {
+                start: Point::new(50.0, 10.0).unwrap(),
+                end: Point::new(50.0, 20.0).unwrap(),
+                n_points: 100,
+            }),
+        ];
+        for locations in others {
+            println!(">>>>>>>>>> not a grid: {}", locations);
+            assert!(!locations.is_grid());
+        }
     }
 }
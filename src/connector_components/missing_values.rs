@@ -0,0 +1,75 @@
+use crate::connector_response::RecordValue;
+use serde::{Deserialize, Serialize};
+
+/// Sentinel values the API returns for data that is not available.
+pub const MISSING_VALUE_SENTINELS: [f64; 3] = [-666.0, -777.0, -999.0];
+
+/// How sentinel values and empty cells are represented in a parsed response.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MissingValuePolicy {
+    /// Pass sentinels through as numbers and empty cells as text.
+    Keep,
+    /// Replace them with `f64::NAN`.
+    #[default]
+    NaN,
+    /// Replace them with `RecordValue::Missing`.
+    Missing,
+}
+
+impl MissingValuePolicy {
+    pub fn apply(&self, value: RecordValue) -> RecordValue {
+        if *self == MissingValuePolicy::Keep || !is_missing(&value) {
+            return value;
+        }
+        match self {
+            MissingValuePolicy::NaN => RecordValue::Number(f64::NAN),
+            _ => RecordValue::Missing,
+        }
+    }
+}
+
+fn is_missing(value: &RecordValue) -> bool {
+    match value {
+        RecordValue::Number(number) => MISSING_VALUE_SENTINELS.contains(number),
+        RecordValue::Text(text) => text.trim().is_empty(),
+        RecordValue::Missing => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::RecordValue;
+    use crate::missing_values::MissingValuePolicy;
+
+    #[tokio::test]
+    async fn apply_policies() {
+        println!("\n##### apply_policies:");
+
+        let sentinel = RecordValue::parse("-666");
+        let empty = RecordValue::parse("");
+        let valid = RecordValue::parse("3.4");
+
+        assert_eq!(MissingValuePolicy::Keep.apply(sentinel.clone()), sentinel);
+        assert_eq!(MissingValuePolicy::Keep.apply(empty.clone()), empty);
+
+        let nan = MissingValuePolicy::NaN.apply(sentinel.clone());
+        println!(">>>>>>>>>> nan: {:?}", nan);
+        assert!(nan.as_f64().unwrap().is_nan());
+        assert!(MissingValuePolicy::NaN
+            .apply(empty.clone())
+            .as_f64()
+            .unwrap()
+            .is_nan());
+
+        assert_eq!(
+            MissingValuePolicy::Missing.apply(sentinel),
+            RecordValue::Missing
+        );
+        assert_eq!(
+            MissingValuePolicy::Missing.apply(empty),
+            RecordValue::Missing
+        );
+        assert_eq!(MissingValuePolicy::Missing.apply(valid.clone()), valid);
+    }
+}
@@ -1,5 +1,15 @@
+pub mod chunked_query;
+pub mod ens_select;
 pub mod format;
 pub mod locations;
+pub mod missing_values;
 pub mod optionals;
+pub mod parameter_template;
 pub mod parameters;
+pub mod query_spec;
+pub mod sites;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod station_catalog;
+pub mod time_series_query;
+pub mod units;
 pub mod valid_date_time;
@@ -1,6 +1,9 @@
+use crate::connector_error::ConnectorError;
+use crate::ens_select::EnsSelect;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Opt<'a> {
     pub k: &'a str,
     pub v: &'a str,
@@ -8,9 +11,188 @@ pub struct Opt<'a> {
 
 pub type OptSet<'a> = Vec<Opt<'a>>;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Optionals<'a> {
+    #[serde(borrow)]
     pub opt_values: OptSet<'a>,
+    /// Typed options, sent after `opt_values`.
+    #[serde(default)]
+    pub query_options: Vec<QueryOption>,
+}
+
+/// Forecast model or data source.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Model {
+    /// The Meteomatics blend of the best available models.
+    Mix,
+    EcmwfIfs,
+    EcmwfEns,
+    NcepGfs,
+    DwdIcon,
+    UkmoUm,
+    /// Any other model by its API name.
+    Other(String),
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Model::Mix => "mix",
+            Model::EcmwfIfs => "ecmwf-ifs",
+            Model::EcmwfEns => "ecmwf-ens",
+            Model::NcepGfs => "ncep-gfs",
+            Model::DwdIcon => "dwd-icon",
+            Model::UkmoUm => "ukmo-um",
+            Model::Other(name) => name,
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Temporal interpolation between the time steps of a model.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum InterpolationMode {
+    Best,
+    Disabled,
+}
+
+/// Restricts grid values to land or sea.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Mask {
+    Land,
+    Sea,
+}
+
+/// A query option with a checked key and value, unlike `Opt`, whose misspelled keys the API
+/// silently ignores.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum QueryOption {
+    Source(Model),
+    Calibrated(bool),
+    EnsSelect(EnsSelect),
+    InterpolationMode(InterpolationMode),
+    Mask(Mask),
+}
+
+impl QueryOption {
+    pub fn key(&self) -> &'static str {
+        match self {
+            QueryOption::Source(_) => "source",
+            QueryOption::Calibrated(_) => "calibrated",
+            QueryOption::EnsSelect(_) => EnsSelect::KEY,
+            QueryOption::InterpolationMode(_) => "temporal_interpolation",
+            QueryOption::Mask(_) => "mask",
+        }
+    }
+
+    pub fn value(&self) -> String {
+        match self {
+            QueryOption::Source(model) => model.to_string(),
+            QueryOption::Calibrated(calibrated) => calibrated.to_string(),
+            QueryOption::EnsSelect(ens_select) => ens_select.to_string(),
+            QueryOption::InterpolationMode(InterpolationMode::Best) => "best".to_string(),
+            QueryOption::InterpolationMode(InterpolationMode::Disabled) => "none".to_string(),
+            QueryOption::Mask(Mask::Land) => "land".to_string(),
+            QueryOption::Mask(Mask::Sea) => "sea".to_string(),
+        }
+    }
+}
+
+impl Display for QueryOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key(), self.value())
+    }
+}
+
+impl<'a> Optionals<'a> {
+    /// Optionals of typed options only.
+    pub fn from_options(query_options: Vec<QueryOption>) -> Self {
+        Optionals {
+            opt_values: vec![],
+            query_options,
+        }
+    }
+
+    /// Keys and values of `opt_values` followed by those of `query_options`, unencoded.
+    pub fn pairs(&self) -> Vec<(String, String)> {
+        self.opt_values
+            .iter()
+            .map(|opt| (opt.k.to_string(), opt.v.to_string()))
+            .chain(
+                self.query_options
+                    .iter()
+                    .map(|option| (option.key().to_string(), option.value())),
+            )
+            .collect()
+    }
+
+    /// The `ens_select` option, typed or parsed from `opt_values`.
+    pub(crate) fn ens_select(&self) -> Result<Option<EnsSelect>, ConnectorError> {
+        for query_option in self.query_options.iter() {
+            if let QueryOption::EnsSelect(ens_select) = query_option {
+                return Ok(Some(ens_select.clone()));
+            }
+        }
+        self.opt_values
+            .iter()
+            .find(|opt| opt.k == EnsSelect::KEY)
+            .map(|opt| opt.v.parse::<EnsSelect>())
+            .transpose()
+    }
+}
+
+/// Owned counterpart of `Opt`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OwnedOpt {
+    pub k: String,
+    pub v: String,
+}
+
+/// Owned counterpart of `Optionals`, which is `'static` and can be moved into spawned tasks.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct OwnedOptionals {
+    pub opt_values: Vec<OwnedOpt>,
+    #[serde(default)]
+    pub query_options: Vec<QueryOption>,
+}
+
+impl OwnedOptionals {
+    /// Borrows the optionals for a query.
+    pub fn as_optionals(&self) -> Optionals<'_> {
+        Optionals {
+            opt_values: self
+                .opt_values
+                .iter()
+                .map(|opt| Opt {
+                    k: &opt.k,
+                    v: &opt.v,
+                })
+                .collect(),
+            query_options: self.query_options.clone(),
+        }
+    }
+}
+
+impl<'a> From<&Optionals<'a>> for OwnedOptionals {
+    fn from(optionals: &Optionals<'a>) -> Self {
+        OwnedOptionals {
+            opt_values: optionals
+                .opt_values
+                .iter()
+                .map(|opt| OwnedOpt {
+                    k: opt.k.to_string(),
+                    v: opt.v.to_string(),
+                })
+                .collect(),
+            query_options: optionals.query_options.clone(),
+        }
+    }
+}
+
+impl Display for OwnedOptionals {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_optionals())
+    }
 }
 
 impl<'a> Display for Opt<'a> {
@@ -27,6 +209,7 @@ impl<'a> Display for Optionals<'a> {
             self.opt_values
                 .iter()
                 .map(|opt| opt.to_string())
+                .chain(self.query_options.iter().map(|option| option.to_string()))
                 .collect::<Vec<String>>()
                 .join("&")
         )
@@ -36,7 +219,8 @@ impl<'a> Display for Optionals<'a> {
 #[cfg(test)]
 mod tests {
 
-    use crate::optionals::{Opt, OptSet, Optionals};
+    use crate::ens_select::EnsSelect;
+    use crate::optionals::{Mask, Model, Opt, OptSet, Optionals, QueryOption};
     use std::iter::FromIterator;
 
     #[tokio::test]
@@ -54,6 +238,7 @@ mod tests {
                     v: "true",
                 },
             ]),
+            query_options: vec![],
         };
 
         println!(">>>>>>>>>> optionals: {}", optionals);
@@ -68,4 +253,36 @@ mod tests {
             }])
         );
     }
+
+    #[tokio::test]
+    async fn with_query_options() {
+        println!("\n##### with_query_options:");
+
+        let optionals = Optionals::from_options(vec![
+            QueryOption::Source(Model::EcmwfIfs),
+            QueryOption::Calibrated(true),
+            QueryOption::Mask(Mask::Land),
+            QueryOption::EnsSelect("member:1-3,median".parse().unwrap()),
+        ]);
+
+        println!(">>>>>>>>>> optionals: {}", optionals);
+        assert_eq!(
+            optionals.to_string(),
+            "source=ecmwf-ifs&calibrated=true&mask=land&ens_select=member:1-3,median"
+        );
+        assert_eq!(
+            optionals.ens_select().unwrap(),
+            Some("member:1-3,median".parse::<EnsSelect>().unwrap())
+        );
+
+        let mixed = Optionals {
+            opt_values: vec![Opt {
+                k: "model",
+                v: "mix",
+            }],
+            query_options: vec![QueryOption::Source(Model::Other("my-model".to_string()))],
+        };
+        assert_eq!(mixed.to_string(), "model=mix&source=my-model");
+        assert_eq!(mixed.ens_select().unwrap(), None);
+    }
 }
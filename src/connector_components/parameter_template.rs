@@ -0,0 +1,187 @@
+use crate::parameters::{OwnedP, OwnedParameters};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Vertical level of a parameter, e.g. the `2m` in `t_2m:C`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Level {
+    /// Height above ground in meters.
+    Meters(u32),
+    /// Pressure level in hectopascals.
+    Hectopascals(u32),
+    /// Any other level as written in the parameter name, e.g. `0cm` or `mean`.
+    Custom(String),
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Meters(meters) => write!(f, "{}m", meters),
+            Level::Hectopascals(hectopascals) => write!(f, "{}hPa", hectopascals),
+            Level::Custom(level) => write!(f, "{}", level),
+        }
+    }
+}
+
+/// Accumulation or aggregation interval of a parameter, e.g. the `1h` in `precip_1h:mm`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Interval {
+    Minutes(u32),
+    Hours(u32),
+    Days(u32),
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interval::Minutes(minutes) => write!(f, "{}min", minutes),
+            Interval::Hours(hours) => write!(f, "{}h", hours),
+            Interval::Days(days) => write!(f, "{}d", days),
+        }
+    }
+}
+
+/// Family of parameters sharing a name and unit, expanded into one parameter per combination of
+/// level and interval, e.g. `t_2m:C`, `t_10m:C` and `t_100m:C`.
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[builder(build_fn(validate = "Self::validate"))]
+pub struct ParameterTemplate {
+    #[builder(setter(into))]
+    pub name: String,
+
+    #[builder(default)]
+    pub levels: Vec<Level>,
+
+    #[builder(default)]
+    pub intervals: Vec<Interval>,
+
+    #[builder(setter(into, strip_option), default)]
+    pub unit: Option<String>,
+
+    /// Model of every parameter, see `P::source`.
+    #[builder(setter(into, strip_option), default)]
+    pub source: Option<String>,
+}
+
+impl ParameterTemplateBuilder {
+    /// Rejects an empty name, which would produce parameters like `_2m:C`.
+    fn validate(&self) -> Result<(), String> {
+        match &self.name {
+            Some(name) if name.is_empty() => Err("Parameter name must not be empty.".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn level(&mut self, level: Level) -> &mut Self {
+        self.levels.get_or_insert_with(Vec::new).push(level);
+        self
+    }
+
+    pub fn interval(&mut self, interval: Interval) -> &mut Self {
+        self.intervals.get_or_insert_with(Vec::new).push(interval);
+        self
+    }
+}
+
+impl ParameterTemplate {
+    /// The parameters of every level and interval, levels varying slowest.
+    pub fn expand(&self) -> OwnedParameters {
+        let levels: Vec<Option<&Level>> = if self.levels.is_empty() {
+            vec![None]
+        } else {
+            self.levels.iter().map(Some).collect()
+        };
+        let intervals: Vec<Option<&Interval>> = if self.intervals.is_empty() {
+            vec![None]
+        } else {
+            self.intervals.iter().map(Some).collect()
+        };
+
+        let mut p_values = vec![];
+        for level in levels.iter() {
+            for interval in intervals.iter() {
+                let mut k = self.name.clone();
+                if let Some(level) = level {
+                    k.push_str(&format!("_{}", level));
+                }
+                if let Some(interval) = interval {
+                    k.push_str(&format!("_{}", interval));
+                }
+                p_values.push(OwnedP {
+                    k,
+                    v: self.unit.clone(),
+                    source: self.source.clone(),
+                });
+            }
+        }
+        OwnedParameters { p_values }
+    }
+
+    /// The parameters of all `templates`, in order.
+    pub fn expand_all(templates: &[ParameterTemplate]) -> OwnedParameters {
+        OwnedParameters {
+            p_values: templates
+                .iter()
+                .flat_map(|template| template.expand().p_values)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::parameter_template::{Interval, Level, ParameterTemplate, ParameterTemplateBuilder};
+
+    #[tokio::test]
+    async fn expand_levels_and_intervals() {
+        println!("\n##### expand_levels_and_intervals:");
+
+        let temperature = ParameterTemplateBuilder::default()
+            .name("t")
+            .levels(vec![
+                Level::Meters(2),
+                Level::Meters(10),
+                Level::Meters(100),
+            ])
+            .unit("C")
+            .build()
+            .unwrap();
+        let precipitation = ParameterTemplateBuilder::default()
+            .name("precip")
+            .interval(Interval::Hours(1))
+            .interval(Interval::Hours(24))
+            .unit("mm")
+            .build()
+            .unwrap();
+        let wind_gusts = ParameterTemplateBuilder::default()
+            .name("wind_gusts")
+            .level(Level::Meters(10))
+            .intervals(vec![Interval::Minutes(10), Interval::Hours(1)])
+            .unit("ms")
+            .build()
+            .unwrap();
+        let geopotential = ParameterTemplateBuilder::default()
+            .name("geopotential_height")
+            .levels(vec![Level::Hectopascals(850), Level::Hectopascals(500)])
+            .build()
+            .unwrap();
+
+        let parameters =
+            ParameterTemplate::expand_all(&[temperature, precipitation, wind_gusts, geopotential]);
+        println!(">>>>>>>>>> parameters: {}", parameters);
+
+        assert_eq!(
+            parameters.to_string(),
+            "t_2m:C,t_10m:C,t_100m:C,precip_1h:mm,precip_24h:mm,wind_gusts_10m_10min:ms,\
+             wind_gusts_10m_1h:ms,geopotential_height_850hPa,geopotential_height_500hPa"
+        );
+        assert_eq!(parameters.as_parameters().p_values.len(), 9);
+
+        assert!(ParameterTemplateBuilder::default()
+            .name("")
+            .build()
+            .is_err());
+        assert!(ParameterTemplateBuilder::default().build().is_err());
+    }
+}
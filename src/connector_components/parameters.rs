@@ -1,28 +1,275 @@
+use crate::connector_error::ConnectorError;
+use crate::units::Unit;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct P<'a> {
     pub k: &'a str,
+    #[serde(borrow)]
     pub v: Option<&'a str>,
+    /// Set with `with_source` and read with `source`.
+    #[serde(borrow, default)]
+    pub(crate) source: Option<&'a str>,
 }
 
 pub type PSet<'a> = Vec<P<'a>>;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Parameters<'a> {
+    #[serde(borrow)]
     pub p_values: PSet<'a>,
 }
 
+/// Names of common parameter families, e.g. `t` for `t_2m`, `t_850hPa` or `t_max_2m_24h`.
+pub const KNOWN_PARAMETERS: &str = include_str!("known_parameters.txt");
+
+/// How parameters are checked before a request is sent.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ParameterValidation {
+    /// Send parameters unchecked.
+    Off,
+    /// Reject parameters that are not syntactically valid.
+    #[default]
+    Syntax,
+    /// Also reject parameters outside the families of `KNOWN_PARAMETERS`.
+    Known,
+}
+
+impl ParameterValidation {
+    pub fn check(&self, p_values: &[P<'_>]) -> Result<(), ConnectorError> {
+        if *self == ParameterValidation::Off {
+            return Ok(());
+        }
+        if p_values.is_empty() {
+            return Err(ConnectorError::InvalidQuery(
+                "At least one parameter is required.".to_string(),
+            ));
+        }
+        for p in p_values.iter() {
+            p.validate()?;
+            if *self == ParameterValidation::Known && !p.is_known() {
+                return Err(ConnectorError::InvalidQuery(format!(
+                    "Unknown parameter `{}`.",
+                    p
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> P<'a> {
+    /// Checks the syntax of the parameter: a name of letters, digits, `_`, `-` and `.` not
+    /// starting or ending with `_`, an optional unit of letters and digits, and an optional
+    /// source of letters, digits, `_`, `-` and `.` following the unit.
+    pub fn validate(&self) -> Result<(), ConnectorError> {
+        let invalid = |reason: &str| {
+            Err(ConnectorError::InvalidQuery(format!(
+                "Invalid parameter `{}`: {}",
+                self, reason
+            )))
+        };
+        if self.k.is_empty() {
+            return invalid("name is empty.");
+        }
+        if !self
+            .k
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        {
+            return invalid("name may only contain letters, digits, `_`, `-` and `.`.");
+        }
+        if self.k.starts_with('_') || self.k.ends_with('_') {
+            return invalid("name must not start or end with `_`.");
+        }
+        match self.v {
+            Some("") => return invalid("unit is empty."),
+            Some(v) if !v.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                return invalid("unit may only contain letters and digits.")
+            }
+            _ => {}
+        }
+        match self.source {
+            Some(_) if self.v.is_none() => invalid("source requires a unit."),
+            Some("") => invalid("source is empty."),
+            Some(source)
+                if !source
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) =>
+            {
+                invalid("source may only contain letters, digits, `_`, `-` and `.`.")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the name is one of `KNOWN_PARAMETERS` or starts with one followed by `_`.
+    pub fn is_known(&self) -> bool {
+        KNOWN_PARAMETERS.lines().any(|family| {
+            self.k == family
+                || self
+                    .k
+                    .strip_prefix(family)
+                    .is_some_and(|rest| rest.starts_with('_'))
+        })
+    }
+
+    /// The parameter `k` in the unit `v`, e.g. `P::new("t_2m", Some("C"))` for `t_2m:C`.
+    pub fn new(k: &'a str, v: Option<&'a str>) -> Self {
+        P { k, v, source: None }
+    }
+
+    /// The parameter `k` in `unit`, e.g. `P::with_unit("t_2m", Unit::Celsius)` for `t_2m:C`.
+    pub fn with_unit(k: &'a str, unit: Unit) -> Self {
+        P {
+            k,
+            v: Some(unit.as_str()),
+            source: None,
+        }
+    }
+
+    /// The parameter taken from the model `source`, e.g. `t_2m:C:ecmwf-ifs`.
+    pub fn with_source(self, source: &'a str) -> Self {
+        P {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    /// Model the parameter is taken from, e.g. `ecmwf-ifs` in `t_2m:C:ecmwf-ifs`, overriding
+    /// the `model` optional for this parameter. Requires a unit.
+    pub fn source(&self) -> Option<&'a str> {
+        self.source
+    }
+}
+
+/// Owned counterpart of `P`, for parameters built from runtime data.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OwnedP {
+    pub k: String,
+    pub v: Option<String>,
+    /// See `P::source`.
+    #[serde(default)]
+    pub(crate) source: Option<String>,
+}
+
+impl OwnedP {
+    pub fn new(k: impl Into<String>, v: Option<String>) -> Self {
+        OwnedP {
+            k: k.into(),
+            v,
+            source: None,
+        }
+    }
+
+    /// The parameter taken from the model `source`, see `P::with_source`.
+    pub fn with_source(self, source: impl Into<String>) -> Self {
+        OwnedP {
+            source: Some(source.into()),
+            ..self
+        }
+    }
+
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+}
+
+/// Owned counterpart of `Parameters`, which is `'static` and can be moved into spawned tasks.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct OwnedParameters {
+    pub p_values: Vec<OwnedP>,
+}
+
+impl OwnedParameters {
+    /// Borrows the parameters for a query.
+    pub fn as_parameters(&self) -> Parameters<'_> {
+        Parameters {
+            p_values: self
+                .p_values
+                .iter()
+                .map(|p| P {
+                    k: &p.k,
+                    v: p.v.as_deref(),
+                    source: p.source.as_deref(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<&Parameters<'a>> for OwnedParameters {
+    fn from(parameters: &Parameters<'a>) -> Self {
+        OwnedParameters {
+            p_values: parameters
+                .p_values
+                .iter()
+                .map(|p| OwnedP {
+                    k: p.k.to_string(),
+                    v: p.v.map(str::to_string),
+                    source: p.source.map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Display for OwnedParameters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_parameters())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for P<'a> {
+    type Error = ConnectorError;
+
+    /// Parses and validates a single parameter, e.g. `t_2m:C` or `t_2m:C:ecmwf-ifs`.
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let mut parts = s.trim().splitn(3, ':');
+        let p = P {
+            k: parts.next().unwrap_or_default(),
+            v: parts.next(),
+            source: parts.next(),
+        };
+        p.validate()?;
+        Ok(p)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Parameters<'a> {
+    type Error = ConnectorError;
+
+    /// Parses and validates a comma-separated parameter list, e.g. `t_2m:C,precip_1h:mm`.
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let p_values = s
+            .split(',')
+            .map(P::try_from)
+            .collect::<Result<PSet<'a>, ConnectorError>>()?;
+        ParameterValidation::Syntax.check(&p_values)?;
+        Ok(Parameters { p_values })
+    }
+}
+
+impl FromStr for OwnedParameters {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(OwnedParameters::from(&Parameters::try_from(s)?))
+    }
+}
+
 impl<'a> Display for P<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self.v {
-            None => write!(f, "{}", self.k),
-            Some(_) => write!(
-                f,
-                "{}",
-                self.k.to_owned() + ":" + &*self.v.unwrap().to_owned()
-            ),
+        write!(f, "{}", self.k)?;
+        if let Some(v) = self.v {
+            write!(f, ":{}", v)?;
         }
+        if let Some(source) = self.source {
+            write!(f, ":{}", source)?;
+        }
+        Ok(())
     }
 }
 
@@ -43,7 +290,8 @@ impl<'a> Display for Parameters<'a> {
 #[cfg(test)]
 mod tests {
 
-    use crate::parameters::{PSet, Parameters, P};
+    use crate::parameters::{OwnedP, OwnedParameters, PSet, ParameterValidation, Parameters, P};
+    use crate::units::Unit;
     use std::iter::FromIterator;
 
     #[tokio::test]
@@ -55,10 +303,12 @@ mod tests {
                 P {
                     k: "t_2m",
                     v: Some("C"),
+                    source: None,
                 },
                 P {
                     k: "precip_1h",
                     v: Some("mm"),
+                    source: None,
                 },
             ]),
         };
@@ -71,7 +321,8 @@ mod tests {
             params.p_values,
             PSet::from_iter([P {
                 k: "t_2m",
-                v: Some("C")
+                v: Some("C"),
+                source: None
             }])
         );
     }
@@ -84,10 +335,12 @@ mod tests {
         let p1 = P {
             k: "precip_1h",
             v: Some("mm"),
+            source: None,
         };
         let p2 = P {
             k: "wind_speed_10m",
             v: None,
+            source: None,
         };
         p_values.push(p1);
         p_values.push(p2);
@@ -103,13 +356,198 @@ mod tests {
             PSet::from_iter([
                 P {
                     k: "precip_1h",
-                    v: Some("mm")
+                    v: Some("mm"),
+                    source: None
                 },
                 P {
                     k: "wind_speed_10m",
-                    v: None
+                    v: None,
+                    source: None
                 }
             ])
         );
     }
+
+    #[tokio::test]
+    async fn serde_round_trip() {
+        println!("\n##### serde_round_trip:");
+
+        let params: Parameters = Parameters {
+            p_values: PSet::from_iter([
+                P {
+                    k: "t_2m",
+                    v: Some("C"),
+                    source: None,
+                },
+                P {
+                    k: "wind_speed_10m",
+                    v: None,
+                    source: None,
+                },
+            ]),
+        };
+
+        let json = serde_json::to_string(&params).unwrap();
+        println!(">>>>>>>>>> json: {}", json);
+
+        let deserialized: Parameters = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, params);
+    }
+
+    #[tokio::test]
+    async fn owned_parameters() {
+        println!("\n##### owned_parameters:");
+
+        let unit = String::from("C");
+        let owned = OwnedParameters {
+            p_values: vec![
+                OwnedP {
+                    k: "t_2m".to_string(),
+                    v: Some(unit),
+                    source: Some("mix".to_string()),
+                },
+                OwnedP {
+                    k: "wind_speed_10m".to_string(),
+                    v: None,
+                    source: None,
+                },
+            ],
+        };
+
+        println!(">>>>>>>>>> owned: {}", owned);
+        assert_eq!(owned.to_string(), "t_2m:C:mix,wind_speed_10m");
+        assert_eq!(OwnedParameters::from(&owned.as_parameters()), owned);
+
+        let deserialized: OwnedParameters = serde_json::from_str(
+            r#"{"p_values":[{"k":"t_2m","v":"C","source":"mix"},{"k":"wind_speed_10m","v":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(deserialized, owned);
+    }
+
+    #[tokio::test]
+    async fn validate_parameters() {
+        println!("\n##### validate_parameters:");
+
+        let valid = [
+            P {
+                k: "t_2m",
+                v: Some("C"),
+                source: None,
+            },
+            P {
+                k: "wind_gusts_10m_1h",
+                v: Some("ms"),
+                source: None,
+            },
+            P {
+                k: "metar",
+                v: None,
+                source: None,
+            },
+        ];
+        assert!(ParameterValidation::Known.check(&valid).is_ok());
+
+        let result = ParameterValidation::Syntax.check(&[P {
+            k: "t 2m",
+            v: Some("C"),
+            source: None,
+        }]);
+        println!(">>>>>>>>>> result: {:?}", result);
+        assert!(result.is_err());
+        assert!(ParameterValidation::Syntax
+            .check(&[P {
+                k: "t_2m",
+                v: Some(""),
+                source: None
+            }])
+            .is_err());
+        assert!(ParameterValidation::Syntax
+            .check(&[P {
+                k: "t_2m,precip_1h",
+                v: None,
+                source: None
+            }])
+            .is_err());
+        assert!(ParameterValidation::Syntax.check(&[]).is_err());
+
+        let typo = [P {
+            k: "temprature_2m",
+            v: Some("C"),
+            source: None,
+        }];
+        assert!(ParameterValidation::Syntax.check(&typo).is_ok());
+        assert!(ParameterValidation::Known.check(&typo).is_err());
+        assert!(ParameterValidation::Off.check(&[]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn with_model_source() {
+        println!("\n##### with_model_source:");
+
+        let params: Parameters = Parameters {
+            p_values: PSet::from_iter([
+                P::with_unit("t_2m", Unit::Celsius).with_source("ecmwf-ifs"),
+                P::new("precip_1h", Some("mm")).with_source("mix"),
+                P::with_unit("wind_speed_10m", Unit::MetersPerSecond),
+            ]),
+        };
+
+        println!(">>>>>>>>>> params: {}", params);
+        assert_eq!(
+            params.to_string(),
+            "t_2m:C:ecmwf-ifs,precip_1h:mm:mix,wind_speed_10m:ms"
+        );
+        assert!(ParameterValidation::Known.check(&params.p_values).is_ok());
+        assert_eq!(params.p_values[1].source(), Some("mix"));
+        assert_eq!(params.p_values[2].source(), None);
+
+        let owned = OwnedParameters::from(&params);
+        assert_eq!(
+            owned.p_values[1],
+            OwnedP::new("precip_1h", Some("mm".to_string())).with_source("mix")
+        );
+        assert_eq!(owned.p_values[0].source(), Some("ecmwf-ifs"));
+
+        let without_unit = P::new("t_2m", None).with_source("ecmwf-ifs");
+        assert!(without_unit.validate().is_err());
+        assert!(P::with_unit("t_2m", Unit::Celsius)
+            .with_source("ecmwf ifs")
+            .validate()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn parse_from_str() {
+        println!("\n##### parse_from_str:");
+
+        let params =
+            Parameters::try_from("t_2m:C, precip_1h:mm,wind_speed_10m:ms:mix,metar").unwrap();
+        println!(">>>>>>>>>> params: {}", params);
+
+        assert_eq!(
+            params.p_values,
+            PSet::from_iter([
+                P::with_unit("t_2m", Unit::Celsius),
+                P::with_unit("precip_1h", Unit::Millimeters),
+                P::with_unit("wind_speed_10m", Unit::MetersPerSecond).with_source("mix"),
+                P {
+                    k: "metar",
+                    v: None,
+                    source: None
+                },
+            ])
+        );
+        assert_eq!(
+            params.to_string(),
+            "t_2m:C,precip_1h:mm,wind_speed_10m:ms:mix,metar"
+        );
+
+        let owned: OwnedParameters = "t_2m:C,precip_1h:mm".parse().unwrap();
+        assert_eq!(owned.to_string(), "t_2m:C,precip_1h:mm");
+
+        assert!(Parameters::try_from("").is_err());
+        assert!(Parameters::try_from("t_2m:C,,precip_1h:mm").is_err());
+        assert!("t 2m:C".parse::<OwnedParameters>().is_err());
+    }
 }
@@ -0,0 +1,100 @@
+use crate::format::Format;
+use crate::locations::{Locations, OwnedLocations};
+use crate::optionals::{Optionals, OwnedOptionals};
+use crate::parameters::{OwnedParameters, Parameters};
+use crate::valid_date_time::ValidDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A complete time series query, e.g. for batches, or to keep in a JSON or YAML file, log and
+/// replay with `MeteomaticsConnector::query`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct QuerySpec<'a> {
+    pub vdt: ValidDateTime,
+    #[serde(borrow)]
+    pub parameters: Parameters<'a>,
+    #[serde(borrow)]
+    pub locations: Locations<'a>,
+    #[serde(borrow)]
+    pub optionals: Option<Optionals<'a>>,
+    /// `CSV`, `XML` or `BIN`, or any format for `MeteomaticsConnector::query_to_file`; `CSV` if
+    /// left out.
+    #[serde(default)]
+    pub format: Format,
+}
+
+/// Owned counterpart of `QuerySpec`, e.g. for queries read from a config file or moved into a
+/// spawned task.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OwnedQuerySpec {
+    pub vdt: ValidDateTime,
+    pub parameters: OwnedParameters,
+    pub locations: OwnedLocations,
+    pub optionals: Option<OwnedOptionals>,
+    #[serde(default)]
+    pub format: Format,
+}
+
+impl OwnedQuerySpec {
+    /// Borrows the query for `MeteomaticsConnector::query` or `query_time_series_batch`.
+    pub fn as_query_spec(&self) -> QuerySpec<'_> {
+        QuerySpec {
+            vdt: self.vdt.clone(),
+            parameters: self.parameters.as_parameters(),
+            locations: self.locations.as_locations(),
+            optionals: self.optionals.as_ref().map(OwnedOptionals::as_optionals),
+            format: self.format,
+        }
+    }
+}
+
+impl<'a> From<&QuerySpec<'a>> for OwnedQuerySpec {
+    fn from(query_spec: &QuerySpec<'a>) -> Self {
+        OwnedQuerySpec {
+            vdt: query_spec.vdt.clone(),
+            parameters: OwnedParameters::from(&query_spec.parameters),
+            locations: OwnedLocations::from(&query_spec.locations),
+            optionals: query_spec.optionals.as_ref().map(OwnedOptionals::from),
+            format: query_spec.format,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::format::Format;
+    use crate::query_spec::{OwnedQuerySpec, QuerySpec};
+
+    #[tokio::test]
+    async fn read_query_spec_from_json() {
+        println!("\n##### read_query_spec_from_json:");
+
+        let json = r#"{
+            "vdt": {
+                "start_date_time": {"Relative": {"anchor": "Today", "time": null, "offset": null}},
+                "period_date": {"Days": 1},
+                "end_date_time": null,
+                "time_step": null,
+                "time_list": null
+            },
+            "parameters": {"p_values": [{"k": "t_2m", "v": "C"}]},
+            "locations": {"Coordinates": ["47.42", "9.37"]},
+            "optionals": {"opt_values": [{"k": "model", "v": "mix"}]}
+        }"#;
+        let owned_spec: OwnedQuerySpec = serde_json::from_str(json).unwrap();
+        println!(">>>>>>>>>> owned_spec: {:?}", owned_spec);
+
+        assert_eq!(owned_spec.format, Format::CSV);
+        assert_eq!(owned_spec.parameters.to_string(), "t_2m:C");
+        assert_eq!(owned_spec.vdt.format().unwrap(), "todayP1D");
+
+        let query_spec = QuerySpec {
+            format: Format::XML,
+            ..owned_spec.as_query_spec()
+        };
+        let json = serde_json::to_string(&query_spec).unwrap();
+        let read_back: OwnedQuerySpec = serde_json::from_str(&json).unwrap();
+        assert_eq!(read_back, OwnedQuerySpec::from(&query_spec));
+        assert_eq!(read_back.format, Format::XML);
+    }
+}
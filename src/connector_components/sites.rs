@@ -0,0 +1,149 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{ResponseBody, ResponseLocation};
+use crate::locations::{Coordinate, Locations, Point};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+/// Tolerance in degrees when matching response coordinates, which the API may round, to sites.
+const COORDINATE_TOLERANCE: f64 = 1e-4;
+
+/// A named location, e.g. a wind farm or weather station of a fleet.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Site {
+    pub name: String,
+    pub point: Point,
+}
+
+/// Sites queried together as one multi-point `Locations`, labelling the response by site name.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Sites {
+    pub sites: Vec<Site>,
+}
+
+#[derive(Deserialize)]
+struct SiteRecord {
+    name: String,
+    lat: f64,
+    lon: f64,
+}
+
+impl Sites {
+    /// Reads sites from a CSV file with a `name,lat,lon` header line.
+    pub fn from_csv_file<P: AsRef<Path>>(path: P) -> Result<Self, ConnectorError> {
+        let file = std::fs::File::open(path)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        Self::from_csv_reader(file)
+    }
+
+    /// Reads sites from CSV with a `name,lat,lon` header line, rejecting invalid coordinates.
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self, ConnectorError> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let mut sites = vec![];
+        for record in rdr.deserialize::<SiteRecord>() {
+            let record = record.map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+            sites.push(Site {
+                point: Point::new(record.lat, record.lon)?,
+                name: record.name,
+            });
+        }
+        if sites.is_empty() {
+            return Err(ConnectorError::InvalidQuery(
+                "Sites must contain at least one site.".to_string(),
+            ));
+        }
+        Ok(Sites { sites })
+    }
+
+    /// All sites as one multi-point location.
+    pub fn locations(&self) -> Locations<'static> {
+        Locations::Points(self.sites.iter().map(|site| site.point).collect())
+    }
+
+    /// The coordinate of the site called `name`.
+    pub fn coordinate(&self, name: &str) -> Option<Coordinate> {
+        self.sites
+            .iter()
+            .find(|site| site.name == name)
+            .map(|site| site.point.into())
+    }
+
+    /// The name of the site at `coordinate`.
+    pub fn name(&self, (lat, lon): Coordinate) -> Option<&str> {
+        self.sites
+            .iter()
+            .find(|site| {
+                (site.point.lat() - lat).abs() < COORDINATE_TOLERANCE
+                    && (site.point.lon() - lon).abs() < COORDINATE_TOLERANCE
+            })
+            .map(|site| site.name.as_str())
+    }
+
+    /// Splits a response to `locations` into one response per site, in response order. Records
+    /// of coordinates that match no site are labelled with the coordinate.
+    pub fn label(&self, response_body: &ResponseBody) -> Vec<(String, ResponseBody)> {
+        response_body
+            .split_by_location()
+            .into_iter()
+            .map(|(location, body)| {
+                let label = match location {
+                    Some(ResponseLocation::Coordinate(coordinate)) => match self.name(coordinate) {
+                        Some(name) => name.to_string(),
+                        None => ResponseLocation::Coordinate(coordinate).to_string(),
+                    },
+                    // A single site is answered without location columns.
+                    None if self.sites.len() == 1 => self.sites[0].name.clone(),
+                    location => location
+                        .as_ref()
+                        .map(ResponseLocation::to_string)
+                        .unwrap_or_default(),
+                };
+                (label, body)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{CsvChunkParser, ResponseBody};
+    use crate::sites::Sites;
+    use crate::test_support::MULTI_POINT_CSV;
+
+    #[tokio::test]
+    async fn load_and_label_sites() {
+        println!("\n##### load_and_label_sites:");
+
+        let csv = "name, lat, lon\n\
+                   St. Gallen, 47.41, 9.35\n\
+                   Winterthur, 47.51, 8.74\n";
+        let sites = Sites::from_csv_reader(csv.as_bytes()).unwrap();
+        println!(">>>>>>>>>> sites: {:?}", sites);
+
+        assert_eq!(sites.locations().to_string(), "47.41,9.35+47.51,8.74");
+        assert_eq!(sites.coordinate("Winterthur"), Some((47.51, 8.74)));
+        assert_eq!(sites.name((47.41, 9.35)), Some("St. Gallen"));
+        assert_eq!(sites.name((0.0, 0.0)), None);
+
+        let mut response_body = ResponseBody::new();
+        response_body.add_header("validdate".to_string());
+        response_body.add_header("t_2m:C".to_string());
+        let mut parser = CsvChunkParser::new();
+        parser
+            .feed(&mut response_body, MULTI_POINT_CSV.as_bytes())
+            .unwrap();
+        parser.finish(&mut response_body).unwrap();
+
+        let labelled = sites.label(&response_body);
+        assert_eq!(labelled.len(), 2);
+        assert_eq!(labelled[0].0, "St. Gallen");
+        assert_eq!(labelled[1].0, "Winterthur");
+        assert_eq!(labelled[1].1.column("t_2m:C"), Some(vec![4.2, 4.0]));
+
+        assert!(Sites::from_csv_reader("name,lat,lon\nNowhere,91,0\n".as_bytes()).is_err());
+        assert!(Sites::from_csv_reader("name,lat,lon\n".as_bytes()).is_err());
+    }
+}
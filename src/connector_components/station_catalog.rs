@@ -0,0 +1,147 @@
+use crate::connector_error::ConnectorError;
+use crate::locations::Coordinate;
+use crate::station::Station;
+use crate::MeteomaticsConnector;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Mean earth radius in kilometres.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// The downloaded station list with the time of the download.
+type Downloaded = Option<(Instant, Arc<Vec<Station>>)>;
+
+/// The station list, downloaded once and kept for local lookups by WMO id, name or distance.
+/// It is downloaded again on the first lookup after `refresh_interval`; clones share the list.
+#[derive(Clone, Debug)]
+pub struct StationCatalog {
+    connector: MeteomaticsConnector,
+    refresh_interval: Duration,
+    stations: Arc<Mutex<Downloaded>>,
+}
+
+impl StationCatalog {
+    pub fn new(connector: MeteomaticsConnector, refresh_interval: Duration) -> Self {
+        Self {
+            connector,
+            refresh_interval,
+            stations: Default::default(),
+        }
+    }
+
+    /// All stations, downloaded if not yet known or older than the refresh interval. Concurrent
+    /// lookups wait for a single download.
+    pub async fn stations(&self) -> Result<Arc<Vec<Station>>, ConnectorError> {
+        let mut stations = self.stations.lock().await;
+        match &*stations {
+            Some((downloaded, list)) if downloaded.elapsed() < self.refresh_interval => {
+                Ok(list.clone())
+            }
+            _ => {
+                let list = Arc::new(self.connector.query_station_list().await?);
+                tracing::debug!(stations = list.len(), "Downloaded station list");
+                *stations = Some((Instant::now(), list.clone()));
+                Ok(list)
+            }
+        }
+    }
+
+    /// Drops the downloaded list, so that the next lookup downloads it again.
+    pub async fn invalidate(&self) {
+        *self.stations.lock().await = None;
+    }
+
+    pub async fn by_wmo_id(&self, wmo_id: &str) -> Result<Option<Station>, ConnectorError> {
+        Ok(self
+            .stations()
+            .await?
+            .iter()
+            .find(|station| station.wmo_id.as_deref() == Some(wmo_id))
+            .cloned())
+    }
+
+    /// The stations whose name contains `name`, ignoring case.
+    pub async fn search(&self, name: &str) -> Result<Vec<Station>, ConnectorError> {
+        let name = name.to_lowercase();
+        Ok(self
+            .stations()
+            .await?
+            .iter()
+            .filter(|station| station.name.to_lowercase().contains(&name))
+            .cloned()
+            .collect())
+    }
+
+    /// The `count` stations closest to `coordinate`, nearest first, with their great-circle
+    /// distance in kilometres.
+    pub async fn nearest(
+        &self,
+        coordinate: Coordinate,
+        count: usize,
+    ) -> Result<Vec<(Station, f64)>, ConnectorError> {
+        let mut by_distance: Vec<(Station, f64)> = self
+            .stations()
+            .await?
+            .iter()
+            .map(|station| {
+                let distance = distance_km(coordinate, (station.lat, station.lon));
+                (station.clone(), distance)
+            })
+            .collect();
+        by_distance.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        by_distance.truncate(count);
+        Ok(by_distance)
+    }
+}
+
+/// Haversine distance between two coordinates.
+fn distance_km((lat1, lon1): Coordinate, (lat2, lon2): Coordinate) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test_support::{MockResponse, MockServer};
+    use crate::MeteomaticsConnector;
+    use std::time::Duration;
+
+    const STATIONS_CSV: &str = "Station Category;Station Type;ID Hash;WMO ID;Alternative IDs;\
+                                Name;Location Lat,Lon;Elevation;Start Date;End Date\n\
+                                SYNOP;SYNO;1;066810;;St. Gallen;47.4255,9.39837;779m;;\n\
+                                SYNOP;SYNO;2;066600;;Zurich / Fluntern;47.3779,8.5658;556m;;\n\
+                                SYNOP;SYNO;3;066310;;Bern / Zollikofen;46.9907,7.4641;553m;;\n";
+
+    #[tokio::test]
+    async fn look_up_cached_stations() {
+        println!("\n##### look_up_cached_stations:");
+
+        let server =
+            MockServer::start(vec![("find_station", MockResponse::csv(STATIONS_CSV))]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let catalog = meteomatics_connector.station_catalog(Duration::from_secs(3600));
+
+        let station = catalog.by_wmo_id("066600").await.unwrap().unwrap();
+        assert_eq!(station.name, "Zurich / Fluntern");
+        assert_eq!(catalog.search("gallen").await.unwrap().len(), 1);
+        let nearest = catalog.nearest((47.42, 9.37), 2).await.unwrap();
+        println!(">>>>>>>>>> nearest: {:?}", nearest);
+        assert_eq!(nearest[0].0.name, "St. Gallen");
+        assert!(nearest[0].1 < 5.0);
+        assert_eq!(nearest[1].0.name, "Zurich / Fluntern");
+        assert!((nearest[1].1 - 62.0).abs() < 2.0);
+        assert_eq!(server.requests().len(), 1);
+
+        catalog.invalidate().await;
+        assert!(catalog.by_wmo_id("000000").await.unwrap().is_none());
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::ConnectorResponse;
+use crate::format::Format;
+use crate::locations::{OwnedLocations, Point};
+use crate::optionals::{OwnedOpt, OwnedOptionals, QueryOption};
+use crate::parameters::{OwnedP, OwnedParameters, P};
+use crate::query_spec::OwnedQuerySpec;
+use crate::valid_date_time::{PeriodTime, VDTOffset, ValidDateTime, ValidDateTimeBuilder};
+use crate::MeteomaticsConnector;
+
+/// Fluent builder of a time series query, started by `MeteomaticsConnector::time_series`.
+///
+/// ```no_run
+/// # async fn example(connector: rust_connector_api::MeteomaticsConnector) {
+/// use chrono::{Duration, Utc};
+/// use rust_connector_api::valid_date_time::PeriodTime;
+///
+/// let response = connector
+///     .time_series()
+///     .from(Utc::now())
+///     .to(Utc::now() + Duration::days(1))
+///     .step(PeriodTime::Hours(1))
+///     .parameter("t_2m:C")
+///     .at(47.42, 9.37)
+///     .option("model", "mix")
+///     .run()
+///     .await;
+/// # }
+/// ```
+///
+/// Invalid arguments are reported by `build` or `run`, starting with the first one given.
+#[derive(Debug)]
+pub struct TimeSeriesQuery<'c> {
+    connector: &'c MeteomaticsConnector,
+    start_date_time: Option<VDTOffset>,
+    end_date_time: Option<VDTOffset>,
+    time_step: Option<PeriodTime>,
+    parameters: OwnedParameters,
+    points: Vec<Point>,
+    optionals: OwnedOptionals,
+    format: Format,
+    error: Option<ConnectorError>,
+}
+
+impl<'c> TimeSeriesQuery<'c> {
+    pub(crate) fn new(connector: &'c MeteomaticsConnector) -> Self {
+        Self {
+            connector,
+            start_date_time: None,
+            end_date_time: None,
+            time_step: None,
+            parameters: OwnedParameters::default(),
+            points: vec![],
+            optionals: OwnedOptionals::default(),
+            format: Format::default(),
+            error: None,
+        }
+    }
+
+    /// The first date time, or the only one if neither `to` nor `step` is given.
+    pub fn from(mut self, start_date_time: impl Into<VDTOffset>) -> Self {
+        self.start_date_time = Some(start_date_time.into());
+        self
+    }
+
+    pub fn to(mut self, end_date_time: impl Into<VDTOffset>) -> Self {
+        self.end_date_time = Some(end_date_time.into());
+        self
+    }
+
+    pub fn step(mut self, time_step: PeriodTime) -> Self {
+        self.time_step = Some(time_step);
+        self
+    }
+
+    /// Adds a parameter such as `t_2m:C` or `t_2m:C:ecmwf-ifs`.
+    pub fn parameter(mut self, parameter: &str) -> Self {
+        match P::try_from(parameter) {
+            Ok(p) => self.parameters.p_values.push(OwnedP {
+                k: p.k.to_string(),
+                v: p.v.map(str::to_string),
+                source: p.source.map(str::to_string),
+            }),
+            Err(error) => self.fail(error),
+        }
+        self
+    }
+
+    /// Adds a point; several points are queried at once.
+    pub fn at(mut self, lat: f64, lon: f64) -> Self {
+        match Point::new(lat, lon) {
+            Ok(point) => self.points.push(point),
+            Err(error) => self.fail(error),
+        }
+        self
+    }
+
+    pub fn option(mut self, key: &str, value: &str) -> Self {
+        self.optionals.opt_values.push(OwnedOpt {
+            k: key.to_string(),
+            v: value.to_string(),
+        });
+        self
+    }
+
+    pub fn query_option(mut self, query_option: QueryOption) -> Self {
+        self.optionals.query_options.push(query_option);
+        self
+    }
+
+    /// The response format, `CSV` by default.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn fail(&mut self, error: ConnectorError) {
+        self.error.get_or_insert(error);
+    }
+
+    /// Assembles the query without sending it.
+    pub fn build(self) -> Result<OwnedQuerySpec, ConnectorError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let start_date_time = self.start_date_time.ok_or_else(|| {
+            ConnectorError::InvalidQuery("A start date time is required.".to_string())
+        })?;
+        let mut vdt_builder = ValidDateTimeBuilder::default();
+        vdt_builder.start_date_time(start_date_time);
+        if let Some(end_date_time) = self.end_date_time {
+            vdt_builder.end_date_time(end_date_time);
+        }
+        if let Some(time_step) = self.time_step {
+            vdt_builder.time_step(time_step);
+        }
+        let vdt: ValidDateTime = vdt_builder
+            .build()
+            .map_err(|error| ConnectorError::InvalidQuery(error.to_string()))?;
+        if self.parameters.p_values.is_empty() {
+            return Err(ConnectorError::InvalidQuery(
+                "At least one parameter is required.".to_string(),
+            ));
+        }
+        let locations = match self.points.as_slice() {
+            [] => {
+                return Err(ConnectorError::InvalidQuery(
+                    "At least one point is required.".to_string(),
+                ))
+            }
+            [point] => OwnedLocations::Point(*point),
+            points => OwnedLocations::Points(points.to_vec()),
+        };
+        let optionals = if self.optionals == OwnedOptionals::default() {
+            None
+        } else {
+            Some(self.optionals)
+        };
+        Ok(OwnedQuerySpec {
+            vdt,
+            parameters: self.parameters,
+            locations,
+            optionals,
+            format: self.format,
+        })
+    }
+
+    /// Sends the query.
+    pub async fn run(self) -> Result<ConnectorResponse, ConnectorError> {
+        let connector = self.connector;
+        let owned_spec = self.build()?;
+        connector.query(owned_spec.as_query_spec()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_error::ConnectorError;
+    use crate::locations::OwnedLocations;
+    use crate::test_support::{MockResponse, MockServer, TIME_SERIES_CSV};
+    use crate::valid_date_time::{PeriodTime, VDTOffset};
+    use crate::MeteomaticsConnector;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn build_and_run_query() {
+        println!("\n##### build_and_run_query:");
+
+        let server = MockServer::start(vec![("t_2m:C", MockResponse::csv(TIME_SERIES_CSV))]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let start = Utc.with_ymd_and_hms(2023, 11, 20, 0, 0, 0).unwrap();
+
+        let query = || {
+            meteomatics_connector
+                .time_series()
+                .from(start)
+                .to(start + chrono::Duration::hours(4))
+                .step(PeriodTime::Hours(1))
+                .parameter("t_2m:C")
+                .parameter("precip_1h:mm")
+                .at(47.419708, 9.358478)
+                .option("model", "mix")
+        };
+        let owned_spec = query().build().unwrap();
+        println!(">>>>>>>>>> owned_spec: {:?}", owned_spec);
+        assert_eq!(owned_spec.vdt.start_date_time, VDTOffset::Utc(start));
+        assert_eq!(owned_spec.parameters.to_string(), "t_2m:C,precip_1h:mm");
+        assert!(matches!(owned_spec.locations, OwnedLocations::Point(_)));
+
+        let response = query().run().await.unwrap();
+        assert_eq!(response.http_status_code, "200");
+        assert!(server.requests()[0]
+            .path
+            .ends_with("/t_2m:C,precip_1h:mm/47.419708,9.358478/csv?model=mix"));
+
+        let result = meteomatics_connector
+            .time_series()
+            .from(start)
+            .parameter("t 2m:C")
+            .at(91.0, 9.0)
+            .build();
+        assert!(
+            matches!(result, Err(ConnectorError::InvalidQuery(message)) if message.contains("t 2m"))
+        );
+        assert!(meteomatics_connector
+            .time_series()
+            .parameter("t_2m:C")
+            .at(47.42, 9.37)
+            .build()
+            .is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Units of the Meteomatics API, written as in parameter names, e.g. the `C` in `t_2m:C`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+    Millimeters,
+    Centimeters,
+    Meters,
+    Inches,
+    MetersPerSecond,
+    KilometersPerHour,
+    Knots,
+    MilesPerHour,
+    Hectopascals,
+    Pascals,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Quantity {
+    Temperature,
+    Length,
+    Speed,
+    Pressure,
+}
+
+impl Unit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Celsius => "C",
+            Unit::Fahrenheit => "F",
+            Unit::Kelvin => "K",
+            Unit::Millimeters => "mm",
+            Unit::Centimeters => "cm",
+            Unit::Meters => "m",
+            Unit::Inches => "inch",
+            Unit::MetersPerSecond => "ms",
+            Unit::KilometersPerHour => "kmh",
+            Unit::Knots => "kn",
+            Unit::MilesPerHour => "mph",
+            Unit::Hectopascals => "hPa",
+            Unit::Pascals => "Pa",
+        }
+    }
+
+    fn quantity(&self) -> Quantity {
+        match self {
+            Unit::Celsius | Unit::Fahrenheit | Unit::Kelvin => Quantity::Temperature,
+            Unit::Millimeters | Unit::Centimeters | Unit::Meters | Unit::Inches => Quantity::Length,
+            Unit::MetersPerSecond | Unit::KilometersPerHour | Unit::Knots | Unit::MilesPerHour => {
+                Quantity::Speed
+            }
+            Unit::Hectopascals | Unit::Pascals => Quantity::Pressure,
+        }
+    }
+
+    /// The value in kelvin, meters, meters per second or pascals.
+    fn into_base(self, value: f64) -> f64 {
+        match self {
+            Unit::Celsius => value + 273.15,
+            Unit::Fahrenheit => (value - 32.0) * 5.0 / 9.0 + 273.15,
+            Unit::Millimeters => value / 1000.0,
+            Unit::Centimeters => value / 100.0,
+            Unit::Inches => value * 0.0254,
+            Unit::KilometersPerHour => value / 3.6,
+            Unit::Knots => value * 1852.0 / 3600.0,
+            Unit::MilesPerHour => value * 0.44704,
+            Unit::Hectopascals => value * 100.0,
+            Unit::Kelvin | Unit::Meters | Unit::MetersPerSecond | Unit::Pascals => value,
+        }
+    }
+
+    fn out_of_base(self, value: f64) -> f64 {
+        match self {
+            Unit::Celsius => value - 273.15,
+            Unit::Fahrenheit => (value - 273.15) * 9.0 / 5.0 + 32.0,
+            Unit::Millimeters => value * 1000.0,
+            Unit::Centimeters => value * 100.0,
+            Unit::Inches => value / 0.0254,
+            Unit::KilometersPerHour => value * 3.6,
+            Unit::Knots => value * 3600.0 / 1852.0,
+            Unit::MilesPerHour => value / 0.44704,
+            Unit::Hectopascals => value / 100.0,
+            Unit::Kelvin | Unit::Meters | Unit::MetersPerSecond | Unit::Pascals => value,
+        }
+    }
+
+    /// Converts `value` from this unit to `to`, failing if they measure different quantities,
+    /// e.g. temperature and speed.
+    pub fn convert(&self, value: f64, to: Unit) -> Result<f64, ConnectorError> {
+        if self.quantity() != to.quantity() {
+            return Err(ConnectorError::LibraryError(format!(
+                "Cannot convert {} to {}.",
+                self, to
+            )));
+        }
+        if *self == to {
+            return Ok(value);
+        }
+        Ok(to.out_of_base(self.into_base(value)))
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unit = match s {
+            "C" => Unit::Celsius,
+            "F" => Unit::Fahrenheit,
+            "K" => Unit::Kelvin,
+            "mm" => Unit::Millimeters,
+            "cm" => Unit::Centimeters,
+            "m" => Unit::Meters,
+            "inch" => Unit::Inches,
+            "ms" => Unit::MetersPerSecond,
+            "kmh" => Unit::KilometersPerHour,
+            "kn" => Unit::Knots,
+            "mph" => Unit::MilesPerHour,
+            "hPa" => Unit::Hectopascals,
+            "Pa" => Unit::Pascals,
+            _ => return Err(ConnectorError::LibraryError(format!("Unknown unit: {}", s))),
+        };
+        Ok(unit)
+    }
+}
+
+impl From<Unit> for String {
+    fn from(unit: Unit) -> Self {
+        unit.as_str().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::units::Unit;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn convert_units() {
+        println!("\n##### convert_units:");
+
+        let fahrenheit = Unit::Celsius.convert(20.0, Unit::Fahrenheit).unwrap();
+        println!(">>>>>>>>>> 20 C in F: {}", fahrenheit);
+
+        assert_close(fahrenheit, 68.0);
+        assert_close(
+            Unit::Fahrenheit.convert(68.0, Unit::Kelvin).unwrap(),
+            293.15,
+        );
+        assert_close(Unit::Inches.convert(1.0, Unit::Millimeters).unwrap(), 25.4);
+        assert_close(
+            Unit::MetersPerSecond
+                .convert(10.0, Unit::KilometersPerHour)
+                .unwrap(),
+            36.0,
+        );
+        assert_close(
+            Unit::Knots.convert(1.0, Unit::KilometersPerHour).unwrap(),
+            1.852,
+        );
+        assert_close(
+            Unit::Hectopascals.convert(1013.25, Unit::Pascals).unwrap(),
+            101325.0,
+        );
+        assert!(Unit::Celsius
+            .convert(f64::NAN, Unit::Kelvin)
+            .unwrap()
+            .is_nan());
+        assert!(Unit::Celsius.convert(1.0, Unit::Knots).is_err());
+
+        assert_eq!("kmh".parse::<Unit>().unwrap(), Unit::KilometersPerHour);
+        assert_eq!(Unit::Inches.to_string(), "inch");
+        assert!("furlong".parse::<Unit>().is_err());
+    }
+}
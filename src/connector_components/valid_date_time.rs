@@ -1,15 +1,49 @@
 use crate::connector_error::ConnectorError;
-use chrono::{DateTime, FixedOffset, Local, Utc};
+use chrono::{
+    DateTime, DurationRound, FixedOffset, Local, NaiveTime, SecondsFormat, Timelike, Utc,
+};
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum VDTOffset {
     Utc(DateTime<Utc>),
     Local(DateTime<Local>),
     FixedOffset(DateTime<FixedOffset>),
+    /// Resolved by the API at request time, e.g. `now-1H`, see `RelativeDateTime`.
+    Relative(RelativeDateTime),
 }
 
-#[derive(Builder, Clone, Debug, PartialEq)]
+/// Date time relative to the time a request is executed, in the Meteomatics syntax
+/// `<anchor>[T<hh>[:<mm>[:<ss>]]Z][(+|-)<n>(D|H|M|S)]`, e.g. `now`, `now-1H`, `today+2D` or
+/// `tomorrowT12Z`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RelativeDateTime {
+    pub anchor: RelativeAnchor,
+    /// UTC time of day replacing the time of the anchor.
+    pub time: Option<NaiveTime>,
+    pub offset: Option<RelativeOffset>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum RelativeAnchor {
+    Now,
+    Today,
+    Tomorrow,
+    Yesterday,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum RelativeOffset {
+    Days(i32),
+    Hours(i32),
+    Minutes(i32),
+    Seconds(i32),
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[builder(build_fn(validate = "Self::validate"))]
 pub struct ValidDateTime {
     #[builder(setter(into))]
     pub start_date_time: VDTOffset,
@@ -23,48 +57,127 @@ pub struct ValidDateTime {
     #[builder(setter(strip_option), default)]
     pub time_step: Option<PeriodTime>,
 
+    /// Explicit list of date times, formatted comma-joined in place of `start_date_time`. Cannot
+    /// be combined with `period_date`, `end_date_time` or `time_step`.
     #[builder(setter(strip_option), default)]
     pub time_list: Option<Vec<VDTOffset>>,
+
+    /// Repeats the start/end range, e.g. 06:00 to 18:00 every day of a month.
+    #[builder(setter(strip_option), default)]
+    #[serde(default)]
+    pub repeat: Option<Repeat>,
+}
+
+/// How date times are written into request URLs.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DatePrecision {
+    /// With as many sub-second digits as needed, e.g. nanoseconds for `Utc::now()`.
+    #[default]
+    Full,
+    Milliseconds,
+    Seconds,
+    /// Truncated to the full hour.
+    Hour,
+    /// Truncated to midnight in the offset of the date time.
+    Day,
+}
+
+/// `count` occurrences of a range, each starting `every` after the previous one.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Repeat {
+    pub count: u32,
+    pub every: PeriodTime,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum PeriodDate {
     Years(i32),
     Months(i32),
+    Weeks(i32),
     Days(i32),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum PeriodTime {
+    Days(i32),
     Hours(i32),
     Minutes(i32),
     Seconds(i32),
+    /// A sum of units, e.g. `P1DT12H`; zero components are left out when formatting.
+    Combined {
+        days: i32,
+        hours: i32,
+        minutes: i32,
+        seconds: i32,
+    },
+}
+
+/// An ISO 8601 duration such as `P1D`, `PT15M` or `P1DT12H`, e.g. read from a configuration file.
+/// Converts into the `PeriodDate` or `PeriodTime` it denotes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Period {
+    pub years: i32,
+    pub months: i32,
+    pub weeks: i32,
+    pub days: i32,
+    pub hours: i32,
+    pub minutes: i32,
+    pub seconds: i32,
 }
 
 impl ValidDateTime {
     pub fn format(&self) -> Result<String, ConnectorError> {
-        let start = self.start_date_time.to_string();
+        self.format_with(DatePrecision::default())
+    }
+
+    /// Like `format`, writing the date times with `precision`.
+    pub fn format_with(&self, precision: DatePrecision) -> Result<String, ConnectorError> {
+        if let Some(repeat) = self.repeat {
+            return Self::format_list_with(&self.occurrences(repeat)?, precision);
+        }
+        if let Some(time_list) = &self.time_list {
+            if time_list.is_empty() {
+                return Err(ConnectorError::InvalidQuery(
+                    "Time list must contain at least one date time.".to_string(),
+                ));
+            }
+            if self.period_date.is_some()
+                || self.end_date_time.is_some()
+                || self.time_step.is_some()
+            {
+                return Err(ConnectorError::InvalidQuery(
+                    "Cannot combine time list with period date, end date time or time step."
+                        .to_string(),
+                ));
+            }
+            return Ok(time_list
+                .iter()
+                .map(|vdt_offset| vdt_offset.format_with(precision))
+                .collect::<Vec<String>>()
+                .join(","));
+        }
+        let start = self.start_date_time.format_with(precision);
         let mut suffix = "".to_string();
         let mut both = false;
-        if self.period_date.is_some() {
-            suffix = self.period_date.unwrap().to_string();
+        if let Some(period_date) = self.period_date {
+            suffix = period_date.to_string();
         }
-        if self.time_step.is_some() {
+        if let Some(time_step) = self.time_step {
             if !suffix.is_empty() {
                 suffix += ":";
                 both = true;
             }
-            suffix += &*self.time_step.unwrap().to_string();
+            suffix += &*time_step.to_string();
         }
         match self.end_date_time {
             None => Ok(start + &*suffix),
             Some(_) => {
                 if both {
-                    return Err(ConnectorError::LibraryError(
+                    return Err(ConnectorError::InvalidQuery(
                         "Cannot use period date and time step simultaneously.".to_string(),
                     ));
                 }
-                let mut end = self.end_date_time.unwrap().to_string();
+                let mut end = self.end_date_time.unwrap().format_with(precision);
                 if !suffix.is_empty() {
                     end = end + ":" + &*suffix;
                 }
@@ -72,6 +185,358 @@ impl ValidDateTime {
             }
         }
     }
+
+    /// A single date time.
+    pub fn single(date_time: impl Into<VDTOffset>) -> Self {
+        ValidDateTime {
+            start_date_time: date_time.into(),
+            period_date: None,
+            end_date_time: None,
+            time_step: None,
+            time_list: None,
+            repeat: None,
+        }
+    }
+
+    /// Hourly from `hours` hours before the current full hour (UTC) up to it.
+    pub fn last_hours(hours: i64) -> Self {
+        let end = current_full_hour();
+        Self::hourly(end - chrono::Duration::hours(hours), end)
+    }
+
+    /// Hourly from the current full hour (UTC) up to `days` days later.
+    pub fn next_days(days: i64) -> Self {
+        let start = current_full_hour();
+        Self::hourly(start, start + chrono::Duration::days(days))
+    }
+
+    fn hourly(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        ValidDateTime {
+            end_date_time: Some(VDTOffset::Utc(end)),
+            time_step: Some(PeriodTime::Hours(1)),
+            ..Self::single(start)
+        }
+    }
+
+    /// The ranges of `repeat`, as disjoint windows without repetition.
+    fn occurrences(&self, repeat: Repeat) -> Result<Vec<ValidDateTime>, ConnectorError> {
+        if repeat.count == 0 || repeat.every.num_seconds() <= 0 || self.time_list.is_some() {
+            return Err(ConnectorError::InvalidQuery(
+                "Repeat requires a positive count and interval and no time list.".to_string(),
+            ));
+        }
+        (0..repeat.count)
+            .map(|n| {
+                let shift = chrono::Duration::seconds(repeat.every.num_seconds() * i64::from(n));
+                let shifted = |vdt_offset: VDTOffset| {
+                    vdt_offset.shifted(shift).ok_or_else(|| {
+                        ConnectorError::InvalidQuery(
+                            "Cannot repeat relative date times.".to_string(),
+                        )
+                    })
+                };
+                Ok(ValidDateTime {
+                    start_date_time: shifted(self.start_date_time)?,
+                    end_date_time: self.end_date_time.map(shifted).transpose()?,
+                    repeat: None,
+                    ..self.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Splits a time list, or an absolute range with a time step, into consecutive valid date
+    /// times of at most `max_time_steps` date times each. `None` if the date times cannot be
+    /// counted, e.g. for relative date times, a period date or a repeat.
+    pub fn split_time_steps(&self, max_time_steps: usize) -> Option<Vec<ValidDateTime>> {
+        let max_time_steps = max_time_steps.max(1);
+        if self.repeat.is_some() || self.period_date.is_some() {
+            return None;
+        }
+        if let Some(time_list) = &self.time_list {
+            return Some(
+                time_list
+                    .chunks(max_time_steps)
+                    .map(|chunk| ValidDateTime {
+                        start_date_time: chunk[0],
+                        time_list: Some(chunk.to_vec()),
+                        ..self.clone()
+                    })
+                    .collect(),
+            );
+        }
+        let (end_date_time, time_step) = match (self.end_date_time, self.time_step) {
+            (None, _) => return Some(vec![self.clone()]),
+            (Some(end_date_time), Some(time_step)) => (end_date_time, time_step),
+            (Some(_), None) => return None,
+        };
+        let step = time_step.num_seconds();
+        let span =
+            (end_date_time.fixed_offset()? - self.start_date_time.fixed_offset()?).num_seconds();
+        if step <= 0 || span < 0 {
+            return None;
+        }
+        let num_time_steps = span / step + 1;
+        let shifted = |n: i64| {
+            self.start_date_time
+                .shifted(chrono::Duration::seconds(n * step))
+        };
+        (0..num_time_steps)
+            .step_by(max_time_steps)
+            .map(|first| {
+                let last = first + max_time_steps as i64 - 1;
+                Some(ValidDateTime {
+                    start_date_time: shifted(first)?,
+                    end_date_time: if last < num_time_steps - 1 {
+                        Some(shifted(last)?)
+                    } else {
+                        Some(end_date_time)
+                    },
+                    ..self.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// The number of date times requested, or `None` if it cannot be counted, e.g. for relative
+    /// date times, a period date or a range without a time step.
+    pub fn num_time_steps(&self) -> Option<u64> {
+        let per_occurrence = match (&self.time_list, self.end_date_time, self.time_step) {
+            _ if self.period_date.is_some() => return None,
+            (Some(time_list), _, _) => time_list.len() as u64,
+            (None, None, _) => 1,
+            (None, Some(end_date_time), Some(time_step)) => {
+                let step = time_step.num_seconds();
+                let span = (end_date_time.fixed_offset()? - self.start_date_time.fixed_offset()?)
+                    .num_seconds();
+                if step <= 0 || span < 0 {
+                    return None;
+                }
+                (span / step + 1) as u64
+            }
+            (None, Some(_), None) => return None,
+        };
+        match self.repeat {
+            Some(repeat) => Some(per_occurrence * u64::from(repeat.count)),
+            None => Some(per_occurrence),
+        }
+    }
+
+    /// Splits an absolute range with a time step into consecutive ranges spanning `chunk` each,
+    /// e.g. one per month for `PeriodDate::Months(1)`, counted from the start. Every chunk
+    /// starts on a time step of the whole range, so that no date time is requested twice. `None`
+    /// for other valid date times or a non-positive `chunk`.
+    pub fn split_by_period(&self, chunk: PeriodDate) -> Option<Vec<ValidDateTime>> {
+        if self.repeat.is_some() || self.period_date.is_some() || self.time_list.is_some() {
+            return None;
+        }
+        let (end_date_time, time_step) = (self.end_date_time?, self.time_step?);
+        let start = self.start_date_time.fixed_offset()?;
+        let step = time_step.num_seconds();
+        let span = (end_date_time.fixed_offset()? - start).num_seconds();
+        if step <= 0 || span < 0 {
+            return None;
+        }
+        let num_time_steps = span / step + 1;
+
+        // Index of the first time step of every chunk.
+        let mut firsts: Vec<i64> = vec![0];
+        for n in 1.. {
+            let boundary = chunk.after(start, n)?;
+            let offset = (boundary - start).num_seconds();
+            if offset <= 0 {
+                return None;
+            }
+            let first = (offset + step - 1) / step;
+            if first >= num_time_steps {
+                break;
+            }
+            if first > *firsts.last().expect("Starts with the first chunk") {
+                firsts.push(first);
+            }
+        }
+        let shifted = |n: i64| {
+            self.start_date_time
+                .shifted(chrono::Duration::seconds(n * step))
+        };
+        firsts
+            .iter()
+            .enumerate()
+            .map(|(i, first)| {
+                Some(ValidDateTime {
+                    start_date_time: shifted(*first)?,
+                    end_date_time: match firsts.get(i + 1) {
+                        Some(next) => Some(shifted(next - 1)?),
+                        None => Some(end_date_time),
+                    },
+                    ..self.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Joins several disjoint windows into a single valid date time segment.
+    pub fn format_list(vdts: &[ValidDateTime]) -> Result<String, ConnectorError> {
+        Self::format_list_with(vdts, DatePrecision::default())
+    }
+
+    /// Like `format_list`, writing the date times with `precision`.
+    pub fn format_list_with(
+        vdts: &[ValidDateTime],
+        precision: DatePrecision,
+    ) -> Result<String, ConnectorError> {
+        if vdts.is_empty() {
+            return Err(ConnectorError::InvalidQuery(
+                "At least one valid date time is required.".to_string(),
+            ));
+        }
+        Ok(vdts
+            .iter()
+            .map(|vdt| vdt.format_with(precision))
+            .collect::<Result<Vec<String>, ConnectorError>>()?
+            .join(","))
+    }
+}
+
+impl ValidDateTimeBuilder {
+    /// Rejects the combinations that `ValidDateTime::format` cannot express: an end before the
+    /// start, a period date and a time step together with an end, a time step without an end or
+    /// a period date, and a time list that is empty or combined with a range.
+    fn validate(&self) -> Result<(), String> {
+        let period_date = self.period_date.flatten();
+        let end_date_time = self.end_date_time.flatten();
+        let time_step = self.time_step.flatten();
+        if let Some(time_list) = self.time_list.as_ref().and_then(Option::as_ref) {
+            if time_list.is_empty() {
+                return Err("Time list must contain at least one date time.".to_string());
+            }
+            if period_date.is_some()
+                || end_date_time.is_some()
+                || time_step.is_some()
+                || self.repeat.flatten().is_some()
+            {
+                return Err(
+                    "Cannot combine time list with period date, end date time, time step or repeat."
+                        .to_string(),
+                );
+            }
+            return Ok(());
+        }
+        if let (Some(start), Some(end)) = (
+            self.start_date_time.and_then(VDTOffset::fixed_offset),
+            end_date_time.and_then(VDTOffset::fixed_offset),
+        ) {
+            if end < start {
+                return Err("End date time must not be before start date time.".to_string());
+            }
+        }
+        if let Some(repeat) = self.repeat.flatten() {
+            if repeat.count == 0 || repeat.every.num_seconds() <= 0 {
+                return Err("Repeat requires a positive count and interval.".to_string());
+            }
+            let relative =
+                |vdt_offset: Option<VDTOffset>| matches!(vdt_offset, Some(VDTOffset::Relative(_)));
+            if relative(self.start_date_time) || relative(end_date_time) {
+                return Err("Cannot repeat relative date times.".to_string());
+            }
+        }
+        if end_date_time.is_some() && period_date.is_some() && time_step.is_some() {
+            return Err("Cannot use period date and time step simultaneously.".to_string());
+        }
+        if time_step.is_some() && end_date_time.is_none() && period_date.is_none() {
+            return Err("Time step requires an end date time or a period date.".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl VDTOffset {
+    /// Formats the date time as RFC 3339 with `precision`. Relative date times are kept as is.
+    pub fn format_with(&self, precision: DatePrecision) -> String {
+        let dt = match self.fixed_offset() {
+            Some(dt) => dt,
+            None => return self.to_string(),
+        };
+        let truncated = match precision {
+            DatePrecision::Full => return dt.to_rfc3339(),
+            DatePrecision::Milliseconds => return dt.to_rfc3339_opts(SecondsFormat::Millis, false),
+            DatePrecision::Seconds => Some(dt),
+            DatePrecision::Hour => dt.with_minute(0).and_then(|dt| dt.with_second(0)),
+            DatePrecision::Day => dt
+                .with_hour(0)
+                .and_then(|dt| dt.with_minute(0))
+                .and_then(|dt| dt.with_second(0)),
+        };
+        truncated
+            .expect("Zero is a valid time field")
+            .to_rfc3339_opts(SecondsFormat::Secs, false)
+    }
+
+    /// The date time `duration` later, or `None` for a relative one.
+    fn shifted(self, duration: chrono::Duration) -> Option<VDTOffset> {
+        match self {
+            VDTOffset::Utc(dt) => Some(VDTOffset::Utc(dt + duration)),
+            VDTOffset::Local(dt) => Some(VDTOffset::Local(dt + duration)),
+            VDTOffset::FixedOffset(dt) => Some(VDTOffset::FixedOffset(dt + duration)),
+            VDTOffset::Relative(_) => None,
+        }
+    }
+
+    /// The absolute date time, or `None` for a relative one.
+    fn fixed_offset(self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            VDTOffset::Utc(dt) => Some(dt.fixed_offset()),
+            VDTOffset::Local(dt) => Some(dt.fixed_offset()),
+            VDTOffset::FixedOffset(dt) => Some(dt),
+            VDTOffset::Relative(_) => None,
+        }
+    }
+}
+
+impl PeriodDate {
+    /// The date time `n` periods after `date_time` in calendar terms, or `None` for a
+    /// non-positive period or an overflow.
+    fn after(self, date_time: DateTime<FixedOffset>, n: i64) -> Option<DateTime<FixedOffset>> {
+        let months = |months: i32| {
+            u32::try_from(i64::from(months) * n)
+                .ok()
+                .filter(|months| *months > 0)
+                .and_then(|months| date_time.checked_add_months(chrono::Months::new(months)))
+        };
+        let days = |days: i32| {
+            Some(i64::from(days) * n)
+                .filter(|days| *days > 0)
+                .and_then(|days| date_time.checked_add_signed(chrono::Duration::try_days(days)?))
+        };
+        match self {
+            PeriodDate::Years(years) => months(years.checked_mul(12)?),
+            PeriodDate::Months(months_per_chunk) => months(months_per_chunk),
+            PeriodDate::Weeks(weeks) => days(weeks.checked_mul(7)?),
+            PeriodDate::Days(days_per_chunk) => days(days_per_chunk),
+        }
+    }
+}
+
+impl PeriodTime {
+    pub fn num_seconds(&self) -> i64 {
+        match *self {
+            PeriodTime::Days(n) => i64::from(n) * 86400,
+            PeriodTime::Hours(n) => i64::from(n) * 3600,
+            PeriodTime::Minutes(n) => i64::from(n) * 60,
+            PeriodTime::Seconds(n) => i64::from(n),
+            PeriodTime::Combined {
+                days,
+                hours,
+                minutes,
+                seconds,
+            } => {
+                i64::from(days) * 86400
+                    + i64::from(hours) * 3600
+                    + i64::from(minutes) * 60
+                    + i64::from(seconds)
+            }
+        }
+    }
 }
 
 impl Display for VDTOffset {
@@ -80,7 +545,273 @@ impl Display for VDTOffset {
             VDTOffset::Utc(dt) => write!(f, "{}", dt.to_rfc3339()),
             VDTOffset::Local(dt) => write!(f, "{}", dt.to_rfc3339()),
             VDTOffset::FixedOffset(dt) => write!(f, "{}", dt.to_rfc3339()),
+            VDTOffset::Relative(relative) => write!(f, "{}", relative),
+        }
+    }
+}
+
+fn current_full_hour() -> DateTime<Utc> {
+    Utc::now()
+        .duration_trunc(chrono::Duration::hours(1))
+        .expect("An hour divides any date time")
+}
+
+impl From<DateTime<Utc>> for VDTOffset {
+    fn from(dt: DateTime<Utc>) -> Self {
+        VDTOffset::Utc(dt)
+    }
+}
+
+impl From<DateTime<Local>> for VDTOffset {
+    fn from(dt: DateTime<Local>) -> Self {
+        VDTOffset::Local(dt)
+    }
+}
+
+impl From<DateTime<FixedOffset>> for VDTOffset {
+    fn from(dt: DateTime<FixedOffset>) -> Self {
+        VDTOffset::FixedOffset(dt)
+    }
+}
+
+impl From<RelativeDateTime> for VDTOffset {
+    fn from(relative: RelativeDateTime) -> Self {
+        VDTOffset::Relative(relative)
+    }
+}
+
+impl Display for RelativeDateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let anchor = match self.anchor {
+            RelativeAnchor::Now => "now",
+            RelativeAnchor::Today => "today",
+            RelativeAnchor::Tomorrow => "tomorrow",
+            RelativeAnchor::Yesterday => "yesterday",
+        };
+        write!(f, "{}", anchor)?;
+        if let Some(time) = self.time {
+            if time.minute() == 0 && time.second() == 0 {
+                write!(f, "T{:02}Z", time.hour())?;
+            } else {
+                write!(f, "T{}Z", time.format("%H:%M:%S"))?;
+            }
+        }
+        if let Some(offset) = self.offset {
+            let (n, unit) = match offset {
+                RelativeOffset::Days(n) => (n, 'D'),
+                RelativeOffset::Hours(n) => (n, 'H'),
+                RelativeOffset::Minutes(n) => (n, 'M'),
+                RelativeOffset::Seconds(n) => (n, 'S'),
+            };
+            write!(f, "{:+}{}", n, unit)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RelativeDateTime {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConnectorError::InvalidQuery(format!("Invalid relative date time: {}", s));
+
+        let (anchor, rest) = [
+            ("now", RelativeAnchor::Now),
+            ("today", RelativeAnchor::Today),
+            ("tomorrow", RelativeAnchor::Tomorrow),
+            ("yesterday", RelativeAnchor::Yesterday),
+        ]
+        .into_iter()
+        .find_map(|(keyword, anchor)| s.strip_prefix(keyword).map(|rest| (anchor, rest)))
+        .ok_or_else(invalid)?;
+
+        let (time, rest) = match rest.strip_prefix('T') {
+            Some(rest) => {
+                let end = rest.find('Z').ok_or_else(invalid)?;
+                let time = match rest[..end].len() {
+                    2 => NaiveTime::parse_from_str(&format!("{}:00", &rest[..end]), "%H:%M"),
+                    5 => NaiveTime::parse_from_str(&rest[..end], "%H:%M"),
+                    _ => NaiveTime::parse_from_str(&rest[..end], "%H:%M:%S"),
+                }
+                .map_err(|_| invalid())?;
+                (Some(time), &rest[end + 1..])
+            }
+            None => (None, rest),
+        };
+
+        let offset = match rest.chars().last() {
+            None => None,
+            Some(unit) => {
+                let n: i32 = rest[..rest.len() - unit.len_utf8()]
+                    .parse()
+                    .map_err(|_| invalid())?;
+                if !rest.starts_with(['+', '-']) {
+                    return Err(invalid());
+                }
+                Some(match unit {
+                    'D' => RelativeOffset::Days(n),
+                    'H' => RelativeOffset::Hours(n),
+                    'M' => RelativeOffset::Minutes(n),
+                    'S' => RelativeOffset::Seconds(n),
+                    _ => return Err(invalid()),
+                })
+            }
+        };
+
+        Ok(RelativeDateTime {
+            anchor,
+            time,
+            offset,
+        })
+    }
+}
+
+impl FromStr for Period {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConnectorError::InvalidQuery(format!("Invalid ISO 8601 duration: {}", s));
+
+        let rest = s.strip_prefix('P').ok_or_else(invalid)?;
+        let (date, time) = match rest.split_once('T') {
+            Some((_, "")) => return Err(invalid()),
+            Some((date, time)) => (date, time),
+            None => (rest, ""),
+        };
+        if date.is_empty() && time.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut period = Period::default();
+        for (part, units) in [(date, "YMWD"), (time, "HMS")] {
+            let mut digits = String::new();
+            let mut next_unit = 0;
+            for c in part.chars() {
+                if c.is_ascii_digit() {
+                    digits.push(c);
+                    continue;
+                }
+                let unit = units[next_unit..].find(c).ok_or_else(invalid)? + next_unit;
+                let n: i32 = digits.parse().map_err(|_| invalid())?;
+                digits.clear();
+                next_unit = unit + 1;
+                let component = match (units, c) {
+                    ("YMWD", 'Y') => &mut period.years,
+                    ("YMWD", 'M') => &mut period.months,
+                    ("YMWD", 'W') => &mut period.weeks,
+                    ("YMWD", 'D') => &mut period.days,
+                    (_, 'H') => &mut period.hours,
+                    (_, 'M') => &mut period.minutes,
+                    _ => &mut period.seconds,
+                };
+                *component = n;
+            }
+            if !digits.is_empty() {
+                return Err(invalid());
+            }
+        }
+        Ok(period)
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "P")?;
+        for (n, unit) in [
+            (self.years, 'Y'),
+            (self.months, 'M'),
+            (self.weeks, 'W'),
+            (self.days, 'D'),
+        ] {
+            if n != 0 {
+                write!(f, "{}{}", n, unit)?;
+            }
+        }
+        let time = [(self.hours, 'H'), (self.minutes, 'M'), (self.seconds, 'S')];
+        if time.iter().any(|(n, _)| *n != 0) {
+            write!(f, "T")?;
+            for (n, unit) in time {
+                if n != 0 {
+                    write!(f, "{}{}", n, unit)?;
+                }
+            }
+        } else if *self == Period::default() {
+            write!(f, "T0S")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<Period> for PeriodDate {
+    type Error = ConnectorError;
+
+    /// Accepts a period of exactly one of years, months, weeks or days.
+    fn try_from(period: Period) -> Result<Self, Self::Error> {
+        match period {
+            Period {
+                hours: 0,
+                minutes: 0,
+                seconds: 0,
+                ..
+            } => match (period.years, period.months, period.weeks, period.days) {
+                (n, 0, 0, 0) if n != 0 => Ok(PeriodDate::Years(n)),
+                (0, n, 0, 0) if n != 0 => Ok(PeriodDate::Months(n)),
+                (0, 0, n, 0) if n != 0 => Ok(PeriodDate::Weeks(n)),
+                (0, 0, 0, n) if n != 0 => Ok(PeriodDate::Days(n)),
+                _ => Err(ConnectorError::InvalidQuery(format!(
+                    "Period date must have exactly one unit: {}",
+                    period
+                ))),
+            },
+            _ => Err(ConnectorError::InvalidQuery(format!(
+                "Period date cannot have a time part: {}",
+                period
+            ))),
+        }
+    }
+}
+
+impl TryFrom<Period> for PeriodTime {
+    type Error = ConnectorError;
+
+    /// Accepts a period of days, hours, minutes and seconds, combined if more than one is set.
+    fn try_from(period: Period) -> Result<Self, Self::Error> {
+        if period.years != 0 || period.months != 0 || period.weeks != 0 {
+            return Err(ConnectorError::InvalidQuery(format!(
+                "Period time cannot have years, months or weeks: {}",
+                period
+            )));
         }
+        Ok(
+            match (period.days, period.hours, period.minutes, period.seconds) {
+                (n, 0, 0, 0) if n != 0 => PeriodTime::Days(n),
+                (0, n, 0, 0) if n != 0 => PeriodTime::Hours(n),
+                (0, 0, n, 0) if n != 0 => PeriodTime::Minutes(n),
+                (0, 0, 0, n) => PeriodTime::Seconds(n),
+                (days, hours, minutes, seconds) => PeriodTime::Combined {
+                    days,
+                    hours,
+                    minutes,
+                    seconds,
+                },
+            },
+        )
+    }
+}
+
+impl FromStr for PeriodDate {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Period>()?.try_into()
+    }
+}
+
+impl FromStr for PeriodTime {
+    type Err = ConnectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Period>()?.try_into()
     }
 }
 
@@ -89,6 +820,7 @@ impl Display for PeriodDate {
         match self {
             PeriodDate::Years(n) => write!(f, "P{}Y", n),
             PeriodDate::Months(n) => write!(f, "P{}M", n),
+            PeriodDate::Weeks(n) => write!(f, "P{}W", n),
             PeriodDate::Days(n) => write!(f, "P{}D", n),
         }
     }
@@ -97,9 +829,33 @@ impl Display for PeriodDate {
 impl Display for PeriodTime {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            PeriodTime::Days(n) => write!(f, "P{}D", n),
             PeriodTime::Hours(n) => write!(f, "PT{}H", n),
             PeriodTime::Minutes(n) => write!(f, "PT{}M", n),
             PeriodTime::Seconds(n) => write!(f, "PT{}S", n),
+            PeriodTime::Combined {
+                days,
+                hours,
+                minutes,
+                seconds,
+            } => {
+                write!(f, "P")?;
+                if *days != 0 {
+                    write!(f, "{}D", days)?;
+                }
+                if *hours != 0 || *minutes != 0 || *seconds != 0 {
+                    write!(f, "T")?;
+                }
+                for (n, unit) in [(hours, 'H'), (minutes, 'M'), (seconds, 'S')] {
+                    if *n != 0 {
+                        write!(f, "{}{}", n, unit)?;
+                    }
+                }
+                if *days == 0 && *hours == 0 && *minutes == 0 && *seconds == 0 {
+                    write!(f, "T0S")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -108,9 +864,10 @@ impl Display for PeriodTime {
 mod tests {
 
     use crate::valid_date_time::{
-        PeriodDate, PeriodTime, VDTOffset, ValidDateTime, ValidDateTimeBuilder,
+        DatePrecision, Period, PeriodDate, PeriodTime, RelativeAnchor, RelativeDateTime,
+        RelativeOffset, Repeat, VDTOffset, ValidDateTime, ValidDateTimeBuilder,
     };
-    use chrono::{Duration, Local, Utc};
+    use chrono::{Duration, FixedOffset, Local, TimeZone, Timelike, Utc};
 
     #[tokio::test]
     async fn create_with_default() {
@@ -146,7 +903,8 @@ mod tests {
                 period_date: None,
                 end_date_time: None,
                 time_step: None,
-                time_list: None
+                time_list: None,
+                repeat: None
             }
         );
     }
@@ -158,7 +916,7 @@ mod tests {
         // Use local time zone.
         let start_date_time = Local::now();
         let period_date = PeriodDate::Days(1);
-        let end_date_time = start_date_time.clone() + Duration::days(1);
+        let end_date_time = start_date_time + Duration::days(1);
         let time_step = PeriodTime::Hours(1);
         let start_vdt_offset = VDTOffset::Local(start_date_time);
         let end_vdt_offset = VDTOffset::Local(end_date_time);
@@ -166,9 +924,18 @@ mod tests {
 
         let local_vdt: ValidDateTime = ValidDateTimeBuilder::default()
             .start_date_time(start_vdt_offset)
-            .period_date(period_date)
             .end_date_time(end_vdt_offset)
             .time_step(time_step)
+            .build()
+            .unwrap();
+        let period_vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(start_vdt_offset)
+            .period_date(period_date)
+            .time_step(time_step)
+            .build()
+            .unwrap();
+        let list_vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(start_vdt_offset)
             .time_list(time_list)
             .build()
             .unwrap();
@@ -182,8 +949,8 @@ mod tests {
             local_vdt.start_date_time
         );
         println!(
-            ">>>>>>>>>> local_vdt.period_date: {}",
-            local_vdt.period_date.unwrap()
+            ">>>>>>>>>> period_vdt.period_date: {}",
+            period_vdt.period_date.unwrap()
         );
         println!(
             ">>>>>>>>>> local_vdt.end_date_time: {:?}",
@@ -198,19 +965,434 @@ mod tests {
             local_vdt.time_step.unwrap()
         );
 
-        let tl = local_vdt.time_list.unwrap();
-        println!(">>>>>>>>>> local_vdt.time_list: {:?}", tl);
+        let tl = list_vdt.time_list.unwrap();
+        println!(">>>>>>>>>> list_vdt.time_list: {:?}", tl);
 
         assert_eq!(local_vdt.start_date_time, start_vdt_offset);
         assert_eq!(local_vdt.end_date_time.unwrap(), end_vdt_offset);
 
-        assert_eq!(local_vdt.period_date.unwrap(), PeriodDate::Days(1));
+        assert_eq!(period_vdt.period_date.unwrap(), PeriodDate::Days(1));
         assert_eq!(local_vdt.time_step.unwrap(), PeriodTime::Hours(1));
 
-        assert_eq!(local_vdt.period_date.unwrap().to_string(), "P1D");
+        assert_eq!(period_vdt.period_date.unwrap().to_string(), "P1D");
         assert_eq!(local_vdt.time_step.unwrap().to_string(), "PT1H");
 
         assert_eq!(tl[0], start_vdt_offset);
         assert_eq!(tl[1], end_vdt_offset);
     }
+
+    #[tokio::test]
+    async fn format_period_granularities() {
+        println!("\n##### format_period_granularities:");
+
+        let combined = PeriodTime::Combined {
+            days: 1,
+            hours: 12,
+            minutes: 0,
+            seconds: 0,
+        };
+        println!(">>>>>>>>>> combined: {}", combined);
+
+        assert_eq!(PeriodDate::Weeks(2).to_string(), "P2W");
+        assert_eq!(PeriodTime::Days(1).to_string(), "P1D");
+        assert_eq!(PeriodTime::Days(1).num_seconds(), 86400);
+        assert_eq!(combined.to_string(), "P1DT12H");
+        assert_eq!(combined.num_seconds(), 129600);
+        assert_eq!(
+            PeriodTime::Combined {
+                days: 0,
+                hours: 0,
+                minutes: 30,
+                seconds: 15,
+            }
+            .to_string(),
+            "PT30M15S"
+        );
+
+        let start = VDTOffset::Utc(Utc.with_ymd_and_hms(2023, 11, 1, 0, 0, 0).unwrap());
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(start)
+            .period_date(PeriodDate::Weeks(2))
+            .time_step(PeriodTime::Days(1))
+            .build()
+            .unwrap();
+        assert_eq!(vdt.format().unwrap(), "2023-11-01T00:00:00+00:00P2W:P1D");
+    }
+
+    #[tokio::test]
+    async fn parse_iso_8601_durations() {
+        println!("\n##### parse_iso_8601_durations:");
+
+        let period: Period = "P1Y2M3W4DT5H6M7S".parse().unwrap();
+        println!(">>>>>>>>>> period: {:?}", period);
+        assert_eq!(period.weeks, 3);
+        assert_eq!(period.minutes, 6);
+        assert_eq!(period.to_string(), "P1Y2M3W4DT5H6M7S");
+
+        assert_eq!("P1D".parse::<PeriodDate>().unwrap(), PeriodDate::Days(1));
+        assert_eq!("P2W".parse::<PeriodDate>().unwrap(), PeriodDate::Weeks(2));
+        assert_eq!(
+            "PT15M".parse::<PeriodTime>().unwrap(),
+            PeriodTime::Minutes(15)
+        );
+        assert_eq!("P1D".parse::<PeriodTime>().unwrap(), PeriodTime::Days(1));
+        assert_eq!(
+            "P1DT12H".parse::<PeriodTime>().unwrap(),
+            PeriodTime::Combined {
+                days: 1,
+                hours: 12,
+                minutes: 0,
+                seconds: 0,
+            }
+        );
+        for period_time in ["PT1H", "PT30M15S", "P1DT12H"] {
+            assert_eq!(
+                period_time.parse::<PeriodTime>().unwrap().to_string(),
+                period_time
+            );
+        }
+
+        for invalid in ["", "P", "1D", "PT", "P1H", "PT1D", "P1D2Y", "P1.5D", "PT15"] {
+            assert!(invalid.parse::<Period>().is_err(), "{}", invalid);
+        }
+        assert!("P1M1D".parse::<PeriodDate>().is_err());
+        assert!("PT1H".parse::<PeriodDate>().is_err());
+        assert!("P1M".parse::<PeriodTime>().is_err());
+    }
+
+    #[tokio::test]
+    async fn format_repeating_interval() {
+        println!("\n##### format_repeating_interval:");
+
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2023, 11, 20, 6, 0, 0).unwrap(),
+            ))
+            .end_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2023, 11, 20, 18, 0, 0).unwrap(),
+            ))
+            .time_step(PeriodTime::Hours(1))
+            .repeat(Repeat {
+                count: 3,
+                every: PeriodTime::Days(1),
+            })
+            .build()
+            .unwrap();
+
+        let formatted = vdt.format().unwrap();
+        println!(">>>>>>>>>> formatted: {}", formatted);
+
+        assert_eq!(
+            formatted,
+            "2023-11-20T06:00:00+00:00--2023-11-20T18:00:00+00:00:PT1H,\
+             2023-11-21T06:00:00+00:00--2023-11-21T18:00:00+00:00:PT1H,\
+             2023-11-22T06:00:00+00:00--2023-11-22T18:00:00+00:00:PT1H"
+        );
+        assert!(ValidDateTimeBuilder::default()
+            .start_date_time("today".parse::<RelativeDateTime>().unwrap())
+            .repeat(Repeat {
+                count: 3,
+                every: PeriodTime::Days(1),
+            })
+            .build()
+            .is_err());
+        assert!(ValidDateTimeBuilder::default()
+            .start_date_time(vdt.start_date_time)
+            .repeat(Repeat {
+                count: 0,
+                every: PeriodTime::Days(1),
+            })
+            .build()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn create_common_time_ranges() {
+        println!("\n##### create_common_time_ranges:");
+
+        let last_hours = ValidDateTime::last_hours(24);
+        println!(">>>>>>>>>> last_hours: {}", last_hours.format().unwrap());
+        match (last_hours.start_date_time, last_hours.end_date_time) {
+            (VDTOffset::Utc(start), Some(VDTOffset::Utc(end))) => {
+                assert_eq!(end - start, Duration::hours(24));
+                assert_eq!(end.minute(), 0);
+                assert_eq!(end.nanosecond(), 0);
+            }
+            _ => panic!("Expected a UTC range"),
+        }
+        assert_eq!(last_hours.time_step, Some(PeriodTime::Hours(1)));
+
+        let next_days = ValidDateTime::next_days(3);
+        match (next_days.start_date_time, next_days.end_date_time) {
+            (VDTOffset::Utc(start), Some(VDTOffset::Utc(end))) => {
+                assert_eq!(end - start, Duration::days(3));
+            }
+            _ => panic!("Expected a UTC range"),
+        }
+
+        let noon = Utc.with_ymd_and_hms(2023, 11, 20, 12, 0, 0).unwrap();
+        let single = ValidDateTime::single(noon);
+        assert_eq!(single.format().unwrap(), "2023-11-20T12:00:00+00:00");
+        assert_eq!(
+            single,
+            ValidDateTimeBuilder::default()
+                .start_date_time(VDTOffset::Utc(noon))
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn format_with_precision() {
+        println!("\n##### format_with_precision:");
+
+        let dt = Utc
+            .with_ymd_and_hms(2023, 11, 20, 13, 45, 30)
+            .unwrap()
+            .with_nanosecond(123456789)
+            .unwrap();
+        let vdt_offset = VDTOffset::Utc(dt);
+        println!(">>>>>>>>>> full: {}", vdt_offset);
+
+        assert_eq!(
+            vdt_offset.format_with(DatePrecision::Full),
+            "2023-11-20T13:45:30.123456789+00:00"
+        );
+        assert_eq!(
+            vdt_offset.format_with(DatePrecision::Milliseconds),
+            "2023-11-20T13:45:30.123+00:00"
+        );
+        assert_eq!(
+            vdt_offset.format_with(DatePrecision::Seconds),
+            "2023-11-20T13:45:30+00:00"
+        );
+        assert_eq!(
+            vdt_offset.format_with(DatePrecision::Hour),
+            "2023-11-20T13:00:00+00:00"
+        );
+        let zurich = FixedOffset::east_opt(3600).unwrap();
+        assert_eq!(
+            VDTOffset::FixedOffset(dt.with_timezone(&zurich)).format_with(DatePrecision::Day),
+            "2023-11-20T00:00:00+01:00"
+        );
+        assert_eq!(
+            VDTOffset::Relative("now-1H".parse().unwrap()).format_with(DatePrecision::Hour),
+            "now-1H"
+        );
+
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(vdt_offset)
+            .end_date_time(VDTOffset::Utc(dt + Duration::hours(2)))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .unwrap();
+        assert_eq!(
+            vdt.format_with(DatePrecision::Hour).unwrap(),
+            "2023-11-20T13:00:00+00:00--2023-11-20T15:00:00+00:00:PT1H"
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_combinations() {
+        println!("\n##### reject_invalid_combinations:");
+
+        let start = VDTOffset::Utc(Utc.with_ymd_and_hms(2023, 11, 20, 0, 0, 0).unwrap());
+        let before_start = VDTOffset::Utc(Utc.with_ymd_and_hms(2023, 11, 19, 0, 0, 0).unwrap());
+        let end = VDTOffset::Utc(Utc.with_ymd_and_hms(2023, 11, 21, 0, 0, 0).unwrap());
+
+        let end_before_start = ValidDateTimeBuilder::default()
+            .start_date_time(start)
+            .end_date_time(before_start)
+            .build();
+        println!(">>>>>>>>>> end_before_start: {:?}", end_before_start);
+        assert!(end_before_start.is_err());
+        assert!(ValidDateTimeBuilder::default()
+            .start_date_time(start)
+            .end_date_time(end)
+            .period_date(PeriodDate::Days(1))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .is_err());
+        assert!(ValidDateTimeBuilder::default()
+            .start_date_time(start)
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .is_err());
+        assert!(ValidDateTimeBuilder::default()
+            .start_date_time(start)
+            .time_list(vec![])
+            .build()
+            .is_err());
+        assert!(ValidDateTimeBuilder::default()
+            .start_date_time(start)
+            .end_date_time(end)
+            .time_list(vec![start, end])
+            .build()
+            .is_err());
+
+        // Relative date times are resolved by the API and not compared.
+        assert!(ValidDateTimeBuilder::default()
+            .start_date_time(start)
+            .end_date_time(VDTOffset::Relative("now".parse().unwrap()))
+            .build()
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn format_time_list() {
+        println!("\n##### format_time_list:");
+
+        let first = VDTOffset::Utc(Utc.with_ymd_and_hms(2023, 11, 20, 6, 0, 0).unwrap());
+        let second = VDTOffset::Utc(Utc.with_ymd_and_hms(2023, 11, 21, 18, 0, 0).unwrap());
+        let utc_vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(first)
+            .time_list(vec![first, second])
+            .build()
+            .unwrap();
+
+        let formatted = utc_vdt.format().unwrap();
+        println!(">>>>>>>>>> formatted: {}", formatted);
+
+        assert_eq!(
+            formatted,
+            "2023-11-20T06:00:00+00:00,2023-11-21T18:00:00+00:00"
+        );
+
+        let with_time_step = ValidDateTime {
+            time_step: Some(PeriodTime::Hours(1)),
+            ..utc_vdt
+        };
+        let result = with_time_step.format();
+        println!(">>>>>>>>>> result: {:?}", result);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn format_relative_date_times() {
+        println!("\n##### format_relative_date_times:");
+
+        for expression in [
+            "now",
+            "now-1H",
+            "today+2D",
+            "tomorrowT12Z",
+            "yesterdayT06:30:00Z+15M",
+        ] {
+            let relative: RelativeDateTime = expression.parse().unwrap();
+            println!(">>>>>>>>>> {}: {:?}", expression, relative);
+            assert_eq!(relative.to_string(), expression);
+        }
+        assert_eq!(
+            "now-1H".parse::<RelativeDateTime>().unwrap(),
+            RelativeDateTime {
+                anchor: RelativeAnchor::Now,
+                time: None,
+                offset: Some(RelativeOffset::Hours(-1)),
+            }
+        );
+        for expression in [
+            "later",
+            "now1H",
+            "now+1X",
+            "now+1é",
+            "todayT25Z",
+            "tomorrowT12",
+        ] {
+            assert!(expression.parse::<RelativeDateTime>().is_err());
+        }
+
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time("today".parse::<RelativeDateTime>().unwrap())
+            .end_date_time(VDTOffset::Relative("today+1D".parse().unwrap()))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .unwrap();
+        assert_eq!(vdt.format().unwrap(), "today--today+1D:PT1H");
+    }
+
+    #[tokio::test]
+    async fn format_disjoint_windows() {
+        println!("\n##### format_disjoint_windows:");
+
+        let monday = Utc.with_ymd_and_hms(2023, 11, 20, 6, 0, 0).unwrap();
+        let friday = Utc.with_ymd_and_hms(2023, 11, 24, 6, 0, 0).unwrap();
+        let windows: Vec<ValidDateTime> = [monday, friday]
+            .iter()
+            .map(|start| {
+                ValidDateTimeBuilder::default()
+                    .start_date_time(VDTOffset::Utc(*start))
+                    .end_date_time(VDTOffset::Utc(*start + Duration::hours(12)))
+                    .time_step(PeriodTime::Hours(6))
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+
+        let formatted = ValidDateTime::format_list(&windows).unwrap();
+        println!(">>>>>>>>>> formatted: {}", formatted);
+
+        assert_eq!(
+            formatted,
+            "2023-11-20T06:00:00+00:00--2023-11-20T18:00:00+00:00:PT6H,\
+             2023-11-24T06:00:00+00:00--2023-11-24T18:00:00+00:00:PT6H"
+        );
+        assert!(ValidDateTime::format_list(&[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn split_by_calendar_period() {
+        println!("\n##### split_by_calendar_period:");
+
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap(),
+            ))
+            .end_date_time(VDTOffset::Utc(
+                Utc.with_ymd_and_hms(2024, 3, 20, 0, 0, 0).unwrap(),
+            ))
+            .time_step(PeriodTime::Hours(7))
+            .build()
+            .unwrap();
+
+        let chunks = vdt.split_by_period(PeriodDate::Months(1)).unwrap();
+        let formatted: Vec<String> = chunks.iter().map(|chunk| chunk.format().unwrap()).collect();
+        println!(">>>>>>>>>> formatted: {:#?}", formatted);
+        // Chunks start on the 7-hourly grid of the whole range, at the first step of each month.
+        assert_eq!(
+            formatted,
+            vec![
+                "2024-01-15T12:00:00+00:00--2024-02-15T10:00:00+00:00:PT7H",
+                "2024-02-15T17:00:00+00:00--2024-03-15T07:00:00+00:00:PT7H",
+                "2024-03-15T14:00:00+00:00--2024-03-20T00:00:00+00:00:PT7H",
+            ]
+        );
+        assert_eq!(
+            vdt.split_by_period(PeriodDate::Years(1)).unwrap(),
+            vec![vdt.clone()]
+        );
+        assert_eq!(vdt.split_by_period(PeriodDate::Days(0)), None);
+        assert_eq!(
+            ValidDateTime::single(Utc::now()).split_by_period(PeriodDate::Days(1)),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn serde_round_trip() {
+        println!("\n##### serde_round_trip:");
+
+        let start = Utc.with_ymd_and_hms(2023, 11, 20, 0, 0, 0).unwrap();
+        let utc_vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(VDTOffset::Utc(start))
+            .end_date_time(VDTOffset::Utc(start + Duration::days(1)))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&utc_vdt).unwrap();
+        println!(">>>>>>>>>> json: {}", json);
+
+        let deserialized: ValidDateTime = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, utc_vdt);
+    }
 }
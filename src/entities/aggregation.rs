@@ -0,0 +1,323 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{RecordValue, ResponseBody, ResponseIndex};
+use crate::derived_quantities::parameter_and_unit;
+use crate::valid_date_time::PeriodTime;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// Reduction applied to the values of a column or of a resampling period.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Aggregation {
+    Mean,
+    Min,
+    Max,
+    Sum,
+}
+
+impl Aggregation {
+    /// Aggregates the numeric values, skipping missing ones. `None` if there are none left.
+    pub fn apply(&self, values: impl IntoIterator<Item = f64>) -> Option<f64> {
+        let values: Vec<f64> = values.into_iter().filter(|value| !value.is_nan()).collect();
+        if values.is_empty() {
+            return None;
+        }
+        let aggregate = match self {
+            Aggregation::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregation::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregation::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregation::Sum => values.iter().sum(),
+        };
+        Some(aggregate)
+    }
+
+    /// The aggregation that keeps the meaning of the column with header `header` when
+    /// resampling: `Sum` for accumulated amounts such as `precip_1h:mm` or `global_rad_1h:J`,
+    /// `Max` and `Min` for extremes such as `t_max_2m_24h:C` or `wind_gusts_10m_1h:ms`, and
+    /// `Mean` for everything else, e.g. `t_2m:C`.
+    pub fn for_header(header: &str) -> Self {
+        const ACCUMULATED: [&str; 5] = [
+            "precip",
+            "fresh_snow",
+            "sunshine_duration",
+            "evaporation",
+            "runoff",
+        ];
+        let (parameter, unit) = parameter_and_unit(header);
+        let mut segments = parameter.split('_');
+        if segments
+            .clone()
+            .any(|segment| segment == "max" || segment == "gusts")
+        {
+            Aggregation::Max
+        } else if segments.any(|segment| segment == "min") {
+            Aggregation::Min
+        } else if ACCUMULATED
+            .iter()
+            .any(|prefix| parameter.starts_with(prefix))
+            || unit == "J"
+        {
+            Aggregation::Sum
+        } else {
+            Aggregation::Mean
+        }
+    }
+}
+
+/// Which end of its period a resampled value is stamped with.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PeriodStamp {
+    /// The period `[start, end)` is stamped with its start.
+    Start,
+    /// The period `(start, end]` is stamped with its end, as the API stamps its own period
+    /// values: `precip_1h` at 00:00 is the rain from 23:00 to 00:00.
+    #[default]
+    End,
+}
+
+impl PeriodStamp {
+    /// The stamp of the period of length `seconds` that holds `timestamp`.
+    fn of(&self, timestamp: i64, seconds: i64) -> i64 {
+        match self {
+            PeriodStamp::Start => timestamp.div_euclid(seconds) * seconds,
+            PeriodStamp::End => -(-timestamp).div_euclid(seconds) * seconds,
+        }
+    }
+}
+
+impl ResponseBody {
+    pub fn mean(&self, name: &str) -> Option<f64> {
+        self.aggregate(name, Aggregation::Mean)
+    }
+
+    pub fn min(&self, name: &str) -> Option<f64> {
+        self.aggregate(name, Aggregation::Min)
+    }
+
+    pub fn max(&self, name: &str) -> Option<f64> {
+        self.aggregate(name, Aggregation::Max)
+    }
+
+    pub fn sum(&self, name: &str) -> Option<f64> {
+        self.aggregate(name, Aggregation::Sum)
+    }
+
+    /// Aggregates the numeric column with header `name`, skipping missing values.
+    pub fn aggregate(&self, name: &str, aggregation: Aggregation) -> Option<f64> {
+        aggregation.apply(self.column(name)?)
+    }
+
+    /// Aggregates the records of every location into periods of length `period`, aligned to the
+    /// Unix epoch, e.g. `PeriodTime::Hours(24)` turns hourly into daily values. Each period is
+    /// indexed by the end `stamp` names. Text values are skipped; periods without records are
+    /// left out and periods without numeric values get missing values.
+    pub fn resample(
+        &self,
+        period: PeriodTime,
+        aggregation: Aggregation,
+        stamp: PeriodStamp,
+    ) -> Result<ResponseBody, ConnectorError> {
+        self.resample_with(period, |_| aggregation, stamp)
+    }
+
+    /// Resamples like `resample`, choosing the aggregation of every column by its parameter with
+    /// `Aggregation::for_header`, e.g. 10-minute data to hourly mean temperatures and hourly
+    /// precipitation sums at once. The periods are stamped with their end like the values of the
+    /// API, so that the sums of accumulated parameters cover the right hours.
+    pub fn resample_by_parameter(
+        &self,
+        period: PeriodTime,
+    ) -> Result<ResponseBody, ConnectorError> {
+        self.resample_with(period, Aggregation::for_header, PeriodStamp::End)
+    }
+
+    fn resample_with(
+        &self,
+        period: PeriodTime,
+        aggregation: impl Fn(&str) -> Aggregation,
+        stamp: PeriodStamp,
+    ) -> Result<ResponseBody, ConnectorError> {
+        let seconds = period.num_seconds();
+        if seconds <= 0 {
+            return Err(ConnectorError::LibraryError(format!(
+                "Cannot resample to non-positive period {}.",
+                period
+            )));
+        }
+
+        let mut buckets: Vec<(ResponseIndex, Vec<Vec<f64>>)> = vec![];
+        for (index, values) in self.response_records.iter() {
+            let timestamp = stamp.of(index.valid_date.timestamp(), seconds);
+            let period_index = ResponseIndex {
+                location: index.location.clone(),
+                valid_date: DateTime::from_timestamp(timestamp, 0).ok_or_else(|| {
+                    ConnectorError::LibraryError(format!("Invalid period stamp {}.", timestamp))
+                })?,
+            };
+            let position = match buckets
+                .iter()
+                .position(|(bucket, _)| *bucket == period_index)
+            {
+                Some(position) => position,
+                None => {
+                    buckets.push((period_index, vec![]));
+                    buckets.len() - 1
+                }
+            };
+            let columns = &mut buckets[position].1;
+            if columns.len() < values.len() {
+                columns.resize(values.len(), vec![]);
+            }
+            for (column, value) in columns.iter_mut().zip(values.iter()) {
+                if let Some(number) = value.as_f64() {
+                    column.push(number);
+                }
+            }
+        }
+
+        let response_records = buckets
+            .into_iter()
+            .map(|(index, columns)| {
+                let values = columns
+                    .into_iter()
+                    .enumerate()
+                    .map(|(n, column)| {
+                        let header = self.value_headers().get(n).map_or("", String::as_str);
+                        match aggregation(header).apply(column) {
+                            Some(aggregate) => RecordValue::Number(aggregate),
+                            None => RecordValue::Missing,
+                        }
+                    })
+                    .collect();
+                (index, values)
+            })
+            .collect();
+
+        Ok(ResponseBody {
+            response_headers: self.response_headers.clone(),
+            response_records,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::aggregation::{Aggregation, PeriodStamp};
+    use crate::connector_response::{RecordValue, ResponseBody};
+    use crate::valid_date_time::PeriodTime;
+
+    fn hourly_body() -> ResponseBody {
+        ResponseBody {
+            response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+            response_records: vec![
+                ("2023-11-20T22:00:00Z".parse().unwrap(), vec![4.0.into()]),
+                ("2023-11-20T23:00:00Z".parse().unwrap(), vec![2.0.into()]),
+                ("2023-11-21T00:00:00Z".parse().unwrap(), vec![1.0.into()]),
+                (
+                    "2023-11-21T01:00:00Z".parse().unwrap(),
+                    vec![RecordValue::Missing],
+                ),
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn aggregate_columns() {
+        println!("\n##### aggregate_columns:");
+
+        let response_body = hourly_body();
+
+        assert_eq!(response_body.mean("t_2m:C"), Some(7.0 / 3.0));
+        assert_eq!(response_body.min("t_2m:C"), Some(1.0));
+        assert_eq!(response_body.max("t_2m:C"), Some(4.0));
+        assert_eq!(response_body.sum("t_2m:C"), Some(7.0));
+        assert_eq!(response_body.sum("precip_1h:mm"), None);
+    }
+
+    #[tokio::test]
+    async fn resample_to_daily() {
+        println!("\n##### resample_to_daily:");
+
+        let daily = hourly_body()
+            .resample(PeriodTime::Hours(24), Aggregation::Mean, PeriodStamp::Start)
+            .unwrap();
+        println!(">>>>>>>>>> daily:\n{}", daily);
+
+        assert_eq!(
+            daily.response_records,
+            vec![
+                ("2023-11-20T00:00:00Z".parse().unwrap(), vec![3.0.into()]),
+                ("2023-11-21T00:00:00Z".parse().unwrap(), vec![1.0.into()]),
+            ]
+        );
+
+        // 00:00 closes the 20th, 01:00 opens the 21st, whose only value is missing.
+        let daily = hourly_body()
+            .resample(PeriodTime::Hours(24), Aggregation::Mean, PeriodStamp::End)
+            .unwrap();
+        println!(">>>>>>>>>> daily:\n{}", daily);
+
+        assert_eq!(
+            daily.response_records,
+            vec![
+                (
+                    "2023-11-21T00:00:00Z".parse().unwrap(),
+                    vec![(7.0 / 3.0).into()]
+                ),
+                (
+                    "2023-11-22T00:00:00Z".parse().unwrap(),
+                    vec![RecordValue::Missing]
+                ),
+            ]
+        );
+        assert!(hourly_body()
+            .resample(PeriodTime::Hours(0), Aggregation::Sum, PeriodStamp::End)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn resample_by_parameter() {
+        println!("\n##### resample_by_parameter:");
+
+        let ten_minutes = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "precip_10min:mm".to_string(),
+                "wind_gusts_10m_10min:ms".to_string(),
+            ],
+            response_records: ["00:10", "00:20", "00:30", "00:40", "00:50", "01:00"]
+                .iter()
+                .enumerate()
+                .map(|(n, time)| {
+                    let n = n as f64;
+                    (
+                        format!("2023-11-20T{}:00Z", time).parse().unwrap(),
+                        vec![n.into(), 0.5.into(), (10.0 - n).into()],
+                    )
+                })
+                .collect(),
+        };
+
+        // The precipitation up to 01:00 is summed into the hour ending at 01:00.
+        let hourly = ten_minutes
+            .resample_by_parameter(PeriodTime::Hours(1))
+            .unwrap();
+        println!(">>>>>>>>>> hourly:\n{}", hourly);
+
+        assert_eq!(
+            hourly.response_records,
+            vec![(
+                "2023-11-20T01:00:00Z".parse().unwrap(),
+                vec![2.5.into(), 3.0.into(), 10.0.into()]
+            )]
+        );
+        assert_eq!(Aggregation::for_header("t_min_2m_24h:C"), Aggregation::Min);
+        assert_eq!(Aggregation::for_header("global_rad_1h:J"), Aggregation::Sum);
+        assert_eq!(
+            Aggregation::for_header("sunshine_duration_1h:min"),
+            Aggregation::Sum
+        );
+        assert_eq!(Aggregation::for_header("global_rad:W"), Aggregation::Mean);
+    }
+}
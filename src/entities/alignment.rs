@@ -0,0 +1,163 @@
+use crate::connector_response::{RecordValue, ResponseBody, ResponseIndex, ResponseLocation};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Which valid dates `align` keeps.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AlignMode {
+    /// Valid dates and locations present in every response.
+    Intersection,
+    /// Valid dates and locations present in any response, padding the others with NaN.
+    Union,
+}
+
+/// Valid dates of every location, in order of first appearance of the locations.
+type TimeIndex = Vec<(Option<ResponseLocation>, BTreeSet<DateTime<Utc>>)>;
+
+fn time_index(body: &ResponseBody) -> TimeIndex {
+    body.split_by_location()
+        .into_iter()
+        .map(|(location, body)| {
+            let valid_dates = body
+                .response_records
+                .iter()
+                .map(|(index, _)| index.valid_date)
+                .collect();
+            (location, valid_dates)
+        })
+        .collect()
+}
+
+/// Reindexes responses with different time steps or ranges onto a shared index of locations and
+/// valid dates, chosen by `mode`. Every returned body keeps its columns and has the same records,
+/// grouped by location and sorted by valid date; values a body lacks become NaN.
+pub fn align(bodies: &[ResponseBody], mode: AlignMode) -> Vec<ResponseBody> {
+    let mut indices = bodies.iter().map(time_index);
+    let mut shared = indices.next().unwrap_or_default();
+    for index in indices {
+        match mode {
+            AlignMode::Intersection => {
+                shared.retain_mut(|(location, valid_dates)| {
+                    match index.iter().find(|(other, _)| other == location) {
+                        Some((_, other_dates)) => {
+                            valid_dates.retain(|valid_date| other_dates.contains(valid_date));
+                            true
+                        }
+                        None => false,
+                    }
+                });
+            }
+            AlignMode::Union => {
+                for (location, other_dates) in index {
+                    match shared.iter_mut().find(|(shared, _)| *shared == location) {
+                        Some((_, valid_dates)) => valid_dates.extend(other_dates),
+                        None => shared.push((location, other_dates)),
+                    }
+                }
+            }
+        }
+    }
+
+    bodies
+        .iter()
+        .map(|body| {
+            let num_columns = body.value_headers().len();
+            let values: HashMap<(Option<String>, DateTime<Utc>), &Vec<RecordValue>> = body
+                .response_records
+                .iter()
+                .map(|(index, values)| ((location_key(&index.location), index.valid_date), values))
+                .collect();
+            let mut aligned = ResponseBody {
+                response_headers: body.response_headers.clone(),
+                response_records: vec![],
+            };
+            for (location, valid_dates) in shared.iter() {
+                let key = location_key(location);
+                for valid_date in valid_dates {
+                    let mut record_values = match values.get(&(key.clone(), *valid_date)) {
+                        Some(values) => values.to_vec(),
+                        None => vec![],
+                    };
+                    record_values.resize(num_columns, RecordValue::Number(f64::NAN));
+                    let index = ResponseIndex {
+                        location: location.clone(),
+                        valid_date: *valid_date,
+                    };
+                    aligned.response_records.push((index, record_values));
+                }
+            }
+            aligned
+        })
+        .collect()
+}
+
+/// A hashable stand-in for a location, which holds floating point coordinates.
+fn location_key(location: &Option<ResponseLocation>) -> Option<String> {
+    location.as_ref().map(|location| format!("{:?}", location))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::alignment::{align, AlignMode};
+    use crate::connector_response::ResponseBody;
+
+    fn body(parameter: &str, records: &[(&str, f64)]) -> ResponseBody {
+        ResponseBody {
+            response_headers: vec!["validdate".to_string(), parameter.to_string()],
+            response_records: records
+                .iter()
+                .map(|(valid_date, value)| (valid_date.parse().unwrap(), vec![(*value).into()]))
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn align_time_indices() {
+        println!("\n##### align_time_indices:");
+
+        let hourly = body(
+            "t_2m:C",
+            &[
+                ("2023-11-20T00:00:00Z", 1.0),
+                ("2023-11-20T01:00:00Z", 2.0),
+                ("2023-11-20T02:00:00Z", 3.0),
+            ],
+        );
+        let two_hourly = body(
+            "precip_2h:mm",
+            &[("2023-11-20T02:00:00Z", 0.4), ("2023-11-20T04:00:00Z", 0.0)],
+        );
+
+        let intersection = align(
+            &[hourly.clone(), two_hourly.clone()],
+            AlignMode::Intersection,
+        );
+        println!(">>>>>>>>>> intersection: {:?}", intersection);
+
+        assert_eq!(
+            intersection[0],
+            body("t_2m:C", &[("2023-11-20T02:00:00Z", 3.0)])
+        );
+        assert_eq!(
+            intersection[1],
+            body("precip_2h:mm", &[("2023-11-20T02:00:00Z", 0.4)])
+        );
+
+        let union = align(&[hourly, two_hourly], AlignMode::Union);
+        println!(">>>>>>>>>> union: {:?}", union);
+
+        assert!(union.iter().all(|body| body.response_records.len() == 4));
+        assert_eq!(
+            union[1].response_records[3].0.valid_date,
+            "2023-11-20T04:00:00Z"
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap()
+        );
+        assert_eq!(union[0].column("t_2m:C").unwrap()[2], 3.0);
+        assert!(union[0].column("t_2m:C").unwrap()[3].is_nan());
+        assert!(union[1].column("precip_2h:mm").unwrap()[0].is_nan());
+        assert!(align(&[], AlignMode::Union).is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{RecordValue, ResponseBody};
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMillisecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use std::sync::Arc;
+
+/// Converts the time series into a UTC `validdate` timestamp column followed by one column per
+/// value header: float64 when every value is numeric, utf8 otherwise. Missing values are null.
+/// Multi-location responses get leading `lat` and `lon` columns, station responses a leading
+/// `station_id` column.
+impl TryFrom<&ResponseBody> for RecordBatch {
+    type Error = ConnectorError;
+
+    fn try_from(response_body: &ResponseBody) -> Result<Self, Self::Error> {
+        let (index_header, value_headers) = response_body
+            .response_headers
+            .split_first()
+            .ok_or_else(|| ConnectorError::LibraryError("Response has no headers.".to_string()))?;
+
+        let mut valid_dates: Vec<i64> = vec![];
+        for (index, values) in response_body.response_records.iter() {
+            if values.len() != value_headers.len() {
+                return Err(ConnectorError::LibraryError(format!(
+                    "Record {} has {} values for {} headers.",
+                    index,
+                    values.len(),
+                    value_headers.len()
+                )));
+            }
+            valid_dates.push(index.valid_date.timestamp_millis());
+        }
+
+        let mut fields = vec![];
+        let mut columns: Vec<ArrayRef> = vec![];
+        let coordinates: Vec<_> = response_body
+            .response_records
+            .iter()
+            .map(|(index, _)| index.coordinate())
+            .collect();
+        if coordinates.iter().any(Option::is_some) {
+            let nullable = coordinates.iter().any(Option::is_none);
+            fields.push(Field::new("lat", DataType::Float64, nullable));
+            columns.push(Arc::new(Float64Array::from_iter(
+                coordinates
+                    .iter()
+                    .map(|coordinate| coordinate.map(|(lat, _)| lat)),
+            )));
+            fields.push(Field::new("lon", DataType::Float64, nullable));
+            columns.push(Arc::new(Float64Array::from_iter(
+                coordinates
+                    .iter()
+                    .map(|coordinate| coordinate.map(|(_, lon)| lon)),
+            )));
+        }
+        let station_ids: Vec<_> = response_body
+            .response_records
+            .iter()
+            .map(|(index, _)| index.station_id())
+            .collect();
+        if station_ids.iter().any(Option::is_some) {
+            let nullable = station_ids.iter().any(Option::is_none);
+            fields.push(Field::new("station_id", DataType::Utf8, nullable));
+            columns.push(Arc::new(StringArray::from(station_ids)));
+        }
+        fields.push(Field::new(
+            index_header,
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ));
+        columns.push(Arc::new(
+            TimestampMillisecondArray::from(valid_dates).with_timezone("UTC"),
+        ));
+        for (n, header) in value_headers.iter().enumerate() {
+            let column = response_body
+                .response_records
+                .iter()
+                .map(|(_, values)| &values[n]);
+            let nullable = column.clone().any(|value| *value == RecordValue::Missing);
+            let numbers: Option<Vec<Option<f64>>> = column
+                .clone()
+                .map(|value| match value {
+                    RecordValue::Number(number) => Some(Some(*number)),
+                    RecordValue::Missing => Some(None),
+                    RecordValue::Text(_) => None,
+                })
+                .collect();
+            match numbers {
+                Some(numbers) => {
+                    fields.push(Field::new(header, DataType::Float64, nullable));
+                    columns.push(Arc::new(Float64Array::from(numbers)));
+                }
+                None => {
+                    fields.push(Field::new(header, DataType::Utf8, nullable));
+                    columns.push(Arc::new(StringArray::from_iter(column.map(
+                        |value| match value {
+                            RecordValue::Missing => None,
+                            value => Some(value.to_string()),
+                        },
+                    ))));
+                }
+            }
+        }
+
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{RecordValue, ResponseBody};
+    use arrow_array::{Array, Float64Array, RecordBatch, StringArray, TimestampMillisecondArray};
+
+    #[tokio::test]
+    async fn convert_to_record_batch() {
+        println!("\n##### convert_to_record_batch:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "precip_1h:mm".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![3.4.into(), 0.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![3.1.into(), 0.12.into()],
+                ),
+            ],
+        };
+
+        let batch = RecordBatch::try_from(&response_body).unwrap();
+        println!(">>>>>>>>>> schema: {:?}", batch.schema());
+
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 3);
+        let valid_dates = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(valid_dates.value(1), 1700442000000);
+        assert_eq!(valid_dates.timezone(), Some("UTC"));
+        let precip = batch
+            .column_by_name("precip_1h:mm")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(precip.value(1), 0.12);
+        assert_eq!(precip.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reject_ragged_records() {
+        println!("\n##### reject_ragged_records:");
+
+        let response_body = ResponseBody {
+            response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+            response_records: vec![(
+                "2023-11-20T00:00:00Z".parse().unwrap(),
+                vec![3.4.into(), 0.0.into()],
+            )],
+        };
+
+        assert!(RecordBatch::try_from(&response_body).is_err());
+    }
+
+    #[tokio::test]
+    async fn convert_text_column() {
+        println!("\n##### convert_text_column:");
+
+        let response_body = ResponseBody {
+            response_headers: vec!["validdate".to_string(), "metar".to_string()],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![RecordValue::Text("LSZH 200020Z".to_string())],
+                ),
+                ("2023-11-20T01:00:00Z".parse().unwrap(), vec![1.0.into()]),
+            ],
+        };
+
+        let batch = RecordBatch::try_from(&response_body).unwrap();
+        let metar = batch
+            .column_by_name("metar")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(metar.value(0), "LSZH 200020Z");
+        assert_eq!(metar.value(1), "1");
+    }
+
+    #[tokio::test]
+    async fn convert_missing_to_null() {
+        println!("\n##### convert_missing_to_null:");
+
+        let response_body = ResponseBody {
+            response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+            response_records: vec![
+                ("2023-11-20T00:00:00Z".parse().unwrap(), vec![3.4.into()]),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![RecordValue::Missing],
+                ),
+            ],
+        };
+
+        let batch = RecordBatch::try_from(&response_body).unwrap();
+        assert!(batch.schema().field(1).is_nullable());
+        let t_2m = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert!(t_2m.is_valid(0));
+        assert!(t_2m.is_null(1));
+    }
+}
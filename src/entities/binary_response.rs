@@ -0,0 +1,62 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Untouched payload of a query whose format is not parsed into a `ResponseBody` (images, NetCDF, ...).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BinaryResponse {
+    pub bytes: Vec<u8>,
+    pub content_type: Option<String>,
+    pub http_status_code: String,
+    pub http_status_message: String,
+}
+
+impl BinaryResponse {
+    /// Writes the payload as-is to `path`, replacing any existing file.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), ConnectorError> {
+        std::fs::write(path, &self.bytes)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+}
+
+#[cfg(feature = "image")]
+impl BinaryResponse {
+    /// Decodes the payload into an image, guessing the image format from its content.
+    pub fn to_image(&self) -> Result<image::DynamicImage, ConnectorError> {
+        image::load_from_memory(&self.bytes)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+}
+
+#[cfg(feature = "netcdf")]
+impl BinaryResponse {
+    /// Decodes a NetCDF payload, e.g. of `MeteomaticsConnector::query_netcdf`.
+    pub fn to_netcdf(&self) -> Result<crate::netcdf_data::NetCDFData, ConnectorError> {
+        crate::netcdf_data::NetCDFData::from_bytes(&self.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::binary_response::BinaryResponse;
+
+    #[tokio::test]
+    async fn write_payload_to_file() {
+        println!("\n##### write_payload_to_file:");
+
+        let binary_response = BinaryResponse {
+            bytes: vec![0x43, 0x44, 0x46, 0x01],
+            content_type: Some("application/netcdf".to_string()),
+            http_status_code: "200".to_string(),
+            http_status_message: "200 OK".to_string(),
+        };
+
+        let path = std::env::temp_dir().join("write_payload_to_file.nc");
+        binary_response.write_to_file(&path).unwrap();
+        println!(">>>>>>>>>> path: {:?}", path);
+
+        assert_eq!(std::fs::read(&path).unwrap(), binary_response.bytes);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+use crate::connector_response::{
+    Record, RecordValue, ResponseBody, ResponseHeader, ResponseIndex, ResponseLocation,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Values of one column: unboxed numbers as long as every value is a number, otherwise any
+/// `RecordValue`s.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Column {
+    Numbers(Vec<f64>),
+    Values(Vec<RecordValue>),
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Column::Numbers(vec![])
+    }
+}
+
+impl Column {
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Numbers(numbers) => numbers.len(),
+            Column::Values(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The numbers of a column without text or missing values.
+    pub fn numbers(&self) -> Option<&[f64]> {
+        match self {
+            Column::Numbers(numbers) => Some(numbers),
+            Column::Values(_) => None,
+        }
+    }
+
+    /// The value at position `n`.
+    pub fn get(&self, n: usize) -> Option<RecordValue> {
+        match self {
+            Column::Numbers(numbers) => numbers.get(n).map(|number| RecordValue::Number(*number)),
+            Column::Values(values) => values.get(n).cloned(),
+        }
+    }
+
+    /// Appends `value`, turning a numeric column into one of `RecordValue`s at the first value
+    /// that is not a number.
+    pub fn push(&mut self, value: RecordValue) {
+        match (&mut *self, value) {
+            (Column::Numbers(numbers), RecordValue::Number(number)) => numbers.push(number),
+            (Column::Numbers(numbers), value) => {
+                let mut values: Vec<RecordValue> =
+                    numbers.drain(..).map(RecordValue::Number).collect();
+                values.push(value);
+                *self = Column::Values(values);
+            }
+            (Column::Values(values), value) => values.push(value),
+        }
+    }
+}
+
+/// Response records stored as struct-of-arrays: the valid date and location of every record
+/// and one array per value column, in the order of `response_headers`. Numeric columns need a
+/// single allocation each, rather than one per record, and are handed out as slices.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ColumnarBody {
+    pub response_headers: ResponseHeader,
+    pub valid_dates: Vec<DateTime<Utc>>,
+    /// The location of every record; `None` throughout for a single location.
+    pub locations: Vec<Option<ResponseLocation>>,
+    pub columns: Vec<Column>,
+}
+
+impl ColumnarBody {
+    /// An empty body with the given headers, one column per value header.
+    pub fn new(response_headers: ResponseHeader) -> Self {
+        let num_columns = response_headers.len().saturating_sub(1);
+        Self {
+            response_headers,
+            valid_dates: vec![],
+            locations: vec![],
+            columns: vec![Column::default(); num_columns],
+        }
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.valid_dates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.valid_dates.is_empty()
+    }
+
+    /// The column with header `name`, e.g. `t_2m:C`.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        let n = self
+            .response_headers
+            .iter()
+            .skip(1)
+            .position(|header| header == name)?;
+        self.columns.get(n)
+    }
+
+    /// Appends a record. Missing trailing values are filled with `RecordValue::Missing`, values
+    /// beyond the headers are dropped.
+    pub fn push_record(
+        &mut self,
+        index: ResponseIndex,
+        values: impl IntoIterator<Item = RecordValue>,
+    ) {
+        self.valid_dates.push(index.valid_date);
+        self.locations.push(index.location);
+        let mut values = values.into_iter();
+        for column in self.columns.iter_mut() {
+            column.push(values.next().unwrap_or(RecordValue::Missing));
+        }
+    }
+
+    /// The record at position `n`.
+    pub fn record(&self, n: usize) -> Option<Record> {
+        Some(Record {
+            location: self.locations.get(n)?.clone(),
+            valid_date: *self.valid_dates.get(n)?,
+            values: self
+                .columns
+                .iter()
+                .map(|column| column.get(n).unwrap_or(RecordValue::Missing))
+                .collect(),
+        })
+    }
+}
+
+impl ResponseBody {
+    /// The records of this body as struct-of-arrays.
+    pub fn to_columnar(&self) -> ColumnarBody {
+        let mut columnar = ColumnarBody::new(self.response_headers.clone());
+        for (index, values) in self.response_records.iter() {
+            columnar.push_record(index.clone(), values.iter().cloned());
+        }
+        columnar
+    }
+}
+
+impl From<ColumnarBody> for ResponseBody {
+    fn from(columnar: ColumnarBody) -> Self {
+        let response_records = (0..columnar.len())
+            .filter_map(|n| columnar.record(n))
+            .map(|record| {
+                let index = ResponseIndex {
+                    location: record.location,
+                    valid_date: record.valid_date,
+                };
+                (index, record.values)
+            })
+            .collect();
+        ResponseBody {
+            response_headers: columnar.response_headers,
+            response_records,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::columnar::{Column, ColumnarBody};
+    use crate::connector_response::{RecordValue, ResponseBody};
+    use crate::lazy_response::LazyResponse;
+    use crate::missing_values::MissingValuePolicy;
+    use crate::test_support::MULTI_POINT_CSV;
+
+    #[tokio::test]
+    async fn store_records_by_column() {
+        println!("\n##### store_records_by_column:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "metar".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![3.4.into(), 1.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![3.1.into(), RecordValue::Text("LSZH".to_string())],
+                ),
+            ],
+        };
+
+        let columnar = response_body.to_columnar();
+        println!(">>>>>>>>>> columnar: {:?}", columnar);
+
+        assert_eq!(columnar.len(), 2);
+        assert_eq!(
+            columnar.column("t_2m:C").and_then(Column::numbers),
+            Some(&[3.4, 3.1][..])
+        );
+        assert_eq!(
+            columnar.column("metar"),
+            Some(&Column::Values(vec![
+                1.0.into(),
+                RecordValue::Text("LSZH".to_string())
+            ]))
+        );
+        assert_eq!(columnar.record(1).unwrap().values[0], 3.1.into());
+        assert!(columnar.column("t_2m:F").is_none());
+        assert_eq!(ResponseBody::from(columnar), response_body);
+
+        let lazy_response = LazyResponse::index(
+            MULTI_POINT_CSV.into(),
+            vec!["validdate".to_string(), "t_2m:C".to_string()],
+            MissingValuePolicy::default(),
+        )
+        .unwrap();
+        let columnar: ColumnarBody = lazy_response.parse_columnar().unwrap();
+        assert_eq!(
+            columnar.column("t_2m:C").and_then(Column::numbers),
+            Some(&[3.4, 3.1, 4.2, 4.0][..])
+        );
+        assert_eq!(
+            columnar.record(2).unwrap().location,
+            lazy_response.record(2).unwrap().unwrap().location
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::connector_response::{RecordValue, ResponseBody, ResponseLocation};
+use crate::derived_quantities::parameter_and_unit;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Forecasts and observations of one parameter at one location, paired by valid date.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PairedSeries {
+    /// Header of the forecast column, e.g. `t_2m:C:ecmwf-ifs`.
+    pub parameter: String,
+    pub location: Option<ResponseLocation>,
+    pub valid_dates: Vec<DateTime<Utc>>,
+    pub forecasts: Vec<f64>,
+    pub observations: Vec<f64>,
+}
+
+impl PairedSeries {
+    pub fn len(&self) -> usize {
+        self.valid_dates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.valid_dates.is_empty()
+    }
+
+    /// Forecast minus observation at every valid date.
+    pub fn errors(&self) -> Vec<f64> {
+        self.forecasts
+            .iter()
+            .zip(self.observations.iter())
+            .map(|(forecast, observation)| forecast - observation)
+            .collect()
+    }
+}
+
+impl ResponseBody {
+    /// Pairs the forecasts of this body with `observations`: a column is paired with the
+    /// observation column of the same parameter and unit, ignoring a model, so that
+    /// `t_2m:C:ecmwf-ifs` is paired with `t_2m:C`, and a record with the observation of the same
+    /// location and valid date. Records without a numeric value on either side are left out.
+    /// Returns one series per paired column and location, in order of the columns and of the
+    /// first appearance of the locations.
+    pub fn pair_with_observations(&self, observations: &ResponseBody) -> Vec<PairedSeries> {
+        let forecast_locations = self.split_by_location();
+        let observed_locations = observations.split_by_location();
+        let mut paired = vec![];
+        for (column, header) in self.value_headers().iter().enumerate() {
+            let Some(observed_column) = observations
+                .value_headers()
+                .iter()
+                .position(|observed| parameter_and_unit(observed) == parameter_and_unit(header))
+            else {
+                continue;
+            };
+            for (location, forecast) in forecast_locations.iter() {
+                let Some((_, observed)) = observed_locations
+                    .iter()
+                    .find(|(observed_location, _)| observed_location == location)
+                else {
+                    continue;
+                };
+                let mut series = PairedSeries {
+                    parameter: header.clone(),
+                    location: location.clone(),
+                    ..Default::default()
+                };
+                for (index, values) in forecast.response_records.iter() {
+                    let Some(forecast) = values.get(column).and_then(RecordValue::as_f64) else {
+                        continue;
+                    };
+                    let observation = observed
+                        .response_records
+                        .iter()
+                        .find(|(observed_index, _)| observed_index.valid_date == index.valid_date)
+                        .and_then(|(_, values)| values.get(observed_column)?.as_f64());
+                    if let Some(observation) = observation {
+                        if !forecast.is_nan() && !observation.is_nan() {
+                            series.valid_dates.push(index.valid_date);
+                            series.forecasts.push(forecast);
+                            series.observations.push(observation);
+                        }
+                    }
+                }
+                paired.push(series);
+            }
+        }
+        paired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{RecordValue, ResponseBody};
+
+    #[tokio::test]
+    async fn pair_forecasts_with_observations() {
+        println!("\n##### pair_forecasts_with_observations:");
+
+        let forecast = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C:ecmwf-ifs".to_string(),
+                "precip_1h:mm:ecmwf-ifs".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![3.4.into(), 0.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![3.1.into(), 0.1.into()],
+                ),
+                (
+                    "2023-11-20T02:00:00Z".parse().unwrap(),
+                    vec![2.9.into(), 0.3.into()],
+                ),
+            ],
+        };
+        let observation = ResponseBody {
+            response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+            response_records: vec![
+                ("2023-11-20T00:00:00Z".parse().unwrap(), vec![3.0.into()]),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![RecordValue::Missing],
+                ),
+                ("2023-11-20T02:00:00Z".parse().unwrap(), vec![3.4.into()]),
+            ],
+        };
+
+        let paired = forecast.pair_with_observations(&observation);
+        println!(">>>>>>>>>> paired: {:?}", paired);
+
+        assert_eq!(paired.len(), 1);
+        assert_eq!(paired[0].parameter, "t_2m:C:ecmwf-ifs");
+        assert_eq!(paired[0].location, None);
+        assert_eq!(
+            paired[0].valid_dates,
+            vec![
+                "2023-11-20T00:00:00Z"
+                    .parse::<chrono::DateTime<chrono::Utc>>()
+                    .unwrap(),
+                "2023-11-20T02:00:00Z".parse().unwrap()
+            ]
+        );
+        assert_eq!(paired[0].forecasts, vec![3.4, 2.9]);
+        assert_eq!(paired[0].observations, vec![3.0, 3.4]);
+        let errors: Vec<f64> = paired[0]
+            .errors()
+            .iter()
+            .map(|error| (error * 10.0).round() / 10.0)
+            .collect();
+        assert_eq!(errors, vec![0.4, -0.5]);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::format::Format;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,9 +8,62 @@ pub enum ConnectorError {
     #[error("API error")]
     ApiError { source: reqwest::Error },
 
-    /// HTTP response error.
+    /// HTTP response error with a status none of the other variants covers, e.g. a redirect.
     #[error("HTTP error: `{0}`, `{1}`, {2}`")]
-    HttpError(String, String, reqwest::StatusCode),
+    HttpError(String, ApiErrorDetail, reqwest::StatusCode),
+
+    /// The credentials were rejected or lack access to the requested data.
+    #[error("Authentication failed ({status}): `{detail}`")]
+    Auth {
+        status: reqwest::StatusCode,
+        detail: ApiErrorDetail,
+    },
+
+    /// Too many requests; wait `retry_after`, as requested by the API, before trying again.
+    #[error("Rate limited, retry after {retry_after:?}: `{detail}`")]
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+        detail: ApiErrorDetail,
+    },
+
+    /// The circuit breaker is open after repeated failures; no request was sent.
+    #[error("Circuit open after repeated failures, retry after {retry_after:?}")]
+    CircuitOpen { retry_after: std::time::Duration },
+
+    /// The query was rejected before sending, e.g. an empty time list or an invalid coordinate.
+    /// A query the API rejects is `Rejected`.
+    #[error("Invalid query: `{0}`")]
+    InvalidQuery(String),
+
+    /// The API rejected the query with `400 Bad Request`, e.g. for an unknown parameter or a date
+    /// out of range, `404 Not Found` or `422 Unprocessable Entity`.
+    #[error("Query rejected ({status}): `{detail}`")]
+    Rejected {
+        status: reqwest::StatusCode,
+        detail: ApiErrorDetail,
+    },
+
+    /// The request did not complete within the configured timeout.
+    #[error("Request timed out")]
+    Timeout { source: reqwest::Error },
+
+    /// No part of the response body arrived within the read timeout of `seconds`.
+    #[error("Response body stalled for {seconds} s")]
+    ReadTimeout { seconds: u64 },
+
+    /// The response body could not be read as `format`.
+    #[error("Cannot parse {format} response: {source}")]
+    Parse {
+        format: Format,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The API failed with a 5xx status.
+    #[error("Server error ({status}): `{detail}`")]
+    Server {
+        status: reqwest::StatusCode,
+        detail: ApiErrorDetail,
+    },
 
     /// Library error.
     #[error("Library error: `{0}`")]
@@ -16,5 +71,162 @@ pub enum ConnectorError {
 
     /// Generic error.
     #[error(transparent)]
-    GenericError(#[from] Box<dyn std::error::Error>),
+    GenericError(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl ConnectorError {
+    /// Whether repeating the same request may succeed: rate limits, server errors, timeouts and
+    /// failed connections.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ConnectorError::RateLimited { .. }
+            | ConnectorError::Server { .. }
+            | ConnectorError::Timeout { .. }
+            | ConnectorError::ReadTimeout { .. } => true,
+            #[cfg(not(target_arch = "wasm32"))]
+            ConnectorError::ApiError { source } => source.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+/// Error body of a non-200 API response, e.g. for an unknown parameter or an exceeded area.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ApiErrorDetail {
+    /// Human-readable description of the error.
+    pub message: String,
+    /// The parameter the message refers to, if it quotes one.
+    pub parameter: Option<String>,
+    /// The limit the message refers to, e.g. `1000000 points`, if it states one.
+    pub limit: Option<String>,
+    /// The body as received.
+    pub body: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: Option<String>,
+    error: Option<String>,
+}
+
+impl ApiErrorDetail {
+    /// Parses a JSON body of the form `{"status":"error","message":"..."}`, falling back to the
+    /// body as plain text.
+    pub fn parse(body: &str) -> Self {
+        let message = serde_json::from_str::<ErrorBody>(body)
+            .ok()
+            .and_then(|error_body| error_body.message.or(error_body.error))
+            .unwrap_or_else(|| body.trim().to_string());
+        ApiErrorDetail {
+            parameter: quoted_after(&message, "parameter"),
+            limit: words_after(&message, "limit of"),
+            message,
+            body: body.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiErrorDetail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// The first `'`-, `"`- or backtick-quoted text after `keyword`, ignoring ASCII case.
+fn quoted_after(message: &str, keyword: &str) -> Option<String> {
+    let start = message.to_ascii_lowercase().find(keyword)? + keyword.len();
+    let rest = &message[start..];
+    let open = rest.find(['\'', '"', '`'])?;
+    let quote = rest[open..].chars().next()?;
+    let quoted = &rest[open + 1..];
+    Some(quoted[..quoted.find(quote)?].to_string())
+}
+
+/// The two words after `phrase`, e.g. `1000000 points` in `exceeds the limit of 1000000 points.`
+fn words_after(message: &str, phrase: &str) -> Option<String> {
+    let start = message.to_ascii_lowercase().find(phrase)? + phrase.len();
+    let words: Vec<&str> = message[start..]
+        .split_whitespace()
+        .take(2)
+        .map(|word| word.trim_end_matches(['.', ',', ';', ')']))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_error::{ApiErrorDetail, ConnectorError};
+    use crate::format::Format;
+    use reqwest::StatusCode;
+
+    #[tokio::test]
+    async fn parse_error_bodies() {
+        println!("\n##### parse_error_bodies:");
+
+        let detail = ApiErrorDetail::parse(
+            r#"{"status":"error","message":"Parameter 't_2mm:C' is not known."}"#,
+        );
+        println!(">>>>>>>>>> detail: {:?}", detail);
+        assert_eq!(detail.message, "Parameter 't_2mm:C' is not known.");
+        assert_eq!(detail.parameter.as_deref(), Some("t_2mm:C"));
+        assert_eq!(detail.limit, None);
+
+        let detail = ApiErrorDetail::parse(
+            "Requested area exceeds the limit of 1000000 points, please reduce the resolution.\n",
+        );
+        assert_eq!(
+            detail.message,
+            "Requested area exceeds the limit of 1000000 points, please reduce the resolution."
+        );
+        assert_eq!(detail.limit.as_deref(), Some("1000000 points"));
+        assert_eq!(detail.parameter, None);
+        assert_eq!(detail.to_string(), detail.message);
+
+        assert_eq!(ApiErrorDetail::parse("").message, "");
+    }
+
+    #[tokio::test]
+    async fn classify_retryable_errors() {
+        println!("\n##### classify_retryable_errors:");
+
+        let detail = ApiErrorDetail::parse("Service unavailable.");
+        assert!(ConnectorError::Server {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            detail: detail.clone(),
+        }
+        .is_retryable());
+        assert!(ConnectorError::RateLimited {
+            retry_after: None,
+            detail: detail.clone(),
+        }
+        .is_retryable());
+        assert!(ConnectorError::ReadTimeout { seconds: 30 }.is_retryable());
+        assert!(!ConnectorError::Auth {
+            status: StatusCode::UNAUTHORIZED,
+            detail,
+        }
+        .is_retryable());
+        assert!(!ConnectorError::Rejected {
+            status: StatusCode::BAD_REQUEST,
+            detail: ApiErrorDetail::parse("Parameter 't_2mm:C' is not known."),
+        }
+        .is_retryable());
+        assert!(
+            !ConnectorError::InvalidQuery("Line must have at least two points.".to_string())
+                .is_retryable()
+        );
+        let parse_error = ConnectorError::Parse {
+            format: Format::CSV,
+            source: "missing header".into(),
+        };
+        assert!(!parse_error.is_retryable());
+        assert_eq!(
+            parse_error.to_string(),
+            "Cannot parse csv response: missing header"
+        );
+    }
 }
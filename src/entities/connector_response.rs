@@ -1,17 +1,184 @@
-use csv::Reader;
+use crate::connector_error::ConnectorError;
+use crate::locations::Coordinate;
+use crate::missing_values::{MissingValuePolicy, MISSING_VALUE_SENTINELS};
+use crate::units::Unit;
+use chrono::{DateTime, SecondsFormat, Utc};
+use csv::{Reader, ReaderBuilder, StringRecord};
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::io::Read;
+use std::str::FromStr;
 
-#[derive(Clone, Debug, PartialEq)]
+/// Headers of the columns that index the records rather than hold values.
+const INDEX_HEADERS: [&str; 4] = ["lat", "lon", "station_id", "validdate"];
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct ConnectorResponse {
     pub response_body: ResponseBody,
     pub http_status_code: String,
     pub http_status_message: String,
+    #[serde(default)]
+    pub metadata: ResponseMetadata,
+}
+
+/// Details of the HTTP exchange behind a response, for logging and debugging.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ResponseMetadata {
+    /// Requested URL, with any credentials redacted.
+    pub request_url: String,
+    pub response_headers: Vec<(String, String)>,
+    pub content_length: Option<u64>,
+    /// Time from sending the request until the body was parsed.
+    pub elapsed: std::time::Duration,
+}
+
+/// Parses a number like `str::parse::<f64>` does, after trimming whitespace, with a faster
+/// parser for the millions of cells of a large download, see `benches/parsing.rs`.
+pub(crate) fn parse_f64(raw: &str) -> Result<f64, fast_float2::Error> {
+    fast_float2::parse(raw.trim())
+}
+
+/// Value of a single response cell: numeric for most parameters, text for e.g. METAR reports.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum RecordValue {
+    Number(f64),
+    Text(String),
+    /// Data not available, see `MissingValuePolicy`.
+    Missing,
+}
+
+impl RecordValue {
+    /// Parses a raw cell, keeping anything that is not a number as text.
+    pub fn parse(raw: &str) -> Self {
+        match parse_f64(raw) {
+            Ok(number) => RecordValue::Number(number),
+            Err(_) => RecordValue::Text(raw.to_string()),
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            RecordValue::Number(number) => Some(*number),
+            RecordValue::Text(_) | RecordValue::Missing => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            RecordValue::Number(_) | RecordValue::Missing => None,
+            RecordValue::Text(text) => Some(text),
+        }
+    }
+}
+
+impl From<f64> for RecordValue {
+    fn from(number: f64) -> Self {
+        RecordValue::Number(number)
+    }
+}
+
+impl std::fmt::Display for RecordValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordValue::Number(number) => write!(f, "{}", number),
+            RecordValue::Text(text) => write!(f, "{}", text),
+            RecordValue::Missing => Ok(()),
+        }
+    }
+}
+
+/// Location a record belongs to in a multi-location or station response.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ResponseLocation {
+    Coordinate(Coordinate),
+    Station(String),
+}
+
+impl std::fmt::Display for ResponseLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResponseLocation::Coordinate((lat, lon)) => write!(f, "{},{}", lat, lon),
+            ResponseLocation::Station(station_id) => write!(f, "{}", station_id),
+        }
+    }
+}
+
+/// Key of a response record: the valid date, plus the location in multi-location and station
+/// responses.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ResponseIndex {
+    pub location: Option<ResponseLocation>,
+    pub valid_date: DateTime<Utc>,
+}
+
+impl ResponseIndex {
+    pub fn coordinate(&self) -> Option<Coordinate> {
+        match self.location {
+            Some(ResponseLocation::Coordinate(coordinate)) => Some(coordinate),
+            _ => None,
+        }
+    }
+
+    pub fn station_id(&self) -> Option<&str> {
+        match &self.location {
+            Some(ResponseLocation::Station(station_id)) => Some(station_id),
+            _ => None,
+        }
+    }
+}
+
+impl From<DateTime<Utc>> for ResponseIndex {
+    fn from(valid_date: DateTime<Utc>) -> Self {
+        ResponseIndex {
+            location: None,
+            valid_date,
+        }
+    }
+}
+
+impl FromStr for ResponseIndex {
+    type Err = Box<dyn std::error::Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_valid_date(s).map(ResponseIndex::from)
+    }
+}
+
+impl std::fmt::Display for ResponseIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{} ", location)?;
+        }
+        write!(
+            f,
+            "{}",
+            self.valid_date.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )
+    }
 }
 
 pub type ResponseHeader = Vec<String>;
-pub type ResponseRecord = Vec<(String, Vec<f64>)>;
+pub type ResponseRecord = Vec<(ResponseIndex, Vec<RecordValue>)>;
+
+/// A single record of a time series, owning its values.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Record {
+    pub location: Option<ResponseLocation>,
+    pub valid_date: DateTime<Utc>,
+    pub values: Vec<RecordValue>,
+}
+
+/// A single record of a time series, borrowed from a `ResponseBody`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecordRef<'a> {
+    pub location: Option<&'a ResponseLocation>,
+    pub valid_date: DateTime<Utc>,
+    pub values: &'a [RecordValue],
+}
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct ResponseBody {
     pub response_headers: ResponseHeader,
     pub response_records: ResponseRecord,
@@ -29,39 +196,1122 @@ impl ResponseBody {
         self.response_headers.push(header);
     }
 
+    /// Populates the records from a CSV reader whose header line is the only line that does not
+    /// hold a record.
     pub async fn populate_records<R: Read>(
         &mut self,
         rdr: &mut Reader<R>,
-        header_num_elements: usize,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        for record in rdr.records().skip(header_num_elements) {
-            let record = record?;
-            let mut index: String = "".to_string();
-            let mut values: Vec<f64> = vec![];
-            // for (&_, value) in self.response_headers.iter().zip(record.iter()) {
-            for n in 0..record.len() {
-                if n == 0 {
-                    index = record.get(n).unwrap().to_string();
-                } else {
-                    let value = record.get(n).unwrap();
-                    values.push(value.parse::<f64>().unwrap());
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let location_columns = LocationColumns::from_headers(rdr.headers()?);
+        for record in rdr.records() {
+            self.push_csv_record(&record?, location_columns)?;
+        }
+        Ok(())
+    }
+
+    /// Pushes a CSV record, reading the leading `lat` and `lon` or `station_id` columns of
+    /// multi-location and station responses into the index.
+    fn push_csv_record(
+        &mut self,
+        record: &StringRecord,
+        location_columns: LocationColumns,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (index, fields) = location_columns.parse_index(record)?;
+        let values: Vec<RecordValue> = fields.map(RecordValue::parse).collect();
+        self.response_records.push((index, values));
+        Ok(())
+    }
+
+    /// Populates the records from a Meteomatics `bin` payload.
+    ///
+    /// For every requested coordinate the payload holds the number of dates followed by, per date,
+    /// the date as seconds since the epoch and `num_columns` values, all little-endian. When more
+    /// than one of `coordinates` was requested, the records are indexed by their coordinate.
+    pub fn populate_records_from_bin(
+        &mut self,
+        bytes: &[u8],
+        num_columns: usize,
+        coordinates: &[Coordinate],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut rdr = BinReader { bytes };
+        let mut block: usize = 0;
+        while !rdr.is_empty() {
+            let location = if coordinates.len() > 1 {
+                Some(ResponseLocation::Coordinate(
+                    *coordinates
+                        .get(block)
+                        .ok_or("More coordinates in binary response than requested")?,
+                ))
+            } else {
+                None
+            };
+            block += 1;
+            let num_dates = rdr.read_i32()?;
+            for _ in 0..num_dates {
+                let seconds = rdr.read_f64()?;
+                let valid_date = DateTime::<Utc>::from_timestamp(seconds as i64, 0)
+                    .ok_or(format!("Invalid date in binary response: {}", seconds))?;
+                let index = ResponseIndex {
+                    location: location.clone(),
+                    valid_date,
+                };
+                let mut values: Vec<RecordValue> = vec![];
+                for _ in 0..num_columns {
+                    values.push(RecordValue::Number(rdr.read_f64()?));
+                }
+                self.response_records.push((index, values));
+            }
+        }
+        Ok(())
+    }
+
+    /// Populates the records from a Meteomatics XML document, one record per location and `value`
+    /// date.
+    ///
+    /// Values are placed in the column of their enclosing `parameter` element, in document order.
+    /// When the document holds more than one `location` element per parameter, the records are
+    /// indexed by their coordinate or station and grouped by location.
+    pub fn populate_records_from_xml(
+        &mut self,
+        xml: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut reader = quick_xml::Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut num_parameters: usize = 0;
+        let mut locations: Vec<Option<ResponseLocation>> = vec![];
+        let mut location: usize = 0;
+        let mut date: Option<String> = None;
+        let mut positions: HashMap<(usize, String), usize> = HashMap::new();
+        let mut rows: Vec<(usize, String, Vec<Option<RecordValue>>)> = vec![];
+        loop {
+            match reader.read_event()? {
+                Event::Start(element) => match element.name().as_ref() {
+                    b"parameter" => num_parameters += 1,
+                    b"location" => {
+                        let mut lat = None;
+                        let mut lon = None;
+                        let mut station_id = None;
+                        for attribute in element.attributes() {
+                            let attribute = attribute?;
+                            let value = attribute.unescape_value()?;
+                            match attribute.key.as_ref() {
+                                b"lat" => lat = Some(parse_f64(&value)?),
+                                b"lon" => lon = Some(parse_f64(&value)?),
+                                b"station_id" => station_id = Some(value.to_string()),
+                                _ => {}
+                            }
+                        }
+                        let element_location = match (lat, lon, station_id) {
+                            (_, _, Some(station_id)) => Some(ResponseLocation::Station(station_id)),
+                            (Some(lat), Some(lon), None) => {
+                                Some(ResponseLocation::Coordinate((lat, lon)))
+                            }
+                            _ => None,
+                        };
+                        location = match locations
+                            .iter()
+                            .position(|known| *known == element_location)
+                        {
+                            Some(position) => position,
+                            None => {
+                                locations.push(element_location);
+                                locations.len() - 1
+                            }
+                        };
+                    }
+                    b"value" => {
+                        for attribute in element.attributes() {
+                            let attribute = attribute?;
+                            if attribute.key.as_ref() == b"date" {
+                                date = Some(attribute.unescape_value()?.to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Text(text) => {
+                    if let Some(date) = date.take() {
+                        let value = RecordValue::parse(&text.unescape()?);
+                        let column = num_parameters
+                            .checked_sub(1)
+                            .ok_or("Value outside of a parameter element")?;
+                        let position =
+                            *positions
+                                .entry((location, date.clone()))
+                                .or_insert_with(|| {
+                                    rows.push((location, date, vec![]));
+                                    rows.len() - 1
+                                });
+                        let values = &mut rows[position].2;
+                        if values.len() <= column {
+                            values.resize(column + 1, None);
+                        }
+                        values[column] = Some(value);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        rows.sort_by_key(|(location, _, _)| *location);
+        let multi_location = locations.len() > 1;
+        for (location, date, values) in rows {
+            let mut record_values: Vec<RecordValue> = vec![];
+            for column in 0..num_parameters {
+                let value = values.get(column).cloned().flatten();
+                record_values
+                    .push(value.ok_or(format!("Missing value for {} in column {}", date, column))?);
+            }
+            let mut index = date.parse::<ResponseIndex>()?;
+            if multi_location {
+                index.location = locations[location].clone();
+            }
+            self.response_records.push((index, record_values));
+        }
+        Ok(())
+    }
+
+    /// Maps sentinel values and empty cells of all records according to `policy`.
+    pub fn apply_missing_value_policy(&mut self, policy: MissingValuePolicy) {
+        for (_, values) in self.response_records.iter_mut() {
+            for value in values.iter_mut() {
+                *value = policy.apply(std::mem::replace(value, RecordValue::Missing));
+            }
+        }
+    }
+
+    /// Iterates over the records in response order.
+    pub fn iter(&self) -> <&ResponseBody as IntoIterator>::IntoIter {
+        self.into_iter()
+    }
+
+    /// Values of the column with header `name`, or `None` if there is no such column or it holds
+    /// text values. Missing values are returned as `f64::NAN`.
+    pub fn column(&self, name: &str) -> Option<Vec<f64>> {
+        let n = self
+            .value_headers()
+            .iter()
+            .position(|header| header == name)?;
+        self.response_records
+            .iter()
+            .map(|(_, values)| match values.get(n) {
+                Some(RecordValue::Missing) => Some(f64::NAN),
+                value => value.and_then(RecordValue::as_f64),
+            })
+            .collect()
+    }
+
+    /// The values of every record by valid date, in order of the value headers. Missing and text
+    /// values become `f64::NAN`. Records of different locations with the same valid date replace
+    /// each other, so split multi-location responses with `split_by_location` first.
+    pub fn to_map(&self) -> BTreeMap<DateTime<Utc>, Vec<f64>> {
+        self.response_records
+            .iter()
+            .map(|(index, values)| {
+                let values = values
+                    .iter()
+                    .map(|value| value.as_f64().unwrap_or(f64::NAN))
+                    .collect();
+                (index.valid_date, values)
+            })
+            .collect()
+    }
+
+    /// The values of the column with header `name` by valid date, or `None` as for `column`.
+    pub fn column_map(&self, name: &str) -> Option<BTreeMap<DateTime<Utc>, f64>> {
+        let valid_dates = self
+            .response_records
+            .iter()
+            .map(|(index, _)| index.valid_date);
+        Some(valid_dates.zip(self.column(name)?).collect())
+    }
+
+    /// Converts the numbers of the column with header `name`, e.g. `t_2m:C`, to `unit` and
+    /// renames the column accordingly, e.g. to `t_2m:F`. Missing values are left as they are.
+    pub fn convert_column(&mut self, name: &str, unit: Unit) -> Result<(), ConnectorError> {
+        let n = self
+            .value_headers()
+            .iter()
+            .position(|header| header == name)
+            .ok_or_else(|| ConnectorError::LibraryError(format!("No column {}.", name)))?;
+        let mut parts = name.splitn(3, ':');
+        let parameter = parts.next().unwrap_or_default();
+        let from: Unit = parts
+            .next()
+            .ok_or_else(|| ConnectorError::LibraryError(format!("No unit in {}.", name)))?
+            .parse()?;
+        let source = parts.next();
+        for (_, values) in self.response_records.iter_mut() {
+            if let Some(RecordValue::Number(number)) = values.get_mut(n) {
+                if !MISSING_VALUE_SENTINELS.contains(number) {
+                    *number = from.convert(*number, unit)?;
+                }
+            }
+        }
+        let offset = self.response_headers.len() - self.value_headers().len();
+        self.response_headers[offset + n] = match source {
+            Some(source) => format!("{}:{}:{}", parameter, unit, source),
+            None => format!("{}:{}", parameter, unit),
+        };
+        Ok(())
+    }
+
+    /// Iterates over the value columns as pairs of header and values, in header order.
+    pub fn columns(&self) -> impl Iterator<Item = (&str, Vec<&RecordValue>)> + '_ {
+        self.value_headers()
+            .iter()
+            .enumerate()
+            .map(move |(n, header)| {
+                let values = self
+                    .response_records
+                    .iter()
+                    .filter_map(|(_, values)| values.get(n))
+                    .collect();
+                (header.as_str(), values)
+            })
+    }
+
+    /// Headers of the value columns, i.e. without the leading index headers: `validdate`, after
+    /// `lat` and `lon` or `station_id` in multi-location and station responses.
+    pub(crate) fn value_headers(&self) -> &[String] {
+        let num_index_headers = self
+            .response_headers
+            .iter()
+            .take_while(|header| INDEX_HEADERS.contains(&header.as_str()))
+            .count()
+            .max(1);
+        self.response_headers
+            .get(num_index_headers..)
+            .unwrap_or_default()
+    }
+
+    /// Splits a multi-location or station response into one response per location, in order of
+    /// first appearance. A single-location response is returned as is, with location `None`.
+    pub fn split_by_location(&self) -> Vec<(Option<ResponseLocation>, ResponseBody)> {
+        let mut split: Vec<(Option<ResponseLocation>, ResponseBody)> = vec![];
+        for (index, values) in self.response_records.iter() {
+            let position = match split
+                .iter()
+                .position(|(location, _)| *location == index.location)
+            {
+                Some(position) => position,
+                None => {
+                    let body = ResponseBody {
+                        response_headers: self.response_headers.clone(),
+                        response_records: vec![],
+                    };
+                    split.push((index.location.clone(), body));
+                    split.len() - 1
                 }
+            };
+            split[position]
+                .1
+                .response_records
+                .push((index.clone(), values.clone()));
+        }
+        split
+    }
+
+    /// Checks that the valid dates of the records of every location are strictly increasing, as
+    /// expected of a time series.
+    pub fn validate_monotonic(&self) -> Result<(), ConnectorError> {
+        let mut latest: Vec<&ResponseIndex> = vec![];
+        for (index, _) in self.response_records.iter() {
+            match latest
+                .iter_mut()
+                .find(|previous| previous.location == index.location)
+            {
+                Some(previous) => {
+                    if index.valid_date <= previous.valid_date {
+                        return Err(ConnectorError::LibraryError(format!(
+                            "Valid dates are not increasing: {} follows {}.",
+                            index, previous
+                        )));
+                    }
+                    *previous = index;
+                }
+                None => latest.push(index),
             }
-            let row: (String, Vec<f64>) = (index, values);
-            self.response_records.push(row);
         }
         Ok(())
     }
 }
 
+/// Location columns leading the CSV records of multi-location and station responses.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum LocationColumns {
+    None,
+    Coordinate,
+    Station,
+}
+
+impl LocationColumns {
+    pub(crate) fn from_headers(headers: &StringRecord) -> Self {
+        match (headers.get(0), headers.get(1)) {
+            (Some("lat"), Some("lon")) => LocationColumns::Coordinate,
+            (Some("station_id"), _) => LocationColumns::Station,
+            _ => LocationColumns::None,
+        }
+    }
+
+    /// Number of fields before the `validdate` field.
+    pub(crate) fn count(&self) -> usize {
+        match self {
+            LocationColumns::None => 0,
+            LocationColumns::Coordinate => 2,
+            LocationColumns::Station => 1,
+        }
+    }
+
+    /// Reads the location and valid date leading `record`, returning the remaining value fields.
+    pub(crate) fn parse_index<'r>(
+        &self,
+        record: &'r StringRecord,
+    ) -> Result<(ResponseIndex, csv::StringRecordIter<'r>), Box<dyn std::error::Error + Send + Sync>>
+    {
+        let mut fields = record.iter();
+        let location = match self {
+            LocationColumns::None => None,
+            LocationColumns::Coordinate => {
+                let lat = parse_f64(fields.next().ok_or("Missing lat column")?)?;
+                let lon = parse_f64(fields.next().ok_or("Missing lon column")?)?;
+                Some(ResponseLocation::Coordinate((lat, lon)))
+            }
+            LocationColumns::Station => {
+                let station_id = fields.next().ok_or("Missing station_id column")?.trim();
+                Some(ResponseLocation::Station(station_id.to_string()))
+            }
+        };
+        let valid_date = parse_valid_date(fields.next().ok_or("Missing validdate column")?)?;
+        let index = ResponseIndex {
+            location,
+            valid_date,
+        };
+        Ok((index, fields))
+    }
+}
+
+fn parse_valid_date(
+    index: &str,
+) -> Result<DateTime<Utc>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(DateTime::parse_from_rfc3339(index.trim())?.with_timezone(&Utc))
+}
+
+/// Incremental parser for `;`-separated CSV bodies that arrive in chunks.
+///
+/// Only complete lines are parsed, so a large response never has to be held in memory at once.
+/// The first line is the header line; every line after it is a record. If the body to populate
+/// already has headers, the header line must have as many value columns, e.g. one per parameter
+/// and ensemble member.
+pub struct CsvChunkParser {
+    pending: Vec<u8>,
+    header_read: bool,
+    location_columns: LocationColumns,
+}
+
+impl Default for CsvChunkParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvChunkParser {
+    pub fn new() -> Self {
+        Self {
+            pending: vec![],
+            header_read: false,
+            location_columns: LocationColumns::None,
+        }
+    }
+
+    /// Parses every line completed by `chunk` into records of `response_body`.
+    pub fn feed(
+        &mut self,
+        response_body: &mut ResponseBody,
+        chunk: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.pending.extend_from_slice(chunk);
+        if let Some(end) = self.pending.iter().rposition(|&byte| byte == b'\n') {
+            let lines: Vec<u8> = self.pending.drain(..=end).collect();
+            self.parse_lines(response_body, &lines)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the trailing line, if the body did not end with a newline.
+    pub fn finish(
+        mut self,
+        response_body: &mut ResponseBody,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let lines = std::mem::take(&mut self.pending);
+        self.parse_lines(response_body, &lines)
+    }
+
+    fn parse_lines(
+        &mut self,
+        response_body: &mut ResponseBody,
+        lines: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut rdr = ReaderBuilder::new()
+            .delimiter(b';')
+            .has_headers(false)
+            .from_reader(lines);
+        for record in rdr.records() {
+            let record = record?;
+            if !self.header_read {
+                self.header_read = true;
+                self.location_columns = LocationColumns::from_headers(&record);
+                check_value_columns(
+                    &record,
+                    self.location_columns,
+                    response_body.value_headers(),
+                )?;
+            } else {
+                response_body.push_csv_record(&record, self.location_columns)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a CSV header line has a value column for every one of `value_headers`, unless
+/// there are none yet.
+pub(crate) fn check_value_columns(
+    header_line: &StringRecord,
+    location_columns: LocationColumns,
+    value_headers: &[String],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let expected = value_headers.len();
+    let found = header_line
+        .len()
+        .saturating_sub(location_columns.count() + 1);
+    if expected > 0 && found != expected {
+        return Err(format!(
+            "CSV header line has {} value columns, expected {}: {}",
+            found,
+            expected,
+            value_headers.join(",")
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Little-endian cursor over a Meteomatics `bin` payload.
+struct BinReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BinReader<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take<const N: usize>(
+        &mut self,
+    ) -> Result<[u8; N], Box<dyn std::error::Error + Send + Sync>> {
+        if self.bytes.len() < N {
+            return Err("Unexpected end of binary response".into());
+        }
+        let (head, tail) = self.bytes.split_at(N);
+        self.bytes = tail;
+        Ok(head.try_into()?)
+    }
+
+    fn read_i32(&mut self) -> Result<i32, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(i32::from_le_bytes(self.take::<4>()?))
+    }
+
+    fn read_f64(&mut self) -> Result<f64, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(f64::from_le_bytes(self.take::<8>()?))
+    }
+}
+
+impl IntoIterator for ResponseBody {
+    type Item = Record;
+    type IntoIter = std::iter::Map<
+        std::vec::IntoIter<(ResponseIndex, Vec<RecordValue>)>,
+        fn((ResponseIndex, Vec<RecordValue>)) -> Record,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.response_records
+            .into_iter()
+            .map(|(index, values)| Record {
+                location: index.location,
+                valid_date: index.valid_date,
+                values,
+            })
+    }
+}
+
+impl<'a> IntoIterator for &'a ResponseBody {
+    type Item = RecordRef<'a>;
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (ResponseIndex, Vec<RecordValue>)>,
+        fn(&'a (ResponseIndex, Vec<RecordValue>)) -> RecordRef<'a>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.response_records
+            .iter()
+            .map(|(index, values)| RecordRef {
+                location: index.location.as_ref(),
+                valid_date: index.valid_date,
+                values,
+            })
+    }
+}
+
 impl std::fmt::Display for ResponseBody {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.response_headers.to_vec().join(","))?;
         for row in self.response_records.iter() {
             let (index, values) = row;
             let values_str: Vec<_> = values.to_vec().iter().map(ToString::to_string).collect();
-            writeln!(f, "{}", index.to_owned() + ": " + &values_str.join(","))?;
+            writeln!(f, "{}: {}", index, values_str.join(","))?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{
+        ConnectorResponse, CsvChunkParser, Record, RecordValue, ResponseBody, ResponseLocation,
+        ResponseMetadata,
+    };
+    use crate::units::Unit;
+    use chrono::{DateTime, Utc};
+
+    fn valid_date<T: From<DateTime<Utc>>>(s: &str) -> T {
+        s.parse::<DateTime<Utc>>().unwrap().into()
+    }
+
+    #[tokio::test]
+    async fn parse_numbers_like_std() {
+        println!("\n##### parse_numbers_like_std:");
+
+        let raws = [
+            "12.5", " -3.25 ", "+7", "1e-3", "6.02E23", ".5", "5.", "-0", "-999", "inf", "-inf",
+            "NaN", "", "-", "1,5", "0x10", "1_000", "abc", "1.2.3",
+        ];
+        for raw in raws {
+            let parsed = super::parse_f64(raw).ok();
+            let expected = raw.trim().parse::<f64>().ok();
+            println!(">>>>>>>>>> {:?}: {:?}", raw, parsed);
+            assert_eq!(parsed.map(f64::to_bits), expected.map(f64::to_bits));
+        }
+        assert_eq!(
+            RecordValue::parse("METAR"),
+            RecordValue::Text("METAR".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn populate_records_from_xml() {
+        println!("\n##### populate_records_from_xml:");
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<meteomatics-api-response version="3.0">
+    <user>python-community</user>
+    <dateGenerated>2023-11-20T10:00:00Z</dateGenerated>
+    <status>OK</status>
+    <data>
+        <parameter name="t_2m:C">
+            <location lat="47.419708" lon="9.358478">
+                <value date="2023-11-20T00:00:00Z">3.4</value>
+                <value date="2023-11-20T01:00:00Z">3.1</value>
+            </location>
+        </parameter>
+        <parameter name="precip_1h:mm">
+            <location lat="47.419708" lon="9.358478">
+                <value date="2023-11-20T00:00:00Z">0</value>
+                <value date="2023-11-20T01:00:00Z">0.12</value>
+            </location>
+        </parameter>
+    </data>
+</meteomatics-api-response>"#;
+
+        let mut response_body = ResponseBody::new();
+        response_body.add_header("validdate".to_string());
+        response_body.add_header("t_2m:C".to_string());
+        response_body.add_header("precip_1h:mm".to_string());
+        response_body.populate_records_from_xml(xml).unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response_body);
+
+        assert_eq!(
+            response_body.response_records,
+            vec![
+                (
+                    valid_date("2023-11-20T00:00:00Z"),
+                    vec![3.4.into(), 0.0.into()]
+                ),
+                (
+                    valid_date("2023-11-20T01:00:00Z"),
+                    vec![3.1.into(), 0.12.into()]
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn populate_multi_location_records_from_xml() {
+        println!("\n##### populate_multi_location_records_from_xml:");
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<meteomatics-api-response version="3.0">
+    <data>
+        <parameter name="t_2m:C">
+            <location lat="47.41" lon="9.35">
+                <value date="2023-11-20T00:00:00Z">3.4</value>
+                <value date="2023-11-20T01:00:00Z">3.1</value>
+            </location>
+            <location lat="47.51" lon="8.74">
+                <value date="2023-11-20T00:00:00Z">4.2</value>
+                <value date="2023-11-20T01:00:00Z">4.0</value>
+            </location>
+        </parameter>
+        <parameter name="precip_1h:mm">
+            <location lat="47.41" lon="9.35">
+                <value date="2023-11-20T00:00:00Z">0</value>
+                <value date="2023-11-20T01:00:00Z">0.12</value>
+            </location>
+            <location lat="47.51" lon="8.74">
+                <value date="2023-11-20T00:00:00Z">0.3</value>
+                <value date="2023-11-20T01:00:00Z">0.5</value>
+            </location>
+        </parameter>
+    </data>
+</meteomatics-api-response>"#;
+
+        let mut response_body = ResponseBody::new();
+        response_body.add_header("validdate".to_string());
+        response_body.add_header("t_2m:C".to_string());
+        response_body.add_header("precip_1h:mm".to_string());
+        response_body.populate_records_from_xml(xml).unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response_body);
+
+        let rows: Vec<String> = response_body
+            .response_records
+            .iter()
+            .map(|(index, values)| format!("{} {} {}", index, values[0], values[1]))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "47.41,9.35 2023-11-20T00:00:00Z 3.4 0",
+                "47.41,9.35 2023-11-20T01:00:00Z 3.1 0.12",
+                "47.51,8.74 2023-11-20T00:00:00Z 4.2 0.3",
+                "47.51,8.74 2023-11-20T01:00:00Z 4 0.5",
+            ]
+        );
+        assert!(response_body.validate_monotonic().is_ok());
+    }
+
+    #[tokio::test]
+    async fn populate_records_from_bin() {
+        println!("\n##### populate_records_from_bin:");
+
+        let mut bytes: Vec<u8> = vec![];
+        bytes.extend(2i32.to_le_bytes());
+        for (date, t_2m, precip_1h) in
+            [(1700438400.0f64, 3.4f64, 0.0f64), (1700442000.0, 3.1, 0.12)]
+        {
+            bytes.extend(date.to_le_bytes());
+            bytes.extend(t_2m.to_le_bytes());
+            bytes.extend(precip_1h.to_le_bytes());
+        }
+
+        let mut response_body = ResponseBody::new();
+        response_body
+            .populate_records_from_bin(&bytes, 2, &[(47.42, 9.36)])
+            .unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response_body);
+
+        assert_eq!(
+            response_body.response_records,
+            vec![
+                (
+                    valid_date("2023-11-20T00:00:00Z"),
+                    vec![3.4.into(), 0.0.into()]
+                ),
+                (
+                    valid_date("2023-11-20T01:00:00Z"),
+                    vec![3.1.into(), 0.12.into()]
+                ),
+            ]
+        );
+
+        let mut response_body = ResponseBody::new();
+        assert!(response_body
+            .populate_records_from_bin(&bytes[..bytes.len() - 1], 2, &[])
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn parse_csv_in_chunks() {
+        println!("\n##### parse_csv_in_chunks:");
+
+        let csv = "validdate;t_2m:C\n\
+                   2023-11-20T00:00:00Z;3.4\n\
+                   2023-11-20T01:00:00Z;3.1\n\
+                   2023-11-20T02:00:00Z;2.9";
+
+        let mut expected = ResponseBody::new();
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(csv.as_bytes());
+        expected.populate_records(&mut rdr).await.unwrap();
+
+        let mut response_body = ResponseBody::new();
+        let mut parser = CsvChunkParser::new();
+        for chunk in csv.as_bytes().chunks(7) {
+            parser.feed(&mut response_body, chunk).unwrap();
+        }
+        parser.finish(&mut response_body).unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response_body);
+
+        assert_eq!(response_body, expected);
+        assert_eq!(response_body.response_records.len(), 3);
+        assert_eq!(
+            response_body.response_records[0],
+            (
+                valid_date("2023-11-20T00:00:00Z"),
+                vec![RecordValue::Number(3.4)]
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn parse_multi_location_csv() {
+        println!("\n##### parse_multi_location_csv:");
+
+        let csv = "lat;lon;validdate;t_2m:C\n\
+                   47.41;9.35;2023-11-20T00:00:00Z;3.4\n\
+                   47.41;9.35;2023-11-20T01:00:00Z;3.1\n\
+                   47.51;8.74;2023-11-20T00:00:00Z;2.2\n\
+                   47.51;8.74;2023-11-20T01:00:00Z;2.0\n";
+
+        let mut response_body = ResponseBody::new();
+        response_body.add_header("validdate".to_string());
+        response_body.add_header("t_2m:C".to_string());
+        let mut parser = CsvChunkParser::new();
+        parser.feed(&mut response_body, csv.as_bytes()).unwrap();
+        parser.finish(&mut response_body).unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response_body);
+
+        assert_eq!(response_body.response_records.len(), 4);
+        assert_eq!(
+            response_body.response_records[0].0.to_string(),
+            "47.41,9.35 2023-11-20T00:00:00Z"
+        );
+        let mut populated = ResponseBody::new();
+        populated.response_headers = response_body.response_headers.clone();
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(csv.as_bytes());
+        populated.populate_records(&mut rdr).await.unwrap();
+        assert_eq!(populated, response_body);
+        let (index, values) = &response_body.response_records[2];
+        assert_eq!(index.coordinate(), Some((47.51, 8.74)));
+        assert_eq!(
+            index.valid_date,
+            valid_date::<DateTime<Utc>>("2023-11-20T00:00:00Z")
+        );
+        assert_eq!(values, &vec![RecordValue::Number(2.2)]);
+        assert!(response_body.validate_monotonic().is_ok());
+
+        let split = response_body.split_by_location();
+        assert_eq!(split.len(), 2);
+        assert_eq!(
+            split[1].0,
+            Some(ResponseLocation::Coordinate((47.51, 8.74)))
+        );
+        assert_eq!(split[1].1.column("t_2m:C"), Some(vec![2.2, 2.0]));
+    }
+
+    #[tokio::test]
+    async fn parse_station_csv() {
+        println!("\n##### parse_station_csv:");
+
+        let csv = "station_id;validdate;t_2m:C\n\
+                   wmo_066810;2023-11-20T00:00:00Z;3.4\n\
+                   metar_LSZH;2023-11-20T00:00:00Z;2.2\n";
+
+        let mut response_body = ResponseBody::new();
+        let mut parser = CsvChunkParser::new();
+        parser.feed(&mut response_body, csv.as_bytes()).unwrap();
+        parser.finish(&mut response_body).unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response_body);
+
+        let (index, _) = &response_body.response_records[1];
+        assert_eq!(index.station_id(), Some("metar_LSZH"));
+        assert_eq!(index.coordinate(), None);
+        assert_eq!(index.to_string(), "metar_LSZH 2023-11-20T00:00:00Z");
+        assert!(response_body.validate_monotonic().is_ok());
+    }
+
+    #[tokio::test]
+    async fn populate_multi_location_bin() {
+        println!("\n##### populate_multi_location_bin:");
+
+        let mut bytes: Vec<u8> = vec![];
+        for t_2m in [3.4f64, 2.2] {
+            bytes.extend(1i32.to_le_bytes());
+            bytes.extend(1700438400.0f64.to_le_bytes());
+            bytes.extend(t_2m.to_le_bytes());
+        }
+
+        let coordinates = [(47.41, 9.35), (47.51, 8.74)];
+        let mut response_body = ResponseBody::new();
+        response_body
+            .populate_records_from_bin(&bytes, 1, &coordinates)
+            .unwrap();
+
+        let records: Vec<Record> = response_body.into_iter().collect();
+        assert_eq!(
+            records[0].location,
+            Some(ResponseLocation::Coordinate((47.41, 9.35)))
+        );
+        assert_eq!(
+            records[1].location,
+            Some(ResponseLocation::Coordinate((47.51, 8.74)))
+        );
+        assert_eq!(records[1].values, vec![2.2.into()]);
+    }
+
+    #[tokio::test]
+    async fn access_columns_by_name() {
+        println!("\n##### access_columns_by_name:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "metar".to_string(),
+            ],
+            response_records: vec![
+                (
+                    valid_date("2023-11-20T00:00:00Z"),
+                    vec![3.4.into(), RecordValue::Text("LSZH".to_string())],
+                ),
+                (
+                    valid_date("2023-11-20T01:00:00Z"),
+                    vec![3.1.into(), RecordValue::Text("LSZH".to_string())],
+                ),
+            ],
+        };
+
+        assert_eq!(response_body.column("t_2m:C"), Some(vec![3.4, 3.1]));
+        assert_eq!(response_body.column("metar"), None);
+        assert_eq!(response_body.column("validdate"), None);
+
+        let columns: Vec<_> = response_body.columns().collect();
+        println!(">>>>>>>>>> columns: {:?}", columns);
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[1].0, "metar");
+        assert_eq!(columns[1].1[0].as_text(), Some("LSZH"));
+    }
+
+    #[tokio::test]
+    async fn access_records_by_valid_date() {
+        println!("\n##### access_records_by_valid_date:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "precip_1h:mm".to_string(),
+            ],
+            response_records: vec![
+                (
+                    valid_date("2023-11-20T01:00:00Z"),
+                    vec![3.1.into(), RecordValue::Missing],
+                ),
+                (
+                    valid_date("2023-11-20T00:00:00Z"),
+                    vec![3.4.into(), 0.2.into()],
+                ),
+            ],
+        };
+
+        let map = response_body.to_map();
+        println!(">>>>>>>>>> map: {:?}", map);
+
+        assert_eq!(map.keys().next(), Some(&valid_date("2023-11-20T00:00:00Z")));
+        assert_eq!(map[&valid_date("2023-11-20T00:00:00Z")], vec![3.4, 0.2]);
+        assert!(map[&valid_date("2023-11-20T01:00:00Z")][1].is_nan());
+
+        let column = response_body.column_map("t_2m:C").unwrap();
+        assert_eq!(column[&valid_date("2023-11-20T01:00:00Z")], 3.1);
+        assert_eq!(response_body.column_map("metar"), None);
+    }
+
+    #[tokio::test]
+    async fn convert_column_units() {
+        println!("\n##### convert_column_units:");
+
+        let mut response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "metar".to_string(),
+            ],
+            response_records: vec![
+                (
+                    valid_date("2023-11-20T00:00:00Z"),
+                    vec![20.0.into(), RecordValue::Text("LSZH".to_string())],
+                ),
+                (
+                    valid_date("2023-11-20T01:00:00Z"),
+                    vec![RecordValue::Missing, RecordValue::Text("LSZH".to_string())],
+                ),
+            ],
+        };
+
+        response_body
+            .convert_column("t_2m:C", Unit::Fahrenheit)
+            .unwrap();
+        println!(">>>>>>>>>> response_body: {:?}", response_body);
+
+        assert_eq!(response_body.response_headers[1], "t_2m:F");
+        assert_eq!(response_body.response_records[0].1[0], 68.0.into());
+        assert_eq!(response_body.response_records[1].1[0], RecordValue::Missing);
+        assert!(response_body.convert_column("t_2m:F", Unit::Knots).is_err());
+        assert!(response_body
+            .convert_column("metar", Unit::Celsius)
+            .is_err());
+        assert!(response_body
+            .convert_column("t_2m:C", Unit::Kelvin)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn convert_multi_point_column_units() {
+        println!("\n##### convert_multi_point_column_units:");
+
+        let mut response_body = ResponseBody::new();
+        for header in ["lat", "lon", "validdate", "t_2m:C", "wind_speed_10m:ms"] {
+            response_body.add_header(header.to_string());
+        }
+        let mut rdr = csv::ReaderBuilder::new().delimiter(b';').from_reader(
+            "lat;lon;validdate;t_2m:C;wind_speed_10m:ms\n\
+                 47.41;9.35;2023-11-20T00:00:00Z;3.4;2.5\n\
+                 47.51;8.74;2023-11-20T00:00:00Z;4.2;5.0\n"
+                .as_bytes(),
+        );
+        response_body.populate_records(&mut rdr).await.unwrap();
+
+        response_body
+            .convert_column("wind_speed_10m:ms", Unit::KilometersPerHour)
+            .unwrap();
+        println!(">>>>>>>>>> response_body: {:?}", response_body);
+
+        assert_eq!(
+            response_body.response_headers,
+            vec!["lat", "lon", "validdate", "t_2m:C", "wind_speed_10m:kmh"]
+        );
+        assert_eq!(
+            response_body.response_records[0].1,
+            vec![3.4.into(), 9.0.into()]
+        );
+        assert_eq!(
+            response_body.response_records[1].1,
+            vec![4.2.into(), 18.0.into()]
+        );
+    }
+
+    #[tokio::test]
+    async fn iterate_records() {
+        println!("\n##### iterate_records:");
+
+        let response_body = ResponseBody {
+            response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+            response_records: vec![
+                (valid_date("2023-11-20T00:00:00Z"), vec![3.4.into()]),
+                (valid_date("2023-11-20T01:00:00Z"), vec![3.1.into()]),
+            ],
+        };
+
+        let warmest = response_body
+            .iter()
+            .max_by(|a, b| {
+                a.values[0]
+                    .as_f64()
+                    .partial_cmp(&b.values[0].as_f64())
+                    .unwrap()
+            })
+            .unwrap();
+        assert_eq!(
+            warmest.valid_date,
+            valid_date::<DateTime<Utc>>("2023-11-20T00:00:00Z")
+        );
+        assert_eq!((&response_body).into_iter().count(), 2);
+
+        let records: Vec<Record> = response_body.into_iter().collect();
+        println!(">>>>>>>>>> records: {:?}", records);
+        assert_eq!(
+            records[1],
+            Record {
+                location: None,
+                valid_date: valid_date::<DateTime<Utc>>("2023-11-20T01:00:00Z"),
+                values: vec![3.1.into()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn validate_monotonic() {
+        println!("\n##### validate_monotonic:");
+
+        let mut response_body = ResponseBody {
+            response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+            response_records: vec![
+                (valid_date("2023-11-20T00:00:00Z"), vec![3.4.into()]),
+                (valid_date("2023-11-20T01:00:00Z"), vec![3.1.into()]),
+            ],
+        };
+        assert!(response_body.validate_monotonic().is_ok());
+
+        response_body
+            .response_records
+            .push((valid_date("2023-11-20T01:00:00Z"), vec![3.0.into()]));
+        assert!(response_body.validate_monotonic().is_err());
+    }
+
+    #[tokio::test]
+    async fn serde_round_trip() {
+        println!("\n##### serde_round_trip:");
+
+        let connector_response = ConnectorResponse {
+            response_body: ResponseBody {
+                response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+                response_records: vec![
+                    (valid_date("2023-11-20T00:00:00Z"), vec![3.4.into()]),
+                    (
+                        valid_date("2023-11-20T01:00:00Z"),
+                        vec![RecordValue::Text("METAR LSZH".to_string())],
+                    ),
+                ],
+            },
+            http_status_code: "200".to_string(),
+            http_status_message: "200 OK".to_string(),
+            metadata: ResponseMetadata {
+                request_url: "https://api.meteomatics.com/now/t_2m:C/47.42,9.36/csv".to_string(),
+                response_headers: vec![("content-type".to_string(), "text/csv".to_string())],
+                content_length: Some(53),
+                elapsed: std::time::Duration::from_millis(120),
+            },
+        };
+
+        let json = serde_json::to_string(&connector_response).unwrap();
+        println!(">>>>>>>>>> json: {}", json);
+
+        let deserialized: ConnectorResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, connector_response);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{RecordValue, ResponseBody};
+use crate::units::Unit;
+
+/// Dew point in °C from the temperature in °C and the relative humidity in percent, by the
+/// Magnus formula.
+pub fn dew_point(temperature: f64, relative_humidity: f64) -> f64 {
+    const B: f64 = 17.62;
+    const C: f64 = 243.12;
+    let gamma = (relative_humidity / 100.0).ln() + B * temperature / (C + temperature);
+    C * gamma / (B - gamma)
+}
+
+/// Wind chill temperature in °C from the temperature in °C and the wind speed in km/h, by the
+/// formula of Environment Canada and the US National Weather Service. Outside its range of
+/// validity, above 10 °C or below 4.8 km/h, it is the temperature itself.
+pub fn wind_chill(temperature: f64, wind_speed: f64) -> f64 {
+    if temperature > 10.0 || wind_speed < 4.8 {
+        return temperature;
+    }
+    let v = wind_speed.powf(0.16);
+    13.12 + 0.6215 * temperature - 11.37 * v + 0.3965 * temperature * v
+}
+
+/// Heat index in °C from the temperature in °C and the relative humidity in percent, by the
+/// Rothfusz regression of the US National Weather Service, with Steadman's simple formula below
+/// a heat index of 80 °F.
+pub fn heat_index(temperature: f64, relative_humidity: f64) -> f64 {
+    let t = temperature * 9.0 / 5.0 + 32.0;
+    let rh = relative_humidity;
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let heat_index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        -42.379 + 2.04901523 * t + 10.14333127 * rh
+            - 0.22475541 * t * rh
+            - 0.00683783 * t * t
+            - 0.05481717 * rh * rh
+            + 0.00122874 * t * t * rh
+            + 0.00085282 * t * rh * rh
+            - 0.00000199 * t * t * rh * rh
+    };
+    (heat_index - 32.0) * 5.0 / 9.0
+}
+
+/// Wind speed, in the unit of the components, and the direction the wind blows from in degrees
+/// clockwise from north, from the eastward `u` and northward `v` components.
+pub fn wind_from_components(u: f64, v: f64) -> (f64, f64) {
+    let speed = u.hypot(v);
+    let direction = (-u).atan2(-v).to_degrees().rem_euclid(360.0);
+    (speed, direction)
+}
+
+/// The parameter and unit of a column header such as `t_2m:C` or `t_2m:C:ecmwf-ifs`.
+pub(crate) fn parameter_and_unit(header: &str) -> (&str, &str) {
+    let mut parts = header.splitn(3, ':');
+    let parameter = parts.next().unwrap_or_default();
+    (parameter, parts.next().unwrap_or_default())
+}
+
+/// The header of a derived quantity at the level of `input`, e.g. `dew_point_2m:C` for `t_2m:C`.
+fn derived_header(name: &str, input: &str, unit: &str) -> String {
+    let (parameter, _) = parameter_and_unit(input);
+    match parameter.split_once('_') {
+        Some((_, level)) => format!("{}_{}:{}", name, level, unit),
+        None => format!("{}:{}", name, unit),
+    }
+}
+
+impl ResponseBody {
+    /// Appends a column `name` computed from the numeric columns `inputs` of every record. A
+    /// record missing any input gets a missing value.
+    pub fn derive_column(
+        &mut self,
+        name: &str,
+        inputs: &[&str],
+        derive: impl Fn(&[f64]) -> f64,
+    ) -> Result<(), ConnectorError> {
+        let columns = inputs
+            .iter()
+            .map(|input| {
+                self.column(input).ok_or_else(|| {
+                    ConnectorError::LibraryError(format!("No numeric column {}.", input))
+                })
+            })
+            .collect::<Result<Vec<Vec<f64>>, ConnectorError>>()?;
+        let mut arguments = vec![0.0; inputs.len()];
+        for (n, (_, values)) in self.response_records.iter_mut().enumerate() {
+            for (argument, column) in arguments.iter_mut().zip(columns.iter()) {
+                *argument = column[n];
+            }
+            values.push(if arguments.iter().any(|argument| argument.is_nan()) {
+                RecordValue::Missing
+            } else {
+                RecordValue::Number(derive(&arguments))
+            });
+        }
+        self.response_headers.push(name.to_string());
+        Ok(())
+    }
+
+    /// Appends the dew point in °C, e.g. `dew_point_2m:C` from `t_2m:C` and
+    /// `relative_humidity_2m:p`, see `dew_point`.
+    pub fn add_dew_point(
+        &mut self,
+        temperature: &str,
+        relative_humidity: &str,
+    ) -> Result<(), ConnectorError> {
+        let to_celsius = self.converter(temperature, Unit::Celsius)?;
+        self.derive_column(
+            &derived_header("dew_point", temperature, "C"),
+            &[temperature, relative_humidity],
+            |inputs| dew_point(to_celsius(inputs[0]), inputs[1]),
+        )
+    }
+
+    /// Appends the wind chill in °C, e.g. `wind_chill_2m:C` from `t_2m:C` and
+    /// `wind_speed_10m:ms`, see `wind_chill`.
+    pub fn add_wind_chill(
+        &mut self,
+        temperature: &str,
+        wind_speed: &str,
+    ) -> Result<(), ConnectorError> {
+        let to_celsius = self.converter(temperature, Unit::Celsius)?;
+        let to_kmh = self.converter(wind_speed, Unit::KilometersPerHour)?;
+        self.derive_column(
+            &derived_header("wind_chill", temperature, "C"),
+            &[temperature, wind_speed],
+            |inputs| wind_chill(to_celsius(inputs[0]), to_kmh(inputs[1])),
+        )
+    }
+
+    /// Appends the heat index in °C, e.g. `heat_index_2m:C` from `t_2m:C` and
+    /// `relative_humidity_2m:p`, see `heat_index`.
+    pub fn add_heat_index(
+        &mut self,
+        temperature: &str,
+        relative_humidity: &str,
+    ) -> Result<(), ConnectorError> {
+        let to_celsius = self.converter(temperature, Unit::Celsius)?;
+        self.derive_column(
+            &derived_header("heat_index", temperature, "C"),
+            &[temperature, relative_humidity],
+            |inputs| heat_index(to_celsius(inputs[0]), inputs[1]),
+        )
+    }
+
+    /// Appends the wind speed and direction, e.g. `wind_speed_10m:ms` and `wind_dir_10m:d` from
+    /// `u_10m:ms` and `v_10m:ms`, see `wind_from_components`.
+    pub fn add_wind_from_components(&mut self, u: &str, v: &str) -> Result<(), ConnectorError> {
+        let (_, unit) = parameter_and_unit(u);
+        self.derive_column(&derived_header("wind_speed", u, unit), &[u, v], |inputs| {
+            wind_from_components(inputs[0], inputs[1]).0
+        })?;
+        self.derive_column(&derived_header("wind_dir", u, "d"), &[u, v], |inputs| {
+            wind_from_components(inputs[0], inputs[1]).1
+        })
+    }
+
+    /// Converts values of the column `header` to `unit`, by the unit in the header.
+    fn converter(&self, header: &str, unit: Unit) -> Result<impl Fn(f64) -> f64, ConnectorError> {
+        let from: Unit = parameter_and_unit(header).1.parse()?;
+        from.convert(0.0, unit)?;
+        Ok(move |value| from.convert(value, unit).unwrap_or(f64::NAN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{RecordValue, ResponseBody};
+    use crate::derived_quantities::{dew_point, heat_index, wind_chill, wind_from_components};
+
+    fn round(value: f64) -> f64 {
+        (value * 10.0).round() / 10.0
+    }
+
+    #[tokio::test]
+    async fn derive_quantities() {
+        println!("\n##### derive_quantities:");
+
+        assert_eq!(round(dew_point(20.0, 50.0)), 9.3);
+        assert_eq!(round(wind_chill(-10.0, 30.0)), -19.5);
+        assert_eq!(wind_chill(15.0, 30.0), 15.0);
+        assert_eq!(round(heat_index(32.0, 70.0)), 40.4);
+        let (speed, direction) = wind_from_components(3.0, 4.0);
+        assert_eq!((speed, round(direction)), (5.0, 216.9));
+        assert_eq!(wind_from_components(0.0, -2.0).1, 0.0);
+
+        let mut response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:F".to_string(),
+                "relative_humidity_2m:p".to_string(),
+                "u_10m:ms".to_string(),
+                "v_10m:ms".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![68.0.into(), 50.0.into(), 3.0.into(), 4.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![68.0.into(), RecordValue::Missing, 0.0.into(), 0.0.into()],
+                ),
+            ],
+        };
+        response_body
+            .add_dew_point("t_2m:F", "relative_humidity_2m:p")
+            .unwrap();
+        response_body
+            .add_wind_from_components("u_10m:ms", "v_10m:ms")
+            .unwrap();
+        println!(">>>>>>>>>> response_body:\n{}", response_body);
+
+        assert_eq!(
+            response_body.response_headers[5..],
+            ["dew_point_2m:C", "wind_speed_10m:ms", "wind_dir_10m:d"]
+        );
+        assert_eq!(
+            response_body.column("dew_point_2m:C").unwrap()[0],
+            dew_point(20.0, 50.0)
+        );
+        assert_eq!(response_body.response_records[1].1[4], RecordValue::Missing);
+        assert_eq!(response_body.column("wind_speed_10m:ms").unwrap()[0], 5.0);
+        assert!(response_body
+            .add_wind_chill("relative_humidity_2m:p", "u_10m:ms")
+            .is_err());
+    }
+}
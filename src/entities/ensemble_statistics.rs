@@ -0,0 +1,206 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{RecordValue, ResponseBody};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Statistic over the members of an ensemble, computed per date time by
+/// `ResponseBody::ensemble_statistics`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum EnsStatistic {
+    Mean,
+    /// Standard deviation of the members around their mean.
+    Spread,
+    Min,
+    Max,
+    /// Percentile between 0 and 100, linearly interpolated between members.
+    Percentile(f64),
+}
+
+impl EnsStatistic {
+    /// The statistic of `members`, or `None` if there are none.
+    pub fn apply(&self, members: &[f64]) -> Option<f64> {
+        if members.is_empty() {
+            return None;
+        }
+        let n = members.len() as f64;
+        let mean = members.iter().sum::<f64>() / n;
+        let statistic = match *self {
+            EnsStatistic::Mean => mean,
+            EnsStatistic::Spread => {
+                let squared_deviations: f64 =
+                    members.iter().map(|member| (member - mean).powi(2)).sum();
+                (squared_deviations / n).sqrt()
+            }
+            EnsStatistic::Min => members.iter().copied().fold(f64::INFINITY, f64::min),
+            EnsStatistic::Max => members.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            EnsStatistic::Percentile(percentile) => {
+                let mut sorted = members.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let rank = (percentile.clamp(0.0, 100.0) / 100.0) * (n - 1.0);
+                let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+                sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+            }
+        };
+        Some(statistic)
+    }
+}
+
+impl Display for EnsStatistic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnsStatistic::Mean => write!(f, "mean"),
+            EnsStatistic::Spread => write!(f, "spread"),
+            EnsStatistic::Min => write!(f, "min"),
+            EnsStatistic::Max => write!(f, "max"),
+            EnsStatistic::Percentile(percentile) => write!(f, "p{}", percentile),
+        }
+    }
+}
+
+/// The parameter of a member column header such as `t_2m:C-m3`.
+fn member_parameter(header: &str) -> Option<&str> {
+    let (parameter, member) = header.rsplit_once('-')?;
+    let number = member.strip_prefix('m')?;
+    (!number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit())).then_some(parameter)
+}
+
+impl ResponseBody {
+    /// Reduces the member columns of an ensemble response, e.g. `t_2m:C-m1` to `t_2m:C-m50` as
+    /// requested with `ens_select=member:1-50`, to one column per parameter and statistic, e.g.
+    /// `t_2m:C-mean` and `t_2m:C-p90`. Missing and text values are skipped; a date time without
+    /// any member value gets a missing value. Other columns, such as an API-side `mean`, are
+    /// left out.
+    pub fn ensemble_statistics(
+        &self,
+        statistics: &[EnsStatistic],
+    ) -> Result<ResponseBody, ConnectorError> {
+        let mut parameters: Vec<(&str, Vec<usize>)> = vec![];
+        for (n, header) in self.value_headers().iter().enumerate() {
+            if let Some(parameter) = member_parameter(header) {
+                match parameters.iter_mut().find(|(known, _)| *known == parameter) {
+                    Some((_, columns)) => columns.push(n),
+                    None => parameters.push((parameter, vec![n])),
+                }
+            }
+        }
+        if parameters.is_empty() {
+            return Err(ConnectorError::LibraryError(
+                "No ensemble member columns in the response.".to_string(),
+            ));
+        }
+
+        let mut response_headers = vec![self
+            .response_headers
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "validdate".to_string())];
+        for (parameter, _) in parameters.iter() {
+            for statistic in statistics {
+                response_headers.push(format!("{}-{}", parameter, statistic));
+            }
+        }
+        let response_records = self
+            .response_records
+            .iter()
+            .map(|(index, values)| {
+                let mut statistic_values = vec![];
+                for (_, columns) in parameters.iter() {
+                    let members: Vec<f64> = columns
+                        .iter()
+                        .filter_map(|n| values.get(*n).and_then(RecordValue::as_f64))
+                        .filter(|member| !member.is_nan())
+                        .collect();
+                    for statistic in statistics {
+                        statistic_values.push(match statistic.apply(&members) {
+                            Some(value) => RecordValue::Number(value),
+                            None => RecordValue::Missing,
+                        });
+                    }
+                }
+                (index.clone(), statistic_values)
+            })
+            .collect();
+
+        Ok(ResponseBody {
+            response_headers,
+            response_records,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{RecordValue, ResponseBody};
+    use crate::ensemble_statistics::EnsStatistic;
+
+    #[tokio::test]
+    async fn reduce_ensemble_members() {
+        println!("\n##### reduce_ensemble_members:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C-m1".to_string(),
+                "t_2m:C-m2".to_string(),
+                "t_2m:C-m3".to_string(),
+                "t_2m:C-m4".to_string(),
+                "t_2m:C-mean".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![1.0.into(), 2.0.into(), 3.0.into(), 6.0.into(), 3.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![
+                        RecordValue::Missing,
+                        4.0.into(),
+                        RecordValue::Missing,
+                        RecordValue::Missing,
+                        4.0.into(),
+                    ],
+                ),
+            ],
+        };
+
+        let statistics = response_body
+            .ensemble_statistics(&[
+                EnsStatistic::Mean,
+                EnsStatistic::Spread,
+                EnsStatistic::Min,
+                EnsStatistic::Max,
+                EnsStatistic::Percentile(50.0),
+            ])
+            .unwrap();
+        println!(">>>>>>>>>> statistics:\n{}", statistics);
+
+        assert_eq!(
+            statistics.response_headers,
+            vec![
+                "validdate",
+                "t_2m:C-mean",
+                "t_2m:C-spread",
+                "t_2m:C-min",
+                "t_2m:C-max",
+                "t_2m:C-p50"
+            ]
+        );
+        assert_eq!(
+            statistics.response_records[0].1,
+            vec![
+                3.0.into(),
+                3.5f64.sqrt().into(),
+                1.0.into(),
+                6.0.into(),
+                2.5.into()
+            ]
+        );
+        assert_eq!(statistics.response_records[1].1[1], 0.0.into());
+        assert_eq!(EnsStatistic::Percentile(90.0).apply(&[]), None);
+        assert!(ResponseBody::new()
+            .ensemble_statistics(&[EnsStatistic::Mean])
+            .is_err());
+    }
+}
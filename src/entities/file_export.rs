@@ -0,0 +1,143 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::ConnectorResponse;
+use chrono::SecondsFormat;
+use std::io::Write;
+use std::path::Path;
+
+impl ConnectorResponse {
+    /// Writes the parsed records as a comma-separated file with a header row of `validdate`
+    /// followed by the value headers, preceded by `lat` and `lon` for multi-location responses and
+    /// by `station_id` for station responses.
+    /// Dates are RFC 3339 in UTC and missing values are empty cells.
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), ConnectorError> {
+        let file = std::fs::File::create(path)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        self.write_csv_to(file)
+    }
+
+    /// Writes the records as `write_csv` does, to `writer` instead of a file.
+    pub fn write_csv_to<W: Write>(&self, writer: W) -> Result<(), ConnectorError> {
+        let response_body = &self.response_body;
+        let has_coordinates = response_body
+            .response_records
+            .iter()
+            .any(|(index, _)| index.coordinate().is_some());
+        let has_stations = response_body
+            .response_records
+            .iter()
+            .any(|(index, _)| index.station_id().is_some());
+
+        let mut writer = csv::Writer::from_writer(writer);
+        let mut header: Vec<String> = vec![];
+        if has_coordinates {
+            header.push("lat".to_string());
+            header.push("lon".to_string());
+        }
+        if has_stations {
+            header.push("station_id".to_string());
+        }
+        header.extend(response_body.response_headers.iter().cloned());
+        writer
+            .write_record(&header)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+
+        for (index, values) in response_body.response_records.iter() {
+            let mut row: Vec<String> = vec![];
+            if has_coordinates {
+                let (lat, lon) = match index.coordinate() {
+                    Some((lat, lon)) => (lat.to_string(), lon.to_string()),
+                    None => ("".to_string(), "".to_string()),
+                };
+                row.push(lat);
+                row.push(lon);
+            }
+            if has_stations {
+                row.push(index.station_id().unwrap_or_default().to_string());
+            }
+            row.push(index.valid_date.to_rfc3339_opts(SecondsFormat::Secs, true));
+            row.extend(values.iter().map(ToString::to_string));
+            writer
+                .write_record(&row)
+                .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        }
+        writer
+            .flush()
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+
+    /// Writes the whole response, including the HTTP status, as pretty-printed JSON in the
+    /// serde representation of `ConnectorResponse`.
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> Result<(), ConnectorError> {
+        let file = std::fs::File::create(path)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        self.write_json_to(std::io::BufWriter::new(file))
+    }
+
+    /// Writes the response as `write_json` does, to `writer` instead of a file.
+    pub fn write_json_to<W: Write>(&self, writer: W) -> Result<(), ConnectorError> {
+        serde_json::to_writer_pretty(writer, self)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{ConnectorResponse, RecordValue, ResponseBody};
+
+    fn connector_response() -> ConnectorResponse {
+        ConnectorResponse {
+            response_body: ResponseBody {
+                response_headers: vec![
+                    "validdate".to_string(),
+                    "t_2m:C".to_string(),
+                    "precip_1h:mm".to_string(),
+                ],
+                response_records: vec![
+                    (
+                        "2023-11-20T00:00:00Z".parse().unwrap(),
+                        vec![3.4.into(), RecordValue::Missing],
+                    ),
+                    (
+                        "2023-11-20T01:00:00Z".parse().unwrap(),
+                        vec![3.1.into(), 0.12.into()],
+                    ),
+                ],
+            },
+            http_status_code: "200".to_string(),
+            http_status_message: "200 OK".to_string(),
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn write_csv_file() {
+        println!("\n##### write_csv_file:");
+
+        let path = std::env::temp_dir().join("write_csv_file.csv");
+        connector_response().write_csv(&path).unwrap();
+        println!(">>>>>>>>>> path: {:?}", path);
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "validdate,t_2m:C,precip_1h:mm\n\
+             2023-11-20T00:00:00Z,3.4,\n\
+             2023-11-20T01:00:00Z,3.1,0.12\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn write_json_file() {
+        println!("\n##### write_json_file:");
+
+        let path = std::env::temp_dir().join("write_json_file.json");
+        connector_response().write_json(&path).unwrap();
+        println!(">>>>>>>>>> path: {:?}", path);
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        let deserialized: ConnectorResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, connector_response());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
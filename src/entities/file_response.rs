@@ -0,0 +1,15 @@
+use crate::connector_response::ResponseMetadata;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A response body streamed to a file by `MeteomaticsConnector::query_to_file` instead of being
+/// kept in memory.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FileResponse {
+    pub path: PathBuf,
+    pub bytes_written: u64,
+    pub content_type: Option<String>,
+    pub http_status_code: String,
+    pub http_status_message: String,
+    pub metadata: ResponseMetadata,
+}
@@ -0,0 +1,228 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{RecordValue, ResponseBody, ResponseIndex};
+use crate::valid_date_time::PeriodTime;
+use serde::{Deserialize, Serialize};
+
+/// How `ResponseBody::fill_gaps` synthesizes missing values.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum FillMethod {
+    /// Repeats the last known value.
+    ForwardFill,
+    /// Interpolates linearly in time between the known values before and after.
+    Linear,
+}
+
+/// A response with its gaps filled, marking what was synthesized.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilledResponse {
+    pub response_body: ResponseBody,
+    /// Per record, whether it was inserted for a missing time step.
+    pub inserted: Vec<bool>,
+    /// Per record and value column, whether the value was synthesized.
+    pub synthesized: Vec<Vec<bool>>,
+}
+
+impl ResponseBody {
+    /// Fills the gaps of every location's time series: with `step`, a record is inserted for every
+    /// missing time step between two records; then missing and NaN values of numeric columns are
+    /// filled by `method`. Values before the first known one, and for `FillMethod::Linear` after
+    /// the last, stay missing; text values are kept. The records are grouped by location.
+    pub fn fill_gaps(
+        &self,
+        step: Option<PeriodTime>,
+        method: FillMethod,
+    ) -> Result<FilledResponse, ConnectorError> {
+        let step_seconds = match step {
+            Some(step) if step.num_seconds() <= 0 => {
+                return Err(ConnectorError::LibraryError(format!(
+                    "Cannot fill gaps of non-positive time step {}.",
+                    step
+                )));
+            }
+            step => step.map(|step| chrono::Duration::seconds(step.num_seconds())),
+        };
+        let num_columns = self.value_headers().len();
+
+        let mut filled = FilledResponse {
+            response_body: ResponseBody {
+                response_headers: self.response_headers.clone(),
+                response_records: vec![],
+            },
+            inserted: vec![],
+            synthesized: vec![],
+        };
+        for (_, location_body) in self.split_by_location() {
+            let mut records: Vec<(ResponseIndex, Vec<RecordValue>)> = vec![];
+            let mut inserted = vec![];
+            for (index, values) in location_body.response_records {
+                if let (Some(step), Some((previous, _))) = (step_seconds, records.last()) {
+                    let mut valid_date = previous.valid_date + step;
+                    let location = previous.location.clone();
+                    while valid_date < index.valid_date {
+                        let gap = ResponseIndex {
+                            location: location.clone(),
+                            valid_date,
+                        };
+                        records.push((gap, vec![RecordValue::Missing; num_columns]));
+                        inserted.push(true);
+                        valid_date += step;
+                    }
+                }
+                records.push((index, values));
+                inserted.push(false);
+            }
+
+            let mut synthesized = vec![vec![false; num_columns]; records.len()];
+            for column in 0..num_columns {
+                fill_column(&mut records, &mut synthesized, column, method);
+            }
+            filled.response_body.response_records.extend(records);
+            filled.inserted.extend(inserted);
+            filled.synthesized.extend(synthesized);
+        }
+        Ok(filled)
+    }
+}
+
+/// Whether `value` is absent, missing or NaN.
+pub(crate) fn is_gap(value: Option<&RecordValue>) -> bool {
+    match value {
+        None | Some(RecordValue::Missing) => true,
+        Some(RecordValue::Number(number)) => number.is_nan(),
+        Some(RecordValue::Text(_)) => false,
+    }
+}
+
+/// Fills the gaps of `column` in the records of one location.
+fn fill_column(
+    records: &mut [(ResponseIndex, Vec<RecordValue>)],
+    synthesized: &mut [Vec<bool>],
+    column: usize,
+    method: FillMethod,
+) {
+    let known: Vec<(usize, f64)> = records
+        .iter()
+        .enumerate()
+        .filter_map(|(n, (_, values))| {
+            let number = values.get(column)?.as_f64()?;
+            (!number.is_nan()).then_some((n, number))
+        })
+        .collect();
+    let mut next_known = 0;
+    for n in 0..records.len() {
+        while next_known < known.len() && known[next_known].0 < n {
+            next_known += 1;
+        }
+        if !is_gap(records[n].1.get(column)) {
+            continue;
+        }
+        let before = next_known.checked_sub(1).map(|k| known[k]);
+        let after = known.get(next_known).copied();
+        let fill = match (method, before, after) {
+            (FillMethod::ForwardFill, Some((_, value)), _) => value,
+            (FillMethod::Linear, Some((b, before)), Some((a, after))) => {
+                let elapsed = seconds_between(records, b, n);
+                let span = seconds_between(records, b, a);
+                before + (after - before) * elapsed / span
+            }
+            _ => continue,
+        };
+        let values = &mut records[n].1;
+        if values.len() <= column {
+            values.resize(column + 1, RecordValue::Missing);
+        }
+        values[column] = RecordValue::Number(fill);
+        synthesized[n][column] = true;
+    }
+}
+
+fn seconds_between(records: &[(ResponseIndex, Vec<RecordValue>)], from: usize, to: usize) -> f64 {
+    (records[to].0.valid_date - records[from].0.valid_date).num_seconds() as f64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{RecordValue, ResponseBody};
+    use crate::gap_filling::FillMethod;
+    use crate::valid_date_time::PeriodTime;
+
+    #[tokio::test]
+    async fn fill_gaps_of_time_series() {
+        println!("\n##### fill_gaps_of_time_series:");
+
+        let response_body = ResponseBody {
+            response_headers: vec!["validdate".to_string(), "t_2m:C".to_string()],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![RecordValue::Missing],
+                ),
+                ("2023-11-20T01:00:00Z".parse().unwrap(), vec![2.0.into()]),
+                (
+                    "2023-11-20T02:00:00Z".parse().unwrap(),
+                    vec![f64::NAN.into()],
+                ),
+                ("2023-11-20T05:00:00Z".parse().unwrap(), vec![8.0.into()]),
+            ],
+        };
+
+        let linear = response_body
+            .fill_gaps(Some(PeriodTime::Hours(1)), FillMethod::Linear)
+            .unwrap();
+        println!(">>>>>>>>>> linear:\n{}", linear.response_body);
+
+        assert_eq!(linear.response_body.response_records.len(), 6);
+        assert_eq!(
+            linear.response_body.response_records[3].0.valid_date,
+            "2023-11-20T03:00:00Z"
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap()
+        );
+        let values: Vec<RecordValue> = linear
+            .response_body
+            .response_records
+            .iter()
+            .map(|(_, values)| values[0].clone())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                RecordValue::Missing,
+                2.0.into(),
+                3.5.into(),
+                5.0.into(),
+                6.5.into(),
+                8.0.into()
+            ]
+        );
+        assert_eq!(
+            linear.inserted,
+            vec![false, false, false, true, true, false]
+        );
+        assert_eq!(
+            linear.synthesized,
+            vec![
+                vec![false],
+                vec![false],
+                vec![true],
+                vec![true],
+                vec![true],
+                vec![false]
+            ]
+        );
+
+        let forward = response_body
+            .fill_gaps(None, FillMethod::ForwardFill)
+            .unwrap();
+        assert_eq!(forward.response_body.response_records.len(), 4);
+        assert_eq!(
+            forward.response_body.response_records[2].1,
+            vec![2.0.into()]
+        );
+        assert_eq!(forward.inserted, vec![false; 4]);
+        assert!(response_body
+            .fill_gaps(Some(PeriodTime::Hours(0)), FillMethod::Linear)
+            .is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::connector_response::ResponseBody;
+use crate::grid::Grid;
+use serde_json::{json, Map, Value};
+
+impl ResponseBody {
+    /// Converts the time series into a GeoJSON `FeatureCollection` with one `Point` feature per
+    /// record, carrying the valid date and the values as properties. Records without a coordinate
+    /// of their own, i.e. of a single-location or station response, are placed at `lat`/`lon`.
+    pub fn to_geojson(&self, lat: f64, lon: f64) -> Value {
+        let (index_header, value_headers) = match self.response_headers.split_first() {
+            Some((index_header, value_headers)) => (index_header.as_str(), value_headers),
+            None => ("validdate", &[] as &[String]),
+        };
+
+        let features: Vec<Value> = self
+            .response_records
+            .iter()
+            .map(|(index, values)| {
+                let mut properties = Map::new();
+                if let Some(station_id) = index.station_id() {
+                    properties.insert("station_id".to_string(), json!(station_id));
+                }
+                properties.insert(index_header.to_string(), json!(index.valid_date));
+                for (header, value) in value_headers.iter().zip(values.iter()) {
+                    properties.insert(header.to_string(), json!(value));
+                }
+                let (lat, lon) = index.coordinate().unwrap_or((lat, lon));
+                point_feature(lat, lon, properties)
+            })
+            .collect();
+
+        feature_collection(features)
+    }
+}
+
+impl Grid {
+    /// Converts the grid into a GeoJSON `FeatureCollection` with one `Point` feature per cell.
+    pub fn to_geojson(&self) -> Value {
+        let parameter = self.parameter.as_deref().unwrap_or("value");
+        let mut features: Vec<Value> = vec![];
+        for (i, lat) in self.lats.iter().enumerate() {
+            for (j, lon) in self.lons.iter().enumerate() {
+                let mut properties = Map::new();
+                if let Some(valid_date) = &self.valid_date {
+                    properties.insert("validdate".to_string(), json!(valid_date));
+                }
+                properties.insert(parameter.to_string(), json!(self.values[[i, j]]));
+                features.push(point_feature(*lat, *lon, properties));
+            }
+        }
+
+        feature_collection(features)
+    }
+}
+
+fn point_feature(lat: f64, lon: f64, properties: Map<String, Value>) -> Value {
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "Point",
+            "coordinates": [lon, lat],
+        },
+        "properties": properties,
+    })
+}
+
+fn feature_collection(features: Vec<Value>) -> Value {
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::ResponseBody;
+    use crate::grid::Grid;
+
+    #[tokio::test]
+    async fn time_series_to_geojson() {
+        println!("\n##### time_series_to_geojson:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "precip_1h:mm".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![3.4.into(), 0.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![3.1.into(), 0.12.into()],
+                ),
+            ],
+        };
+
+        let geojson = response_body.to_geojson(47.419708, 9.358478);
+        println!(">>>>>>>>>> geojson: {}", geojson);
+
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[1]["geometry"]["coordinates"][0], 9.358478);
+        assert_eq!(
+            features[1]["properties"]["validdate"],
+            "2023-11-20T01:00:00Z"
+        );
+        assert_eq!(features[1]["properties"]["precip_1h:mm"], 0.12);
+    }
+
+    #[tokio::test]
+    async fn grid_to_geojson() {
+        println!("\n##### grid_to_geojson:");
+
+        let grid = Grid::from_csv(
+            "Valid date = 2023-11-20T12:00:00Z\nParameter = t_2m:C\ndata;9.3;9.4\n47.5;4.1;4.3\n",
+        )
+        .unwrap();
+
+        let geojson = grid.to_geojson();
+        println!(">>>>>>>>>> geojson: {}", geojson);
+
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[1]["geometry"]["coordinates"][0], 9.4);
+        assert_eq!(features[1]["geometry"]["coordinates"][1], 47.5);
+        assert_eq!(features[1]["properties"]["t_2m:C"], 4.3);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::connector_response::parse_f64;
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+/// Single parameter at a single valid date time sampled on a regular lat/lon grid.
+///
+/// `values` is indexed as `[lat, lon]`, following the order of `lats` and `lons`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Grid {
+    pub valid_date: Option<String>,
+    pub parameter: Option<String>,
+    pub lats: Vec<f64>,
+    pub lons: Vec<f64>,
+    pub values: Array2<f64>,
+}
+
+impl Grid {
+    /// Parses the grid CSV returned by the API: `Key = Value` metadata lines, a `data;<lon>;...`
+    /// header and one `<lat>;<value>;...` row per latitude.
+    pub fn from_csv(body: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut valid_date: Option<String> = None;
+        let mut parameter: Option<String> = None;
+        let mut lines = body.lines().filter(|line| !line.trim().is_empty());
+
+        let header = loop {
+            let line = lines.next().ok_or("Grid response has no data header")?;
+            if line.starts_with("data") {
+                break line;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                match key.trim() {
+                    "Valid date" => valid_date = Some(value.trim().to_string()),
+                    "Parameter" => parameter = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+        };
+
+        let lons = header
+            .split(';')
+            .skip(1)
+            .map(parse_f64)
+            .collect::<Result<Vec<f64>, _>>()?;
+
+        let rows: Vec<&str> = lines.collect();
+        let threads = parsing_threads(body.len());
+        let (lats, values) = parse_rows_in_parallel(&rows, lons.len(), threads)?;
+
+        let values = Array2::from_shape_vec((lats.len(), lons.len()), values)?;
+        Ok(Self {
+            valid_date,
+            parameter,
+            lats,
+            lons,
+            values,
+        })
+    }
+}
+
+/// Bodies from this size on are parsed by several threads.
+#[cfg(not(target_arch = "wasm32"))]
+const PARALLEL_PARSING_BYTES: usize = 1 << 20;
+
+type RowsResult = Result<(Vec<f64>, Vec<f64>), Box<dyn std::error::Error + Send + Sync>>;
+
+/// Threads to parse a body of `body_bytes` with: one per core for large bodies, otherwise, and
+/// on wasm targets, just the calling thread.
+fn parsing_threads(
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))] body_bytes: usize,
+) -> usize {
+    #[cfg(not(target_arch = "wasm32"))]
+    if body_bytes >= PARALLEL_PARSING_BYTES {
+        return std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    }
+    1
+}
+
+/// Parses `rows` in one contiguous range per thread and concatenates the latitudes and values of
+/// the ranges in order.
+fn parse_rows_in_parallel(rows: &[&str], num_lons: usize, threads: usize) -> RowsResult {
+    if threads <= 1 || rows.len() <= 1 {
+        return parse_rows(rows, num_lons);
+    }
+    let parsed: Vec<RowsResult> = std::thread::scope(|scope| {
+        let handles: Vec<_> = rows
+            .chunks(rows.len().div_ceil(threads))
+            .map(|range| scope.spawn(move || parse_rows(range, num_lons)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    let mut lats = Vec::with_capacity(rows.len());
+    let mut values = Vec::with_capacity(rows.len() * num_lons);
+    for range in parsed {
+        let (range_lats, range_values) = range?;
+        lats.extend(range_lats);
+        values.extend(range_values);
+    }
+    Ok((lats, values))
+}
+
+/// Parses `<lat>;<value>;...` rows into their latitudes and the values in row-major order.
+fn parse_rows(rows: &[&str], num_lons: usize) -> RowsResult {
+    let mut lats: Vec<f64> = Vec::with_capacity(rows.len());
+    let mut values: Vec<f64> = Vec::with_capacity(rows.len() * num_lons);
+    for line in rows {
+        let mut cells = line.split(';');
+        let lat = cells.next().ok_or("Grid row has no latitude")?;
+        lats.push(parse_f64(lat)?);
+        let row_start = values.len();
+        for value in cells {
+            values.push(parse_f64(value)?);
+        }
+        let row_len = values.len() - row_start;
+        if row_len != num_lons {
+            return Err(format!(
+                "Grid row at latitude {} has {} values for {} longitudes",
+                lat, row_len, num_lons
+            )
+            .into());
+        }
+    }
+    Ok((lats, values))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::grid::{parse_rows_in_parallel, Grid};
+
+    #[tokio::test]
+    async fn parse_grid_csv() {
+        println!("\n##### parse_grid_csv:");
+
+        let body = "Valid date = 2023-11-20T12:00:00Z\n\
+                    Parameter = t_2m:C\n\
+                    data;9.3;9.4;9.5\n\
+                    47.5;4.1;4.3;4.0\n\
+                    47.4;5.2;5.0;4.8\n";
+
+        let grid = Grid::from_csv(body).unwrap();
+        println!(">>>>>>>>>> grid: {:?}", grid);
+
+        assert_eq!(grid.valid_date.as_deref(), Some("2023-11-20T12:00:00Z"));
+        assert_eq!(grid.parameter.as_deref(), Some("t_2m:C"));
+        assert_eq!(grid.lats, vec![47.5, 47.4]);
+        assert_eq!(grid.lons, vec![9.3, 9.4, 9.5]);
+        assert_eq!(grid.values.dim(), (2, 3));
+        assert_eq!(grid.values[[1, 2]], 4.8);
+    }
+
+    #[tokio::test]
+    async fn parse_grid_rows_in_parallel() {
+        println!("\n##### parse_grid_rows_in_parallel:");
+
+        let size = 50;
+        let rows: Vec<String> = (0..size)
+            .map(|lat| {
+                let mut row = lat.to_string();
+                for lon in 0..size {
+                    row.push_str(&format!(";{}.25", lat * size + lon));
+                }
+                row
+            })
+            .collect();
+        let mut rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+
+        let sequential = parse_rows_in_parallel(&rows, size, 1).unwrap();
+        let parallel = parse_rows_in_parallel(&rows, size, 4).unwrap();
+        println!(">>>>>>>>>> parallel.0: {:?}", parallel.0);
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.0[size - 1], (size - 1) as f64);
+        assert_eq!(parallel.1[size * size - 1], (size * size - 1) as f64 + 0.25);
+
+        rows[40] = "40;1.0";
+        let error = parse_rows_in_parallel(&rows, size, 4).unwrap_err();
+        assert!(error.to_string().contains("latitude 40"));
+    }
+
+    #[tokio::test]
+    async fn reject_ragged_grid() {
+        println!("\n##### reject_ragged_grid:");
+
+        let body = "data;9.3;9.4\n47.5;4.1\n";
+
+        assert!(Grid::from_csv(body).is_err());
+    }
+}
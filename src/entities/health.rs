@@ -0,0 +1,21 @@
+use crate::connector_error::{ApiErrorDetail, ConnectorError};
+
+/// Outcome of `MeteomaticsConnector::ping`.
+#[derive(Debug)]
+pub enum Health {
+    /// The API answered an authenticated request.
+    Healthy,
+    /// The API was reached but rejected the credentials.
+    AuthFailed {
+        status: reqwest::StatusCode,
+        detail: ApiErrorDetail,
+    },
+    /// The API could not be reached, timed out or failed to answer.
+    Unreachable(ConnectorError),
+}
+
+impl Health {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Health::Healthy)
+    }
+}
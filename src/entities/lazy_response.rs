@@ -0,0 +1,207 @@
+use crate::columnar::ColumnarBody;
+use crate::connector_components::format::Format;
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{
+    check_value_columns, ConnectorResponse, LocationColumns, Record, RecordValue, ResponseBody,
+    ResponseHeader, ResponseIndex, ResponseMetadata,
+};
+use crate::missing_values::MissingValuePolicy;
+use bytes::Bytes;
+use csv::{Reader, ReaderBuilder, StringRecord};
+use std::ops::Range;
+
+/// A CSV time series response from `MeteomaticsConnector::query_time_series_lazy` that keeps the
+/// raw body and parses records only when asked for, by row or by column. Reading one column
+/// parses the numbers of that column alone.
+#[derive(Clone, Debug)]
+pub struct LazyResponse {
+    pub http_status_code: String,
+    pub http_status_message: String,
+    pub metadata: ResponseMetadata,
+    response_headers: ResponseHeader,
+    body: Bytes,
+    location_columns: LocationColumns,
+    /// Byte ranges of the records in `body`.
+    records: Vec<Range<usize>>,
+    missing_value_policy: MissingValuePolicy,
+}
+
+impl LazyResponse {
+    /// Finds the records of `body`, every line after the header line, without parsing them.
+    pub(crate) fn index(
+        body: Bytes,
+        response_headers: ResponseHeader,
+        missing_value_policy: MissingValuePolicy,
+    ) -> Result<Self, ConnectorError> {
+        let mut rdr = reader(&body);
+        let mut record = StringRecord::new();
+        let mut location_columns = None;
+        let mut starts = vec![];
+        while rdr.read_record(&mut record).map_err(parse_error)? {
+            if location_columns.is_none() {
+                let columns = LocationColumns::from_headers(&record);
+                let value_headers = response_headers.get(1..).unwrap_or_default();
+                check_value_columns(&record, columns, value_headers).map_err(|source| {
+                    ConnectorError::Parse {
+                        format: Format::CSV,
+                        source,
+                    }
+                })?;
+                location_columns = Some(columns);
+            } else if let Some(position) = record.position() {
+                starts.push(position.byte() as usize);
+            }
+        }
+        let ends = starts.iter().skip(1).copied().chain([body.len()]);
+        let records = starts
+            .iter()
+            .zip(ends)
+            .map(|(&start, end)| start..end)
+            .collect();
+        Ok(Self {
+            http_status_code: Default::default(),
+            http_status_message: Default::default(),
+            metadata: Default::default(),
+            response_headers,
+            body,
+            location_columns: location_columns.unwrap_or(LocationColumns::None),
+            records,
+            missing_value_policy,
+        })
+    }
+
+    /// The `validdate` header followed by the value headers, as in `ResponseBody`.
+    pub fn response_headers(&self) -> &[String] {
+        &self.response_headers
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Parses the record at position `n`, or returns `None` past the last record.
+    pub fn record(&self, n: usize) -> Result<Option<Record>, ConnectorError> {
+        let Some(range) = self.records.get(n) else {
+            return Ok(None);
+        };
+        let mut record = StringRecord::new();
+        reader(&self.body[range.clone()])
+            .read_record(&mut record)
+            .map_err(parse_error)?;
+        let (index, values) = self.parse_record(&record)?;
+        Ok(Some(Record {
+            location: index.location,
+            valid_date: index.valid_date,
+            values,
+        }))
+    }
+
+    /// Parses the values of the column with header `name`, e.g. `t_2m:C`, of all records.
+    pub fn column(&self, name: &str) -> Result<Vec<RecordValue>, ConnectorError> {
+        let n = self
+            .response_headers
+            .iter()
+            .skip(1)
+            .position(|header| header == name)
+            .ok_or_else(|| ConnectorError::LibraryError(format!("No column {}.", name)))?;
+        let field = self.location_columns.count() + 1 + n;
+        let mut values = Vec::with_capacity(self.records.len());
+        self.for_each_record(|record| {
+            values.push(match record.get(field) {
+                Some(raw) => self.parse_value(raw),
+                None => RecordValue::Missing,
+            });
+            Ok(())
+        })?;
+        Ok(values)
+    }
+
+    /// Parses all records into a `ConnectorResponse`, as the eager queries return it.
+    pub fn parse(&self) -> Result<ConnectorResponse, ConnectorError> {
+        let mut response_body = ResponseBody::new();
+        response_body.response_headers = self.response_headers.clone();
+        self.for_each_record(|record| {
+            response_body
+                .response_records
+                .push(self.parse_record(record)?);
+            Ok(())
+        })?;
+        response_body.validate_monotonic()?;
+        Ok(ConnectorResponse {
+            response_body,
+            http_status_code: self.http_status_code.clone(),
+            http_status_message: self.http_status_message.clone(),
+            metadata: self.metadata.clone(),
+        })
+    }
+
+    /// Parses all records straight into columns, without a `Vec` per record.
+    pub fn parse_columnar(&self) -> Result<ColumnarBody, ConnectorError> {
+        let mut columnar = ColumnarBody::new(self.response_headers.clone());
+        self.for_each_record(|record| {
+            let (index, fields) = self.parse_index(record)?;
+            columnar.push_record(index, fields.map(|field| self.parse_value(field)));
+            Ok(())
+        })?;
+        Ok(columnar)
+    }
+
+    /// Reads the records in order with a single reader.
+    fn for_each_record(
+        &self,
+        mut visit: impl FnMut(&StringRecord) -> Result<(), ConnectorError>,
+    ) -> Result<(), ConnectorError> {
+        let Some(first) = self.records.first() else {
+            return Ok(());
+        };
+        let mut rdr = reader(&self.body[first.start..]);
+        let mut record = StringRecord::new();
+        while rdr.read_record(&mut record).map_err(parse_error)? {
+            visit(&record)?;
+        }
+        Ok(())
+    }
+
+    fn parse_record(
+        &self,
+        record: &StringRecord,
+    ) -> Result<(ResponseIndex, Vec<RecordValue>), ConnectorError> {
+        let (index, fields) = self.parse_index(record)?;
+        Ok((index, fields.map(|field| self.parse_value(field)).collect()))
+    }
+
+    fn parse_index<'r>(
+        &self,
+        record: &'r StringRecord,
+    ) -> Result<(ResponseIndex, csv::StringRecordIter<'r>), ConnectorError> {
+        self.location_columns
+            .parse_index(record)
+            .map_err(|source| ConnectorError::Parse {
+                format: Format::CSV,
+                source,
+            })
+    }
+
+    fn parse_value(&self, raw: &str) -> RecordValue {
+        self.missing_value_policy.apply(RecordValue::parse(raw))
+    }
+}
+
+fn reader(bytes: &[u8]) -> Reader<&[u8]> {
+    ReaderBuilder::new()
+        .delimiter(b';')
+        .has_headers(false)
+        .from_reader(bytes)
+}
+
+fn parse_error(error: csv::Error) -> ConnectorError {
+    ConnectorError::Parse {
+        format: Format::CSV,
+        source: Box::new(error),
+    }
+}
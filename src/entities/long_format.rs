@@ -0,0 +1,147 @@
+use crate::connector_response::{RecordValue, ResponseBody, ResponseIndex, ResponseLocation};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One value of a response in long format: a single parameter at a single valid date and
+/// location, as a database table with one row per observation stores it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LongRecord {
+    pub location: Option<ResponseLocation>,
+    pub valid_date: DateTime<Utc>,
+    pub parameter: String,
+    pub value: RecordValue,
+}
+
+/// A response in long format, with one `LongRecord` per value instead of one column per
+/// parameter.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LongBody {
+    pub records: Vec<LongRecord>,
+}
+
+impl LongBody {
+    /// Pivots the records back to one column per parameter, in the order the parameters and
+    /// the records first appear. Values absent from the long records become missing values.
+    pub fn to_wide(&self) -> ResponseBody {
+        let mut response_body = ResponseBody::new();
+        response_body.add_header("validdate".to_string());
+        for record in self.records.iter() {
+            let column = match response_body
+                .value_headers()
+                .iter()
+                .position(|header| *header == record.parameter)
+            {
+                Some(column) => column,
+                None => {
+                    response_body.add_header(record.parameter.clone());
+                    response_body.value_headers().len() - 1
+                }
+            };
+            let index = ResponseIndex {
+                location: record.location.clone(),
+                valid_date: record.valid_date,
+            };
+            let records = &mut response_body.response_records;
+            let position = match records.iter().position(|(other, _)| *other == index) {
+                Some(position) => position,
+                None => {
+                    records.push((index, vec![]));
+                    records.len() - 1
+                }
+            };
+            let values = &mut records[position].1;
+            if values.len() <= column {
+                values.resize(column + 1, RecordValue::Missing);
+            }
+            values[column] = record.value.clone();
+        }
+
+        let num_columns = response_body.value_headers().len();
+        for (_, values) in response_body.response_records.iter_mut() {
+            values.resize(num_columns, RecordValue::Missing);
+        }
+        response_body
+    }
+}
+
+impl ResponseBody {
+    /// The values of this body in long format, record by record and within a record in the
+    /// order of the headers.
+    pub fn to_long(&self) -> LongBody {
+        let records = self
+            .response_records
+            .iter()
+            .flat_map(|(index, values)| {
+                self.value_headers()
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(parameter, value)| LongRecord {
+                        location: index.location.clone(),
+                        valid_date: index.valid_date,
+                        parameter: parameter.clone(),
+                        value: value.clone(),
+                    })
+            })
+            .collect();
+        LongBody { records }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{RecordValue, ResponseBody, ResponseLocation};
+    use crate::long_format::LongBody;
+
+    #[tokio::test]
+    async fn pivot_between_wide_and_long() {
+        println!("\n##### pivot_between_wide_and_long:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "precip_1h:mm".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![3.4.into(), 0.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![3.1.into(), RecordValue::Missing],
+                ),
+            ],
+        };
+
+        let long = response_body.to_long();
+        println!(">>>>>>>>>> long: {:?}", long);
+
+        assert_eq!(long.records.len(), 4);
+        assert_eq!(long.records[1].parameter, "precip_1h:mm");
+        assert_eq!(long.records[2].value, 3.1.into());
+        assert_eq!(long.to_wide(), response_body);
+
+        let mut partial = LongBody {
+            records: long.records[1..].to_vec(),
+        };
+        partial.records[0].location = Some(ResponseLocation::Station("LSZH".to_string()));
+        let wide = partial.to_wide();
+        println!(">>>>>>>>>> wide:\n{}", wide);
+
+        assert_eq!(
+            wide.response_headers,
+            vec!["validdate", "precip_1h:mm", "t_2m:C"]
+        );
+        assert_eq!(wide.response_records.len(), 2);
+        assert_eq!(
+            wide.response_records[0].1,
+            vec![0.0.into(), RecordValue::Missing]
+        );
+        assert_eq!(
+            wide.response_records[1].1,
+            vec![RecordValue::Missing, 3.1.into()]
+        );
+    }
+}
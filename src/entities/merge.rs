@@ -0,0 +1,210 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::{
+    ConnectorResponse, RecordValue, ResponseBody, ResponseIndex, ResponseLocation,
+};
+use crate::gap_filling::is_gap;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// The records of one location being merged, with the position of every valid date.
+type LocationRecords = (
+    Option<ResponseLocation>,
+    HashMap<DateTime<Utc>, usize>,
+    Vec<(ResponseIndex, Vec<RecordValue>)>,
+);
+
+impl ResponseBody {
+    /// Merges bodies with different parameters or locations into one body with the union of
+    /// their columns and records. Records with the same valid date and location are joined;
+    /// values absent from a body become missing values. The records of every location are kept
+    /// together, in order of first appearance, and sorted by valid date. Fails if two bodies
+    /// hold different values for the same parameter and record, missing values aside.
+    pub fn merge(
+        bodies: impl IntoIterator<Item = ResponseBody>,
+    ) -> Result<ResponseBody, ConnectorError> {
+        let mut merged = ResponseBody::new();
+        merged.add_header("validdate".to_string());
+        let mut locations: Vec<LocationRecords> = vec![];
+        for body in bodies {
+            let columns: Vec<usize> = body
+                .value_headers()
+                .iter()
+                .map(|header| {
+                    match merged
+                        .value_headers()
+                        .iter()
+                        .position(|merged_header| merged_header == header)
+                    {
+                        Some(column) => column,
+                        None => {
+                            merged.add_header(header.clone());
+                            merged.value_headers().len() - 1
+                        }
+                    }
+                })
+                .collect();
+
+            for (index, values) in body.response_records {
+                let position = match locations
+                    .iter()
+                    .position(|(location, _, _)| *location == index.location)
+                {
+                    Some(position) => position,
+                    None => {
+                        locations.push((index.location.clone(), HashMap::new(), vec![]));
+                        locations.len() - 1
+                    }
+                };
+                let (_, positions, records) = &mut locations[position];
+                let record = *positions.entry(index.valid_date).or_insert_with(|| {
+                    records.push((index.clone(), vec![]));
+                    records.len() - 1
+                });
+                let merged_values = &mut records[record].1;
+                for (&column, value) in columns.iter().zip(values) {
+                    if merged_values.len() <= column {
+                        merged_values.resize(column + 1, RecordValue::Missing);
+                    }
+                    let merged_value = &mut merged_values[column];
+                    if is_gap(Some(merged_value)) {
+                        *merged_value = value;
+                    } else if !is_gap(Some(&value)) && value != *merged_value {
+                        return Err(ConnectorError::LibraryError(format!(
+                            "Cannot merge different values {} and {} of {} at {}.",
+                            merged_value,
+                            value,
+                            merged.response_headers[column + 1],
+                            index
+                        )));
+                    }
+                }
+            }
+        }
+
+        let num_columns = merged.value_headers().len();
+        for (_, _, mut records) in locations {
+            records.sort_by_key(|(index, _)| index.valid_date);
+            for (_, values) in records.iter_mut() {
+                values.resize(num_columns, RecordValue::Missing);
+            }
+            merged.response_records.extend(records);
+        }
+        Ok(merged)
+    }
+}
+
+impl ConnectorResponse {
+    /// Merges responses with different parameters or locations, e.g. of split queries or of
+    /// several sources, as `ResponseBody::merge` does. The status and metadata are those of the
+    /// first response, with the elapsed time of all of them.
+    pub fn merge(responses: Vec<ConnectorResponse>) -> Result<ConnectorResponse, ConnectorError> {
+        let mut responses = responses.into_iter();
+        let mut merged = responses
+            .next()
+            .ok_or_else(|| ConnectorError::LibraryError("No responses to merge.".to_string()))?;
+        let mut bodies = vec![std::mem::take(&mut merged.response_body)];
+        for response in responses {
+            merged.metadata.elapsed += response.metadata.elapsed;
+            bodies.push(response.response_body);
+        }
+        merged.response_body = ResponseBody::merge(bodies)?;
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{
+        ConnectorResponse, RecordValue, ResponseBody, ResponseIndex, ResponseLocation,
+        ResponseMetadata,
+    };
+
+    fn response(parameter: &str, records: Vec<(ResponseIndex, f64)>) -> ConnectorResponse {
+        ConnectorResponse {
+            response_body: ResponseBody {
+                response_headers: vec!["validdate".to_string(), parameter.to_string()],
+                response_records: records
+                    .into_iter()
+                    .map(|(index, value)| (index, vec![value.into()]))
+                    .collect(),
+            },
+            http_status_code: "200".to_string(),
+            http_status_message: "OK".to_string(),
+            metadata: ResponseMetadata::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn merge_responses() {
+        println!("\n##### merge_responses:");
+
+        let station = |valid_date: &str| ResponseIndex {
+            location: Some(ResponseLocation::Station("LSZH".to_string())),
+            valid_date: valid_date.parse().unwrap(),
+        };
+        let temperature = response(
+            "t_2m:C",
+            vec![
+                (station("2023-11-20T00:00:00Z"), 3.4),
+                (station("2023-11-20T01:00:00Z"), 3.1),
+            ],
+        );
+        let precipitation = response(
+            "precip_1h:mm",
+            vec![
+                (station("2023-11-20T01:00:00Z"), 0.2),
+                (station("2023-11-20T02:00:00Z"), 0.0),
+            ],
+        );
+        let other_station = response(
+            "t_2m:C",
+            vec![(
+                ResponseIndex {
+                    location: Some(ResponseLocation::Station("LSGG".to_string())),
+                    valid_date: "2023-11-20T00:00:00Z".parse().unwrap(),
+                },
+                5.0,
+            )],
+        );
+
+        let merged =
+            ConnectorResponse::merge(vec![temperature.clone(), precipitation, other_station])
+                .unwrap();
+        println!(">>>>>>>>>> merged:\n{}", merged.response_body);
+
+        assert_eq!(
+            merged.response_body.response_headers,
+            vec!["validdate", "t_2m:C", "precip_1h:mm"]
+        );
+        assert_eq!(
+            merged.response_body.response_records,
+            vec![
+                (
+                    station("2023-11-20T00:00:00Z"),
+                    vec![3.4.into(), RecordValue::Missing]
+                ),
+                (
+                    station("2023-11-20T01:00:00Z"),
+                    vec![3.1.into(), 0.2.into()]
+                ),
+                (
+                    station("2023-11-20T02:00:00Z"),
+                    vec![RecordValue::Missing, 0.0.into()]
+                ),
+                (
+                    ResponseIndex {
+                        location: Some(ResponseLocation::Station("LSGG".to_string())),
+                        valid_date: "2023-11-20T00:00:00Z".parse().unwrap(),
+                    },
+                    vec![5.0.into(), RecordValue::Missing]
+                ),
+            ]
+        );
+        assert!(merged.response_body.validate_monotonic().is_ok());
+
+        let conflicting = response("t_2m:C", vec![(station("2023-11-20T01:00:00Z"), 9.9)]);
+        assert!(ConnectorResponse::merge(vec![temperature, conflicting]).is_err());
+        assert!(ConnectorResponse::merge(vec![]).is_err());
+    }
+}
@@ -1,2 +1,28 @@
+pub mod aggregation;
+pub mod alignment;
+#[cfg(feature = "arrow")]
+mod arrow_export;
+pub mod binary_response;
+pub mod columnar;
+pub mod comparison;
 pub mod connector_error;
 pub mod connector_response;
+pub mod derived_quantities;
+pub mod ensemble_statistics;
+mod file_export;
+pub mod file_response;
+pub mod gap_filling;
+mod geojson;
+pub mod grid;
+pub mod health;
+pub mod lazy_response;
+pub mod long_format;
+pub mod merge;
+#[cfg(feature = "netcdf")]
+pub mod netcdf_data;
+#[cfg(feature = "parquet")]
+mod parquet_export;
+pub mod raw_response;
+pub mod station;
+pub mod table;
+pub mod time_range;
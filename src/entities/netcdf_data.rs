@@ -0,0 +1,128 @@
+use crate::connector_error::ConnectorError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Grid time series decoded from a NetCDF payload.
+///
+/// Each entry of `variables` holds one parameter, flattened in `time`, `lat`, `lon` order.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NetCDFData {
+    pub time: Vec<f64>,
+    pub time_units: Option<String>,
+    pub lat: Vec<f64>,
+    pub lon: Vec<f64>,
+    pub variables: Vec<(String, Vec<f64>)>,
+}
+
+const TIME: &str = "time";
+const LAT: &str = "lat";
+const LON: &str = "lon";
+
+/// Distinguishes the temporary files of concurrent `NetCDFData::from_bytes` calls.
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+impl NetCDFData {
+    /// Decodes a NetCDF payload held in memory, e.g. `BinaryResponse::bytes`. The NetCDF library
+    /// reads from files, so the payload passes through a temporary file that is removed again.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ConnectorError> {
+        let path = std::env::temp_dir().join(format!(
+            "meteomatics-{}-{}.nc",
+            std::process::id(),
+            TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, bytes)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        let decoded = Self::from_file(&path);
+        let _ = std::fs::remove_file(&path);
+        decoded
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConnectorError> {
+        let file =
+            netcdf::open(path).map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+
+        let time_units = match file.variable(TIME) {
+            Some(variable) => match variable.attribute("units").map(|units| units.value()) {
+                Some(Ok(netcdf::AttrValue::Str(units))) => Some(units),
+                _ => None,
+            },
+            None => None,
+        };
+
+        let mut variables = vec![];
+        for variable in file.variables() {
+            let name = variable.name();
+            if name == TIME || name == LAT || name == LON {
+                continue;
+            }
+            variables.push((name, read_values(&file, &variable.name())?));
+        }
+
+        Ok(Self {
+            time: read_values(&file, TIME)?,
+            time_units,
+            lat: read_values(&file, LAT)?,
+            lon: read_values(&file, LON)?,
+            variables,
+        })
+    }
+}
+
+fn read_values(file: &netcdf::File, name: &str) -> Result<Vec<f64>, ConnectorError> {
+    let variable = file.variable(name).ok_or_else(|| {
+        ConnectorError::LibraryError(format!("NetCDF payload has no `{}` variable.", name))
+    })?;
+    variable
+        .values::<f64, _>(..)
+        .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::netcdf_data::NetCDFData;
+
+    #[tokio::test]
+    async fn decode_netcdf_from_bytes() {
+        println!("\n##### decode_netcdf_from_bytes:");
+
+        let path = std::env::temp_dir().join("decode_netcdf_from_bytes.nc");
+        {
+            let mut file = netcdf::create(&path).unwrap();
+            file.add_dimension("time", 2).unwrap();
+            file.add_dimension("lat", 1).unwrap();
+            file.add_dimension("lon", 2).unwrap();
+            let mut time = file.add_variable::<f64>("time", &["time"]).unwrap();
+            time.put_values(&[0.0, 1.0], ..).unwrap();
+            time.put_attribute("units", "hours since 2023-11-20 00:00:00")
+                .unwrap();
+            let mut lat = file.add_variable::<f64>("lat", &["lat"]).unwrap();
+            lat.put_values(&[47.4], ..).unwrap();
+            let mut lon = file.add_variable::<f64>("lon", &["lon"]).unwrap();
+            lon.put_values(&[9.3, 9.4], ..).unwrap();
+            let mut t_2m = file
+                .add_variable::<f64>("t_2m", &["time", "lat", "lon"])
+                .unwrap();
+            t_2m.put_values(&[3.4, 3.5, 3.1, 3.2], ..).unwrap();
+        }
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let netcdf_data = NetCDFData::from_bytes(&bytes).unwrap();
+        println!(">>>>>>>>>> netcdf_data: {:?}", netcdf_data);
+
+        assert_eq!(netcdf_data.time, vec![0.0, 1.0]);
+        assert_eq!(
+            netcdf_data.time_units.as_deref(),
+            Some("hours since 2023-11-20 00:00:00")
+        );
+        assert_eq!(netcdf_data.lat, vec![47.4]);
+        assert_eq!(netcdf_data.lon, vec![9.3, 9.4]);
+        assert_eq!(
+            netcdf_data.variables,
+            vec![("t_2m".to_string(), vec![3.4, 3.5, 3.1, 3.2])]
+        );
+        assert!(NetCDFData::from_bytes(b"CDF\x01 payload").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::ResponseBody;
+use arrow_array::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use std::path::Path;
+
+impl ResponseBody {
+    /// Writes the time series as a Parquet file with a UTC `validdate` timestamp column followed
+    /// by one float64 column per value header.
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> Result<(), ConnectorError> {
+        let batch = RecordBatch::try_from(self)?;
+        let file = std::fs::File::create(path)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        writer
+            .write(&batch)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        writer
+            .close()
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::ResponseBody;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    #[tokio::test]
+    async fn write_parquet_file() {
+        println!("\n##### write_parquet_file:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "precip_1h:mm".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![3.4.into(), 0.0.into()],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![3.1.into(), 0.12.into()],
+                ),
+            ],
+        };
+
+        let path = std::env::temp_dir().join("write_parquet_file.parquet");
+        response_body.to_parquet(&path).unwrap();
+        println!(">>>>>>>>>> path: {:?}", path);
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 2);
+        assert_eq!(metadata.schema_descr().num_columns(), 3);
+        assert_eq!(metadata.schema_descr().column(1).name(), "t_2m:C");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+use crate::connector_error::ConnectorError;
+use crate::connector_response::ResponseMetadata;
+use serde::{Deserialize, Serialize};
+
+/// The untouched response to a query from `MeteomaticsConnector::query_raw`, whatever its format
+/// and status, for formats or parsing the crate does not support.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RawResponse {
+    pub body: Vec<u8>,
+    pub content_type: Option<String>,
+    pub http_status_code: String,
+    pub http_status_message: String,
+    /// Request URL and all response headers.
+    pub metadata: ResponseMetadata,
+}
+
+impl RawResponse {
+    /// The body as UTF-8 text.
+    pub fn text(&self) -> Result<&str, ConnectorError> {
+        std::str::from_utf8(&self.body)
+            .map_err(|error| ConnectorError::GenericError(Box::new(error)))
+    }
+
+    /// The value of the response header `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.metadata
+            .response_headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use csv::Reader;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+
+/// A measuring station as listed by the `find_station` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Station {
+    /// E.g. `SYNOP` or `METAR`.
+    pub category: String,
+    pub station_type: String,
+    pub id_hash: String,
+    pub wmo_id: Option<String>,
+    pub alternative_ids: Vec<String>,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Elevation in metres.
+    pub elevation: Option<f64>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+impl Station {
+    /// Reads the `;`-separated rows returned by the API, finding the columns by their header.
+    pub fn from_reader<R: Read>(
+        rdr: &mut Reader<R>,
+    ) -> Result<Vec<Station>, Box<dyn std::error::Error + Send + Sync>> {
+        let headers = rdr.headers()?.clone();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header.trim() == name)
+                .ok_or_else(|| format!("Missing station column: {}", name))
+        };
+        let category = column("Station Category")?;
+        let station_type = column("Station Type")?;
+        let id_hash = column("ID Hash")?;
+        let wmo_id = column("WMO ID")?;
+        let alternative_ids = column("Alternative IDs")?;
+        let name = column("Name")?;
+        let location = column("Location Lat,Lon")?;
+        let elevation = column("Elevation")?;
+        let start_date = column("Start Date")?;
+        let end_date = column("End Date")?;
+
+        let mut stations = vec![];
+        for record in rdr.records() {
+            let record = record?;
+            let field = |index: usize| record.get(index).unwrap_or_default().trim();
+            let (lat, lon) = field(location)
+                .split_once(',')
+                .ok_or_else(|| format!("Unexpected station location: {:?}", record))?;
+            let date = |index: usize| {
+                DateTime::parse_from_rfc3339(field(index))
+                    .ok()
+                    .map(|date| date.with_timezone(&Utc))
+            };
+            stations.push(Station {
+                category: field(category).to_string(),
+                station_type: field(station_type).to_string(),
+                id_hash: field(id_hash).to_string(),
+                wmo_id: Some(field(wmo_id))
+                    .filter(|wmo_id| !wmo_id.is_empty())
+                    .map(str::to_string),
+                alternative_ids: field(alternative_ids)
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                name: field(name).to_string(),
+                lat: lat.trim().parse()?,
+                lon: lon.trim().parse()?,
+                elevation: field(elevation).trim_end_matches('m').parse().ok(),
+                start_date: date(start_date),
+                end_date: date(end_date),
+            });
+        }
+        Ok(stations)
+    }
+}
+
+impl Display for Station {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}, {})", self.name, self.lat, self.lon)?;
+        if let Some(wmo_id) = &self.wmo_id {
+            write!(f, " WMO {}", wmo_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::station::Station;
+
+    #[tokio::test]
+    async fn parse_station_list() {
+        println!("\n##### parse_station_list:");
+
+        let body = "Station Category;Station Type;ID Hash;WMO ID;Alternative IDs;Name;\
+                    Location Lat,Lon;Elevation;Start Date;End Date;Horizontal Distance;\
+                    Vertical Distance;Effective Distance\n\
+                    SYNOP;SYNO;1159846848;066810;LSZG;St. Gallen;47.4255,9.39837;779m;\
+                    1981-01-01T00:00:00Z;2023-11-20T00:00:00Z;0;0;0\n\
+                    METAR;META;3149127620;;LSZR;St. Gallen-Altenrhein;47.485,9.561;398m;\
+                    2000-01-01T00:00:00Z;-;0;0;0\n";
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(body.as_bytes());
+        let stations = Station::from_reader(&mut rdr).unwrap();
+        println!(">>>>>>>>>> stations: {:#?}", stations);
+
+        assert_eq!(stations.len(), 2);
+        assert_eq!(stations[0].wmo_id.as_deref(), Some("066810"));
+        assert_eq!(stations[0].elevation, Some(779.0));
+        assert_eq!(
+            stations[0].to_string(),
+            "St. Gallen (47.4255, 9.39837) WMO 066810"
+        );
+        assert_eq!(stations[1].wmo_id, None);
+        assert_eq!(stations[1].alternative_ids, vec!["LSZR"]);
+        assert_eq!(stations[1].end_date, None);
+    }
+}
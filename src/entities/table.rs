@@ -0,0 +1,241 @@
+use crate::connector_response::ResponseBody;
+use crate::derived_quantities::parameter_and_unit;
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+
+/// How `ResponseBody::table` renders a response.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TableOptions {
+    /// Records shown before the rest is summarized in one line; `None` shows all of them.
+    pub max_rows: Option<usize>,
+    /// Value columns shown before the rest is summarized in one line; `None` shows all of them.
+    pub max_columns: Option<usize>,
+    /// Cells longer than this many characters are cut off with `…`.
+    pub max_cell_width: usize,
+    /// Shows the units, e.g. `C` of `t_2m:C`, in a row of their own below the parameters.
+    pub units_row: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            max_rows: Some(20),
+            max_columns: Some(10),
+            max_cell_width: 24,
+            units_row: true,
+        }
+    }
+}
+
+/// A response rendered as a table with aligned columns by its `Display` implementation.
+#[derive(Clone, Copy, Debug)]
+pub struct Table<'a> {
+    response_body: &'a ResponseBody,
+    options: &'a TableOptions,
+}
+
+impl ResponseBody {
+    /// Renders this body as a table for logs and interactive exploration, e.g.
+    /// `println!("{}", response_body.table(&TableOptions::default()))`. Unlike the `Display` of
+    /// the body itself, which prints every value, the table aligns the columns and is cut down by
+    /// `options`.
+    pub fn table<'a>(&'a self, options: &'a TableOptions) -> Table<'a> {
+        Table {
+            response_body: self,
+            options,
+        }
+    }
+}
+
+impl Table<'_> {
+    fn cell(&self, text: String) -> String {
+        let max_width = self.options.max_cell_width.max(1);
+        if text.chars().count() <= max_width {
+            return text;
+        }
+        let mut cell: String = text.chars().take(max_width - 1).collect();
+        cell.push('…');
+        cell
+    }
+}
+
+impl std::fmt::Display for Table<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let body = self.response_body;
+        let value_headers = body.value_headers();
+        let num_columns = self
+            .options
+            .max_columns
+            .map_or(value_headers.len(), |max| max.min(value_headers.len()));
+        let num_rows = self
+            .options
+            .max_rows
+            .map_or(body.response_records.len(), |max| {
+                max.min(body.response_records.len())
+            });
+        let records = &body.response_records[..num_rows];
+        let with_location = body
+            .response_records
+            .iter()
+            .any(|(index, _)| index.location.is_some());
+
+        // The index columns are aligned left, the value columns right.
+        let mut headers = vec![];
+        let mut units = vec![];
+        if with_location {
+            headers.push("location".to_string());
+            units.push(String::new());
+        }
+        headers.push(
+            body.response_headers
+                .first()
+                .cloned()
+                .unwrap_or_else(|| "validdate".to_string()),
+        );
+        units.push(String::new());
+        let num_index_columns = headers.len();
+        for header in value_headers[..num_columns].iter() {
+            if self.options.units_row {
+                let (parameter, unit) = parameter_and_unit(header);
+                headers.push(self.cell(parameter.to_string()));
+                units.push(self.cell(unit.to_string()));
+            } else {
+                headers.push(self.cell(header.clone()));
+            }
+        }
+
+        let rows: Vec<Vec<String>> = records
+            .iter()
+            .map(|(index, values)| {
+                let mut row = vec![];
+                if with_location {
+                    let location = index.location.as_ref().map(ToString::to_string);
+                    row.push(self.cell(location.unwrap_or_default()));
+                }
+                row.push(index.valid_date.to_rfc3339_opts(SecondsFormat::Secs, true));
+                for n in 0..num_columns {
+                    let value = values.get(n).map(ToString::to_string);
+                    row.push(self.cell(value.unwrap_or_default()));
+                }
+                row
+            })
+            .collect();
+
+        let mut widths: Vec<usize> = headers
+            .iter()
+            .map(|header| header.chars().count())
+            .collect();
+        for row in rows.iter().chain(std::iter::once(&units)) {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let write_row = |f: &mut std::fmt::Formatter<'_>, row: &[String]| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths.iter())
+                .enumerate()
+                .map(|(n, (cell, &width))| {
+                    if n < num_index_columns {
+                        format!("{:<width$}", cell)
+                    } else {
+                        format!("{:>width$}", cell)
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())
+        };
+
+        write_row(f, &headers)?;
+        if self.options.units_row {
+            write_row(f, &units)?;
+        }
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        writeln!(f, "{}", rule.join("  "))?;
+        for row in rows.iter() {
+            write_row(f, row)?;
+        }
+        if num_columns < value_headers.len() {
+            writeln!(f, "... {} more columns", value_headers.len() - num_columns)?;
+        }
+        if num_rows < body.response_records.len() {
+            writeln!(
+                f,
+                "... {} more records",
+                body.response_records.len() - num_rows
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connector_response::{RecordValue, ResponseBody};
+    use crate::table::TableOptions;
+
+    #[tokio::test]
+    async fn render_table() {
+        println!("\n##### render_table:");
+
+        let response_body = ResponseBody {
+            response_headers: vec![
+                "validdate".to_string(),
+                "t_2m:C".to_string(),
+                "precip_1h:mm".to_string(),
+                "metar".to_string(),
+            ],
+            response_records: vec![
+                (
+                    "2023-11-20T00:00:00Z".parse().unwrap(),
+                    vec![
+                        3.4.into(),
+                        RecordValue::Missing,
+                        RecordValue::Text("METAR LSZH 200020Z".to_string()),
+                    ],
+                ),
+                (
+                    "2023-11-20T01:00:00Z".parse().unwrap(),
+                    vec![(-12.25).into(), 0.1.into(), RecordValue::Missing],
+                ),
+                (
+                    "2023-11-20T02:00:00Z".parse().unwrap(),
+                    vec![2.9.into(), 0.0.into(), RecordValue::Missing],
+                ),
+            ],
+        };
+
+        let table = response_body.table(&TableOptions::default()).to_string();
+        println!(">>>>>>>>>> table:\n{}", table);
+
+        assert_eq!(
+            table,
+            "validdate               t_2m  precip_1h               metar\n\
+             \x20                          C         mm\n\
+             --------------------  ------  ---------  ------------------\n\
+             2023-11-20T00:00:00Z     3.4             METAR LSZH 200020Z\n\
+             2023-11-20T01:00:00Z  -12.25        0.1\n\
+             2023-11-20T02:00:00Z     2.9          0\n"
+        );
+
+        let options = TableOptions {
+            max_rows: Some(1),
+            max_columns: Some(1),
+            max_cell_width: 3,
+            units_row: false,
+        };
+        let table = response_body.table(&options).to_string();
+        println!(">>>>>>>>>> table:\n{}", table);
+
+        assert_eq!(
+            table,
+            "validdate             t_…\n\
+             --------------------  ---\n\
+             2023-11-20T00:00:00Z  3.4\n\
+             ... 2 more columns\n\
+             ... 2 more records\n"
+        );
+    }
+}
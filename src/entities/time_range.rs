@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use csv::Reader;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+
+/// Availability window of a single parameter as reported by the `get_time_range` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TimeRange {
+    pub parameter: String,
+    pub min_date: DateTime<Utc>,
+    pub max_date: DateTime<Utc>,
+}
+
+pub type TimeRanges = Vec<TimeRange>;
+
+impl TimeRange {
+    /// Reads the `parameter;min_date;max_date` rows returned by the API.
+    pub fn from_reader<R: Read>(
+        rdr: &mut Reader<R>,
+    ) -> Result<TimeRanges, Box<dyn std::error::Error + Send + Sync>> {
+        let mut time_ranges = TimeRanges::new();
+        for record in rdr.records() {
+            let record = record?;
+            if record.len() < 3 {
+                return Err(format!("Unexpected time range record: {:?}", record).into());
+            }
+            time_ranges.push(TimeRange {
+                parameter: record[0].to_string(),
+                min_date: DateTime::parse_from_rfc3339(&record[1])?.with_timezone(&Utc),
+                max_date: DateTime::parse_from_rfc3339(&record[2])?.with_timezone(&Utc),
+            });
+        }
+        Ok(time_ranges)
+    }
+}
+
+impl Display for TimeRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}--{}",
+            self.parameter,
+            self.min_date.to_rfc3339(),
+            self.max_date.to_rfc3339()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::time_range::TimeRange;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn parse_time_ranges() {
+        println!("\n##### parse_time_ranges:");
+
+        let body = "parameter;min_date;max_date\n\
+                    t_2m:C;1979-01-01T00:00:00Z;2023-11-20T00:00:00Z\n\
+                    precip_1h:mm;2000-01-01T00:00:00Z;2023-11-18T12:00:00Z\n";
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(body.as_bytes());
+
+        let time_ranges = TimeRange::from_reader(&mut rdr).unwrap();
+        for time_range in time_ranges.iter() {
+            println!(">>>>>>>>>> time_range: {}", time_range);
+        }
+
+        assert_eq!(time_ranges.len(), 2);
+        assert_eq!(time_ranges[0].parameter, "t_2m:C");
+        assert_eq!(
+            time_ranges[0].min_date,
+            Utc.with_ymd_and_hms(1979, 1, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            time_ranges[1].max_date,
+            Utc.with_ymd_and_hms(2023, 11, 18, 12, 0, 0).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_malformed_dates() {
+        println!("\n##### reject_malformed_dates:");
+
+        let body = "parameter;min_date;max_date\nt_2m:C;yesterday;today\n";
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(body.as_bytes());
+
+        assert!(TimeRange::from_reader(&mut rdr).is_err());
+    }
+}
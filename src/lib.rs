@@ -1,17 +1,49 @@
 mod configuration;
 mod connector_components;
 mod entities;
+#[cfg(all(any(test, feature = "test-support"), not(target_arch = "wasm32")))]
+pub mod test_support;
 
+pub use crate::configuration::cache::CacheOptions;
+pub use crate::configuration::circuit_breaker::CircuitBreakerOptions;
+pub use crate::configuration::config::Config;
+pub use crate::configuration::connector_builder::ConnectorBuilder;
+pub use crate::configuration::pool::PoolOptions;
+pub use crate::configuration::progress::{Progress, ProgressHook};
+pub use crate::configuration::rate_limiter::Priority;
+pub use crate::configuration::recording::{RecordingMode, RecordingOptions};
+pub use crate::configuration::redirect::RedirectOptions;
+pub use crate::configuration::request_limits::{CostEstimate, RequestLimits};
+pub use crate::configuration::retry::RetryOptions;
+pub use crate::configuration::secret::SecretString;
+pub use crate::configuration::timeouts::TimeoutOptions;
+pub use crate::configuration::tls::{TlsBackend, TlsOptions};
 pub use crate::connector_components::*;
 pub use crate::entities::*;
 
+use crate::binary_response::BinaryResponse;
+use crate::chunked_query::ChunkedQuery;
 use crate::configuration::api_client::APIClient;
 use crate::connector_error::ConnectorError;
 use crate::connector_response::ConnectorResponse;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::file_response::FileResponse;
+use crate::format::Format;
+use crate::grid::Grid;
+use crate::health::Health;
+use crate::lazy_response::LazyResponse;
 use crate::locations::Locations;
+use crate::missing_values::MissingValuePolicy;
 use crate::optionals::Optionals;
-use crate::parameters::Parameters;
-use crate::valid_date_time::ValidDateTime;
+use crate::parameters::{ParameterValidation, Parameters, P};
+use crate::query_spec::QuerySpec;
+use crate::raw_response::RawResponse;
+use crate::station::Station;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::station_catalog::StationCatalog;
+use crate::time_range::TimeRanges;
+use crate::time_series_query::TimeSeriesQuery;
+use crate::valid_date_time::{DatePrecision, ValidDateTime};
 
 #[macro_use]
 extern crate derive_builder;
@@ -22,12 +54,184 @@ pub struct MeteomaticsConnector {
 }
 
 impl MeteomaticsConnector {
+    /// Starts a `ConnectorBuilder`; `username` and `password` are required.
+    pub fn builder() -> ConnectorBuilder {
+        ConnectorBuilder::default()
+    }
+
+    /// Creates a connector from the `METEOMATICS_*` environment variables, see
+    /// `ConnectorBuilder::from_env`.
+    pub fn from_env() -> Result<Self, ConnectorError> {
+        ConnectorBuilder::from_env()?.build()
+    }
+
+    /// Creates a connector from a TOML or YAML configuration file, see `Config::from_file`.
+    pub fn from_config_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ConnectorError> {
+        Config::from_file(path)?.builder().build()
+    }
+
     pub fn new(username: String, password: String, timeout_seconds: u64) -> Self {
         Self {
-            api_client: APIClient::new(username, password, timeout_seconds),
+            api_client: APIClient::new(username, password.into(), timeout_seconds),
         }
     }
 
+    /// Swaps the username and password of this connector and all its clones at once, e.g. when
+    /// a long-running service rotates its credentials, without rebuilding the HTTP client or
+    /// dropping pooled connections.
+    pub fn rotate_credentials(&self, username: String, password: String) {
+        self.api_client
+            .rotate_credentials(username, password.into());
+    }
+
+    /// Points the connector at a different API base URL, e.g. an on-premise installation.
+    pub fn with_base_url(mut self, base_url: &str) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_base_url(base_url)?;
+        Ok(self)
+    }
+
+    /// Inserts a path, e.g. an API version or the sub-path of a gateway, between the base URL
+    /// and every query.
+    pub fn with_path_prefix(mut self, path_prefix: &str) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_path_prefix(path_prefix)?;
+        Ok(self)
+    }
+
+    /// Sets how sentinel values such as `-666` and empty cells appear in parsed responses.
+    pub fn with_missing_value_policy(mut self, missing_value_policy: MissingValuePolicy) -> Self {
+        self.api_client = self
+            .api_client
+            .with_missing_value_policy(missing_value_policy);
+        self
+    }
+
+    /// Rebuilds the HTTP client with custom TLS settings, see `TlsOptions`.
+    pub fn with_tls(mut self, tls_options: TlsOptions) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_tls(tls_options)?;
+        Ok(self)
+    }
+
+    /// Sets connect and read timeouts besides the total request timeout, see `TimeoutOptions`.
+    pub fn with_timeouts(
+        mut self,
+        timeout_options: TimeoutOptions,
+    ) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_timeouts(timeout_options)?;
+        Ok(self)
+    }
+
+    /// Rebuilds the HTTP client with custom connection pool settings, see `PoolOptions`.
+    pub fn with_pool(mut self, pool_options: PoolOptions) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_pool(pool_options)?;
+        Ok(self)
+    }
+
+    /// Sets which failed requests are retried and the time budget for retries, see
+    /// `RetryOptions`.
+    pub fn with_retry_options(mut self, retry_options: RetryOptions) -> Self {
+        self.api_client = self.api_client.with_retry_options(retry_options);
+        self
+    }
+
+    /// Sets how redirects are followed and whether the credentials are sent along, see
+    /// `RedirectOptions`.
+    pub fn with_redirects(mut self, redirect_options: RedirectOptions) -> Self {
+        self.api_client = self.api_client.with_redirects(redirect_options);
+        self
+    }
+
+    /// Answers repeated identical requests from a cache of successful responses, see
+    /// `CacheOptions`.
+    pub fn with_cache(mut self, cache_options: CacheOptions) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_cache(cache_options)?;
+        Ok(self)
+    }
+
+    /// Fails fast for a cool-down period after consecutive failed requests instead of hammering a
+    /// degraded API, see `CircuitBreakerOptions`.
+    pub fn with_circuit_breaker(
+        mut self,
+        circuit_breaker_options: CircuitBreakerOptions,
+    ) -> Result<Self, ConnectorError> {
+        self.api_client = self
+            .api_client
+            .with_circuit_breaker(circuit_breaker_options)?;
+        Ok(self)
+    }
+
+    /// Records responses to disk, or replays recorded ones without using the network, see
+    /// `RecordingOptions`.
+    pub fn with_recording(
+        mut self,
+        recording_options: RecordingOptions,
+    ) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_recording(recording_options)?;
+        Ok(self)
+    }
+
+    /// Reports the bytes downloaded and records parsed as response bodies arrive, e.g. to drive a
+    /// progress bar. Applies to all clones made afterwards, so clone the connector first to
+    /// watch a single query.
+    pub fn with_progress(mut self, progress_hook: ProgressHook) -> Self {
+        self.api_client = self.api_client.with_progress(progress_hook);
+        self
+    }
+
+    /// Lets the requests of this connector wait for the rate and concurrency limits with
+    /// `priority`. Clones share the limits, so e.g. a `Priority::Batch` clone for a backfill
+    /// yields to interactive queries of the original.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.api_client = self.api_client.with_priority(priority);
+        self
+    }
+
+    /// Splits time series queries exceeding `request_limits` into several requests and stitches
+    /// their responses back into one.
+    pub fn with_request_limits(mut self, request_limits: RequestLimits) -> Self {
+        self.api_client = self.api_client.with_request_limits(request_limits);
+        self
+    }
+
+    /// Writes the date times of request URLs with `date_precision`.
+    pub fn with_date_precision(mut self, date_precision: DatePrecision) -> Self {
+        self.api_client = self.api_client.with_date_precision(date_precision);
+        self
+    }
+
+    /// Checks the parameters of every request with `parameter_validation` before sending it.
+    pub fn with_parameter_validation(mut self, parameter_validation: ParameterValidation) -> Self {
+        self.api_client = self
+            .api_client
+            .with_parameter_validation(parameter_validation);
+        self
+    }
+
+    /// Sends `user_agent` as the `User-Agent` header of every request.
+    pub fn with_user_agent(mut self, user_agent: &str) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_user_agent(user_agent)?;
+        Ok(self)
+    }
+
+    /// Adds the `(name, value)` headers to every request, e.g. tracing headers for a proxy.
+    pub fn with_default_headers(
+        mut self,
+        default_headers: Vec<(String, String)>,
+    ) -> Result<Self, ConnectorError> {
+        self.api_client = self.api_client.with_default_headers(default_headers)?;
+        Ok(self)
+    }
+
+    /// Checks the credentials and the connection with a minimal request that uses no quota, e.g.
+    /// to verify the configuration when a service starts.
+    pub async fn ping(&self) -> Health {
+        self.api_client.ping().await
+    }
+
+    /// Starts a fluent time series query, see `TimeSeriesQuery`.
+    pub fn time_series(&self) -> TimeSeriesQuery<'_> {
+        TimeSeriesQuery::new(self)
+    }
+
     pub async fn query_time_series(
         &self,
         vdt: ValidDateTime,
@@ -39,19 +243,220 @@ impl MeteomaticsConnector {
             .query_time_series(vdt, parameters, locations, optionals)
             .await
     }
+
+    pub async fn query_time_series_with_format(
+        &self,
+        vdt: ValidDateTime,
+        parameters: Parameters<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+        format: Format,
+    ) -> Result<ConnectorResponse, ConnectorError> {
+        self.api_client
+            .query_time_series_with_format(vdt, parameters, locations, optionals, format)
+            .await
+    }
+
+    pub async fn query_time_series_windows(
+        &self,
+        vdts: Vec<ValidDateTime>,
+        parameters: Parameters<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+    ) -> Result<ConnectorResponse, ConnectorError> {
+        self.api_client
+            .query_time_series_windows(vdts, parameters, locations, optionals)
+            .await
+    }
+
+    /// Runs the time series query described by `query_spec`, e.g. one read from a file with
+    /// `OwnedQuerySpec::as_query_spec`.
+    pub async fn query(
+        &self,
+        query_spec: QuerySpec<'_>,
+    ) -> Result<ConnectorResponse, ConnectorError> {
+        self.api_client.query(query_spec).await
+    }
+
+    /// Counts the data points of `query_spec`, locations × parameters × time steps, without
+    /// sending it; fails if they exceed `RequestLimits::max_data_points`.
+    pub fn estimate_cost(
+        &self,
+        query_spec: &QuerySpec<'_>,
+    ) -> Result<CostEstimate, ConnectorError> {
+        self.api_client.estimate_cost(query_spec)
+    }
+
+    /// The URLs `query` would request for `query_spec`, without sending it, e.g. to debug a
+    /// query or to try it in the API documentation. Credentials are left out.
+    pub fn request_urls(&self, query_spec: &QuerySpec<'_>) -> Result<Vec<String>, ConnectorError> {
+        self.api_client.request_urls(query_spec)
+    }
+
+    /// Runs independent time series queries concurrently, at most `parallelism` at a time, and
+    /// returns one result per query in input order.
+    pub async fn query_time_series_batch(
+        &self,
+        query_specs: Vec<QuerySpec<'_>>,
+        parallelism: usize,
+    ) -> Vec<Result<ConnectorResponse, ConnectorError>> {
+        self.api_client
+            .query_time_series_batch(query_specs, parallelism)
+            .await
+    }
+
+    /// Runs a long-range query chunk by chunk, at most `parallelism` chunks at a time, and
+    /// concatenates the records. After a failure, calling this again with the same
+    /// `chunked_query` resumes with the missing chunks.
+    pub async fn query_chunked(
+        &self,
+        chunked_query: &mut ChunkedQuery,
+        parallelism: usize,
+    ) -> Result<ConnectorResponse, ConnectorError> {
+        self.api_client
+            .query_chunked(chunked_query, parallelism)
+            .await
+    }
+
+    /// Streams the response to the query, in its format, e.g. NetCDF for a large grid, straight
+    /// to the file at `path` without buffering it in memory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn query_to_file<P: AsRef<std::path::Path>>(
+        &self,
+        query_spec: QuerySpec<'_>,
+        path: P,
+    ) -> Result<FileResponse, ConnectorError> {
+        self.api_client
+            .query_to_file(query_spec, path.as_ref())
+            .await
+    }
+
+    /// Requests the time series as JSON and deserializes it straight into the caller's type,
+    /// e.g. a struct mirroring the API's `data` / `coordinates` / `dates` layout, without going
+    /// through a `ResponseBody`.
+    pub async fn query_time_series_as<T: serde::de::DeserializeOwned>(
+        &self,
+        vdt: ValidDateTime,
+        parameters: Parameters<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+    ) -> Result<T, ConnectorError> {
+        self.api_client
+            .query_time_series_as(vdt, parameters, locations, optionals)
+            .await
+    }
+
+    /// Requests the time series as CSV but parses records only on demand, by row or by column,
+    /// e.g. when only one of many parameters is needed right away.
+    pub async fn query_time_series_lazy(
+        &self,
+        vdt: ValidDateTime,
+        parameters: Parameters<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+    ) -> Result<LazyResponse, ConnectorError> {
+        self.api_client
+            .query_time_series_lazy(vdt, parameters, locations, optionals)
+            .await
+    }
+
+    /// Sends the query and returns the response body and headers untouched, whatever the format
+    /// and also for an HTTP error status, e.g. for formats the crate does not parse yet.
+    pub async fn query_raw(
+        &self,
+        query_spec: QuerySpec<'_>,
+    ) -> Result<RawResponse, ConnectorError> {
+        self.api_client.query_raw(query_spec).await
+    }
+
+    pub async fn query_grid(
+        &self,
+        vdt: ValidDateTime,
+        parameter: P<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+    ) -> Result<Grid, ConnectorError> {
+        self.api_client
+            .query_grid(vdt, parameter, locations, optionals)
+            .await
+    }
+
+    pub async fn query_grid_png(
+        &self,
+        vdt: ValidDateTime,
+        parameter: P<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+    ) -> Result<BinaryResponse, ConnectorError> {
+        self.api_client
+            .query_grid_png(vdt, parameter, locations, optionals)
+            .await
+    }
+
+    pub async fn query_netcdf(
+        &self,
+        vdt: ValidDateTime,
+        parameters: Parameters<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+    ) -> Result<BinaryResponse, ConnectorError> {
+        self.api_client
+            .query_netcdf(vdt, parameters, locations, optionals)
+            .await
+    }
+
+    pub async fn query_grads_plot(
+        &self,
+        vdt: ValidDateTime,
+        parameters: Parameters<'_>,
+        locations: Locations<'_>,
+        optionals: Option<Optionals<'_>>,
+    ) -> Result<BinaryResponse, ConnectorError> {
+        self.api_client
+            .query_grads_plot(vdt, parameters, locations, optionals)
+            .await
+    }
+
+    pub async fn query_time_range(
+        &self,
+        model: &str,
+        parameters: Parameters<'_>,
+    ) -> Result<TimeRanges, ConnectorError> {
+        self.api_client.query_time_range(model, parameters).await
+    }
+
+    /// Downloads the list of all stations; see `station_catalog` for cached lookups.
+    pub async fn query_station_list(&self) -> Result<Vec<Station>, ConnectorError> {
+        self.api_client.query_station_list().await
+    }
+
+    /// A catalog of the stations, downloaded on first use and again once `refresh_interval` has
+    /// passed, for repeated local lookups.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn station_catalog(&self, refresh_interval: std::time::Duration) -> StationCatalog {
+        StationCatalog::new(self.clone(), refresh_interval)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::connector_response::ResponseBody;
-    use crate::locations::{Coordinates, Locations};
-    use crate::optionals::{Opt, OptSet, Optionals};
-    use crate::parameters::{PSet, Parameters, P};
+    use crate::chunked_query::ChunkedQuery;
+    use crate::connector_error::ConnectorError;
+    use crate::connector_response::{ResponseBody, ResponseLocation};
+    use crate::format::Format;
+    use crate::health::Health;
+    use crate::locations::{Coordinates, Locations, OwnedLocations, Point};
+    use crate::optionals::{Opt, OptSet, Optionals, QueryOption};
+    use crate::parameters::{OwnedParameters, PSet, Parameters, P};
+    use crate::query_spec::{OwnedQuerySpec, QuerySpec};
+    use crate::test_support::{
+        MockResponse, MockServer, MULTI_POINT_CSV, TIME_SERIES_CSV, UNAUTHORIZED_JSON,
+    };
     use crate::valid_date_time::{
         PeriodDate, PeriodTime, VDTOffset, ValidDateTime, ValidDateTimeBuilder,
     };
-    use crate::MeteomaticsConnector;
+    use crate::{MeteomaticsConnector, RequestLimits};
     use chrono::{Duration, Utc};
     use std::iter::FromIterator;
 
@@ -68,7 +473,7 @@ mod tests {
 
         // Create ValidDateTime
         let now = Utc::now();
-        let yesterday = VDTOffset::Utc(now.clone() - Duration::days(1));
+        let yesterday = VDTOffset::Utc(now - Duration::days(1));
         let now = VDTOffset::Utc(now);
         let time_step = PeriodTime::Hours(1);
         let utc_vdt: ValidDateTime = ValidDateTimeBuilder::default()
@@ -84,18 +489,19 @@ mod tests {
                 P {
                     k: "t_2m",
                     v: Some("C"),
+                    source: None,
                 },
                 P {
                     k: "precip_1h",
                     v: Some("mm"),
+                    source: None,
                 },
             ]),
         };
 
         // Create Locations
-        let locations: Locations = Locations {
-            coordinates: Coordinates::from(["47.419708", "9.358478"]),
-        };
+        let locations: Locations =
+            Locations::Coordinates(Coordinates::from(["47.419708", "9.358478"]));
 
         // Create Optionals
         let optionals: Optionals = Optionals {
@@ -109,6 +515,7 @@ mod tests {
                     v: "true",
                 },
             ]),
+            query_options: vec![],
         };
 
         // Call endpoint
@@ -129,7 +536,7 @@ mod tests {
                     let (index, values) = row;
                     let values_str: Vec<_> =
                         values.to_vec().iter().map(ToString::to_string).collect();
-                    println!("{}", index.to_owned() + ": " + &values_str.join(","));
+                    println!("{}: {}", index, values_str.join(","));
                 }
                 assert_eq!(response.http_status_code, "200");
                 assert_eq!(response.http_status_message, "200 OK");
@@ -163,7 +570,7 @@ mod tests {
 
         // Create ValidDateTime
         let now = Utc::now();
-        let yesterday = VDTOffset::Utc(now.clone() - Duration::days(1));
+        let yesterday = VDTOffset::Utc(now - Duration::days(1));
         let now = VDTOffset::Utc(now);
         let period_date = PeriodDate::Days(1);
         let utc_vdt: ValidDateTime = ValidDateTimeBuilder::default()
@@ -179,18 +586,19 @@ mod tests {
                 P {
                     k: "t_2m",
                     v: Some("C"),
+                    source: None,
                 },
                 P {
                     k: "precip_1h",
                     v: Some("mm"),
+                    source: None,
                 },
             ]),
         };
 
         // Create Locations
-        let locations: Locations = Locations {
-            coordinates: Coordinates::from(["47.419708", "9.358478"]),
-        };
+        let locations: Locations =
+            Locations::Coordinates(Coordinates::from(["47.419708", "9.358478"]));
 
         // Call endpoint
         let result = meteomatics_connector
@@ -225,17 +633,25 @@ mod tests {
 
         // Create ValidDateTime
         let now = Utc::now();
-        let yesterday = VDTOffset::Utc(now.clone() - Duration::days(1));
+        let yesterday = VDTOffset::Utc(now - Duration::days(1));
         let now = VDTOffset::Utc(now);
         let period_date = PeriodDate::Days(1);
         let time_step = PeriodTime::Hours(1);
-        let utc_vdt: ValidDateTime = ValidDateTimeBuilder::default()
+        assert!(ValidDateTimeBuilder::default()
             .start_date_time(yesterday)
             .end_date_time(now)
             .period_date(period_date)
             .time_step(time_step)
             .build()
-            .unwrap();
+            .is_err());
+        let utc_vdt = ValidDateTime {
+            start_date_time: yesterday,
+            period_date: Some(period_date),
+            end_date_time: Some(now),
+            time_step: Some(time_step),
+            time_list: None,
+            repeat: None,
+        };
 
         // Create Parameters
         let parameters: Parameters = Parameters {
@@ -243,18 +659,19 @@ mod tests {
                 P {
                     k: "t_2m",
                     v: Some("C"),
+                    source: None,
                 },
                 P {
                     k: "precip_1h",
                     v: Some("mm"),
+                    source: None,
                 },
             ]),
         };
 
         // Create Locations
-        let locations: Locations = Locations {
-            coordinates: Coordinates::from(["47.419708", "9.358478"]),
-        };
+        let locations: Locations =
+            Locations::Coordinates(Coordinates::from(["47.419708", "9.358478"]));
 
         // Call endpoint
         let result = meteomatics_connector
@@ -269,6 +686,880 @@ mod tests {
             }
         }
 
-        assert!(!result.is_ok());
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn call_query_time_series_batch_keeps_input_order() {
+        println!("\n##### call_query_time_series_batch_keeps_input_order:");
+
+        let meteomatics_connector = MeteomaticsConnector::new(
+            "python-community".to_string(),
+            "Umivipawe179".to_string(),
+            10,
+        );
+
+        // Both queries are invalid, so they fail before any request is sent.
+        let start_date_time = VDTOffset::Utc(Utc::now());
+        let empty_time_list = ValidDateTime {
+            start_date_time,
+            period_date: None,
+            end_date_time: None,
+            time_step: None,
+            time_list: Some(vec![]),
+            repeat: None,
+        };
+        let period_and_time_step = ValidDateTime {
+            start_date_time,
+            period_date: Some(PeriodDate::Days(1)),
+            end_date_time: Some(start_date_time),
+            time_step: Some(PeriodTime::Hours(1)),
+            time_list: None,
+            repeat: None,
+        };
+        let query_specs: Vec<QuerySpec> = [empty_time_list, period_and_time_step]
+            .into_iter()
+            .map(|vdt| QuerySpec {
+                vdt,
+                parameters: Parameters {
+                    p_values: PSet::from_iter([P {
+                        k: "t_2m",
+                        v: Some("C"),
+                        source: None,
+                    }]),
+                },
+                locations: Locations::Coordinates(Coordinates::from(["47.42", "9.36"])),
+                optionals: None,
+                format: Format::CSV,
+            })
+            .collect();
+
+        let results = meteomatics_connector
+            .query_time_series_batch(query_specs, 2)
+            .await;
+        println!(">>>>>>>>>> results: {:?}", results);
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("Time list"));
+        assert!(results[1]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("period date and time step"));
+    }
+
+    fn time_series_query() -> (ValidDateTime, Parameters<'static>) {
+        let vdt: ValidDateTime = ValidDateTimeBuilder::default()
+            .start_date_time(VDTOffset::Utc("2023-11-20T00:00:00Z".parse().unwrap()))
+            .end_date_time(VDTOffset::Utc("2023-11-20T04:00:00Z".parse().unwrap()))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .unwrap();
+        let parameters: Parameters = Parameters {
+            p_values: PSet::from_iter([
+                P {
+                    k: "t_2m",
+                    v: Some("C"),
+                    source: None,
+                },
+                P {
+                    k: "precip_1h",
+                    v: Some("mm"),
+                    source: None,
+                },
+            ]),
+        };
+        (vdt, parameters)
+    }
+
+    #[tokio::test]
+    async fn query_time_series_from_mock_server() {
+        println!("\n##### query_time_series_from_mock_server:");
+
+        let server = MockServer::start(vec![("t_2m:C", MockResponse::csv(TIME_SERIES_CSV))]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+
+        let (vdt, parameters) = time_series_query();
+        let locations: Locations =
+            Locations::Coordinates(Coordinates::from(["47.419708", "9.358478"]));
+        let response = meteomatics_connector
+            .query_time_series(vdt, parameters, locations, None)
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response.response_body);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].path.ends_with("/47.419708,9.358478/csv"));
+        assert!(requests[0].header("authorization").is_some());
+        assert_eq!(response.http_status_code, "200");
+        assert_eq!(
+            response.response_body.response_headers,
+            vec!["validdate", "t_2m:C", "precip_1h:mm"]
+        );
+        // One record per parameter is skipped after the header line.
+        let rows: Vec<String> = response
+            .response_body
+            .response_records
+            .iter()
+            .map(|(index, values)| format!("{} {} {}", index, values[0], values[1]))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "2023-11-20T00:00:00Z 3.4 0",
+                "2023-11-20T01:00:00Z 3.1 0.12",
+                "2023-11-20T02:00:00Z 2.9 0.31",
+                "2023-11-20T03:00:00Z 2.6 0.08",
+                "2023-11-20T04:00:00Z 2.5 NaN",
+            ]
+        );
+        let precip = response.response_body.column("precip_1h:mm").unwrap();
+        assert_eq!(precip.len(), 5);
+        assert_eq!(precip[3], 0.08);
+        assert!(precip[4].is_nan());
+    }
+
+    #[tokio::test]
+    async fn query_multiple_points_from_mock_server() {
+        println!("\n##### query_multiple_points_from_mock_server:");
+
+        let server = MockServer::start(vec![("t_2m:C", MockResponse::csv(MULTI_POINT_CSV))]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+
+        let (vdt, _) = time_series_query();
+        let parameters = Parameters::try_from("t_2m:C").unwrap();
+        let locations: Locations = Locations::Points(vec![
+            Point::new(47.41, 9.35).unwrap(),
+            Point::new(47.51, 8.74).unwrap(),
+        ]);
+        let response = meteomatics_connector
+            .query_time_series(vdt, parameters, locations, None)
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response.response_body);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].path.ends_with("/47.41,9.35+47.51,8.74/csv"));
+        let rows: Vec<String> = response
+            .response_body
+            .response_records
+            .iter()
+            .map(|(index, values)| format!("{} {}", index, values[0]))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "47.41,9.35 2023-11-20T00:00:00Z 3.4",
+                "47.41,9.35 2023-11-20T01:00:00Z 3.1",
+                "47.51,8.74 2023-11-20T00:00:00Z 4.2",
+                "47.51,8.74 2023-11-20T01:00:00Z 4",
+            ]
+        );
+        let split = response.response_body.split_by_location();
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[0].1.column("t_2m:C"), Some(vec![3.4, 3.1]));
+        assert_eq!(split[1].1.column("t_2m:C"), Some(vec![4.2, 4.0]));
+    }
+
+    #[tokio::test]
+    async fn query_ensemble_members_from_mock_server() {
+        println!("\n##### query_ensemble_members_from_mock_server:");
+
+        let csv = "validdate;t_2m:C-m1;t_2m:C-m2;precip_1h:mm-m1;precip_1h:mm-m2\n\
+                   2023-11-20T00:00:00Z;3.4;3.6;0.0;0.1\n\
+                   2023-11-20T01:00:00Z;3.1;3.0;0.2;0.0\n";
+        let server = MockServer::start(vec![("t_2m:C", MockResponse::csv(csv))]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let (vdt, parameters) = time_series_query();
+        let locations = Locations::Coordinates(Coordinates::from(["47.41", "9.35"]));
+        let optionals = |ens_select: &str| {
+            Some(Optionals::from_options(vec![QueryOption::EnsSelect(
+                ens_select.parse().unwrap(),
+            )]))
+        };
+
+        let response = meteomatics_connector
+            .query_time_series(
+                vdt.clone(),
+                parameters.clone(),
+                locations.clone(),
+                optionals("member:1-2"),
+            )
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response.response_body);
+
+        assert_eq!(
+            response.response_body.response_headers,
+            vec![
+                "validdate",
+                "t_2m:C-m1",
+                "t_2m:C-m2",
+                "precip_1h:mm-m1",
+                "precip_1h:mm-m2"
+            ]
+        );
+        assert_eq!(response.response_body.response_records.len(), 2);
+        assert_eq!(
+            response.response_body.response_records[0].1,
+            vec![3.4.into(), 3.6.into(), 0.0.into(), 0.1.into()]
+        );
+        assert_eq!(
+            response.response_body.column("t_2m:C-m2"),
+            Some(vec![3.6, 3.0])
+        );
+
+        let lazy_response = meteomatics_connector
+            .query_time_series_lazy(
+                vdt.clone(),
+                parameters.clone(),
+                locations.clone(),
+                optionals("member:1-2"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(lazy_response.len(), 2);
+        assert_eq!(
+            lazy_response.column("precip_1h:mm-m1").unwrap(),
+            vec![0.0.into(), 0.2.into()]
+        );
+
+        let result = meteomatics_connector
+            .query_time_series(vdt, parameters, locations, optionals("member:1-3"))
+            .await;
+        println!(">>>>>>>>>> result: {:?}", result);
+        assert!(matches!(
+            result,
+            Err(ConnectorError::Parse {
+                format: Format::CSV,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn resume_chunked_query() {
+        println!("\n##### resume_chunked_query:");
+
+        let chunk_csv =
+            |date: &str| MockResponse::csv(format!("validdate;t_2m:C\n{}T00:00:00Z;3.4\n", date));
+        let failing = MockServer::start(vec![
+            (
+                "/2024-02-01T",
+                MockResponse::new(503, "text/plain", "Unavailable"),
+            ),
+            ("/2024-01-01T", chunk_csv("2024-01-01")),
+            ("/2024-03-01T", chunk_csv("2024-03-01")),
+        ])
+        .await;
+        let server = MockServer::start(vec![
+            ("/2024-01-01T", chunk_csv("2024-01-01")),
+            ("/2024-02-01T", chunk_csv("2024-02-01")),
+            ("/2024-03-01T", chunk_csv("2024-03-01")),
+        ])
+        .await;
+        let connector = |base_url: &str| {
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(base_url)
+                .unwrap()
+        };
+
+        let query_spec = OwnedQuerySpec {
+            vdt: ValidDateTimeBuilder::default()
+                .start_date_time(VDTOffset::Utc("2024-01-01T00:00:00Z".parse().unwrap()))
+                .end_date_time(VDTOffset::Utc("2024-03-31T00:00:00Z".parse().unwrap()))
+                .time_step(PeriodTime::Days(1))
+                .build()
+                .unwrap(),
+            parameters: "t_2m:C".parse().unwrap(),
+            locations: OwnedLocations::Point(Point::new(47.41, 9.35).unwrap()),
+            optionals: None,
+            format: Format::CSV,
+        };
+        let mut chunked_query = ChunkedQuery::new(query_spec, PeriodDate::Months(1)).unwrap();
+        assert_eq!(chunked_query.chunks().len(), 3);
+
+        let result = connector(failing.base_url())
+            .query_chunked(&mut chunked_query, 1)
+            .await;
+        assert!(matches!(result, Err(ConnectorError::Server { .. })));
+        assert_eq!(chunked_query.num_completed(), 1);
+
+        let response = connector(server.base_url())
+            .query_chunked(&mut chunked_query, 2)
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response.response_body);
+        assert!(chunked_query.is_complete());
+        assert_eq!(server.requests().len(), 2);
+        let dates: Vec<String> = response
+            .response_body
+            .response_records
+            .iter()
+            .map(|(index, _)| index.valid_date.format("%F").to_string())
+            .collect();
+        assert_eq!(dates, vec!["2024-01-01", "2024-02-01", "2024-03-01"]);
+    }
+
+    #[tokio::test]
+    async fn stream_response_to_file() {
+        println!("\n##### stream_response_to_file:");
+
+        let server = MockServer::start(vec![
+            (
+                "t_2m:C/47.5,9.3_47.4,9.4:0.1,0.1/netcdf",
+                MockResponse::new(200, "application/netcdf", "CDF\x01 payload"),
+            ),
+            (
+                "precip_1h:mm",
+                MockResponse::new(503, "text/plain", "Unavailable"),
+            ),
+        ])
+        .await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let query_spec = |parameter| QuerySpec {
+            vdt: ValidDateTime::single(VDTOffset::Utc("2023-11-20T12:00:00Z".parse().unwrap())),
+            parameters: Parameters::try_from(parameter).unwrap(),
+            locations: Locations::Coordinates(Coordinates::from(["47.5,9.3_47.4,9.4:0.1,0.1"])),
+            optionals: None,
+            format: Format::NetCDF,
+        };
+        let path = std::env::temp_dir().join("stream_response_to_file.nc");
+
+        let file_response = meteomatics_connector
+            .query_to_file(query_spec("t_2m:C"), &path)
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> file_response: {:?}", file_response);
+        assert_eq!(file_response.path, path);
+        assert_eq!(file_response.bytes_written, 12);
+        assert_eq!(
+            file_response.content_type.as_deref(),
+            Some("application/netcdf")
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), b"CDF\x01 payload");
+        std::fs::remove_file(&path).unwrap();
+
+        let result = meteomatics_connector
+            .query_to_file(query_spec("precip_1h:mm"), &path)
+            .await;
+        assert!(matches!(result, Err(ConnectorError::Server { .. })));
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn query_raw_response() {
+        println!("\n##### query_raw_response:");
+
+        let server = MockServer::start(vec![
+            (
+                "t_2m:C/47.4,9.4/xml",
+                MockResponse::new(200, "text/xml", "<meteomatics-api-response/>")
+                    .with_header("x-request-id", "42"),
+            ),
+            (
+                "precip_1h:mm",
+                MockResponse::new(503, "text/plain", "Unavailable"),
+            ),
+        ])
+        .await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let query_spec = |parameter| QuerySpec {
+            vdt: ValidDateTime::single(VDTOffset::Utc("2023-11-20T12:00:00Z".parse().unwrap())),
+            parameters: Parameters::try_from(parameter).unwrap(),
+            locations: Locations::Coordinates(Coordinates::from(["47.4,9.4"])),
+            optionals: None,
+            format: Format::XML,
+        };
+
+        let raw_response = meteomatics_connector
+            .query_raw(query_spec("t_2m:C"))
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> raw_response: {:?}", raw_response);
+        assert_eq!(raw_response.text().unwrap(), "<meteomatics-api-response/>");
+        assert_eq!(raw_response.content_type.as_deref(), Some("text/xml"));
+        assert_eq!(raw_response.header("X-Request-Id"), Some("42"));
+
+        let raw_response = meteomatics_connector
+            .query_raw(query_spec("precip_1h:mm"))
+            .await
+            .unwrap();
+        assert_eq!(raw_response.http_status_code, "503");
+        assert_eq!(raw_response.body, b"Unavailable");
+    }
+
+    #[tokio::test]
+    async fn parse_lazy_response_on_demand() {
+        println!("\n##### parse_lazy_response_on_demand:");
+
+        let server = MockServer::start(vec![
+            ("precip_1h:mm", MockResponse::csv(TIME_SERIES_CSV)),
+            ("t_2m:C", MockResponse::csv(MULTI_POINT_CSV)),
+        ])
+        .await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let (vdt, _) = time_series_query();
+        let parameters = Parameters::try_from("t_2m:C,precip_1h:mm").unwrap();
+        let locations = Locations::Coordinates(Coordinates::from(["47.41", "9.35"]));
+
+        let lazy_response = meteomatics_connector
+            .query_time_series_lazy(vdt.clone(), parameters.clone(), locations.clone(), None)
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> lazy_response.len(): {}", lazy_response.len());
+        assert_eq!(lazy_response.len(), 5);
+        assert_eq!(lazy_response.http_status_code, "200");
+        let precip = lazy_response.column("precip_1h:mm").unwrap();
+        assert_eq!(precip[2..4], [0.31.into(), 0.08.into()]);
+        assert!(precip[4].as_f64().unwrap().is_nan());
+        let record = lazy_response.record(3).unwrap().unwrap();
+        assert_eq!(
+            record.valid_date,
+            "2023-11-20T03:00:00Z"
+                .parse::<chrono::DateTime<Utc>>()
+                .unwrap()
+        );
+        assert_eq!(record.values, vec![2.6.into(), 0.08.into()]);
+        assert!(lazy_response.record(5).unwrap().is_none());
+        assert!(lazy_response.column("t_2m:F").is_err());
+
+        let eager_response = meteomatics_connector
+            .query_time_series(vdt.clone(), parameters, locations, None)
+            .await
+            .unwrap();
+        // Compared as text, since the missing value is NaN.
+        assert_eq!(
+            format!("{:?}", lazy_response.parse().unwrap().response_body),
+            format!("{:?}", eager_response.response_body)
+        );
+
+        let multi_point = meteomatics_connector
+            .query_time_series_lazy(
+                vdt,
+                Parameters::try_from("t_2m:C").unwrap(),
+                Locations::Points(vec![
+                    Point::new(47.41, 9.35).unwrap(),
+                    Point::new(47.51, 8.74).unwrap(),
+                ]),
+                None,
+            )
+            .await
+            .unwrap();
+        let record = multi_point.record(2).unwrap().unwrap();
+        assert_eq!(
+            record.location,
+            Some(ResponseLocation::Coordinate((47.51, 8.74)))
+        );
+        assert_eq!(multi_point.column("t_2m:C").unwrap()[2], 4.2.into());
+    }
+
+    #[tokio::test]
+    async fn query_time_series_into_own_type() {
+        println!("\n##### query_time_series_into_own_type:");
+
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct Document {
+            data: Vec<Series>,
+        }
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct Series {
+            parameter: String,
+            coordinates: Vec<Place>,
+        }
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct Place {
+            lat: f64,
+            lon: f64,
+            dates: Vec<Value>,
+        }
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct Value {
+            date: String,
+            value: f64,
+        }
+
+        let body = r#"{"version":"3.0","status":"OK","data":[{"parameter":"t_2m:C",
+            "coordinates":[{"lat":47.4,"lon":9.4,"dates":[{"date":"2023-11-20T12:00:00Z",
+            "value":6.2}]}]}]}"#;
+        let server = MockServer::start(vec![
+            (
+                "t_2m:C/47.4,9.4/json",
+                MockResponse::new(200, "application/json", body),
+            ),
+            (
+                "precip_1h:mm",
+                MockResponse::new(200, "application/json", "{}"),
+            ),
+        ])
+        .await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let vdt = ValidDateTime::single(VDTOffset::Utc("2023-11-20T12:00:00Z".parse().unwrap()));
+        let locations = Locations::Coordinates(Coordinates::from(["47.4,9.4"]));
+
+        let document: Document = meteomatics_connector
+            .query_time_series_as(
+                vdt.clone(),
+                Parameters::try_from("t_2m:C").unwrap(),
+                locations.clone(),
+                None,
+            )
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> document: {:?}", document);
+        assert_eq!(document.data[0].parameter, "t_2m:C");
+        assert_eq!(
+            document.data[0].coordinates[0],
+            Place {
+                lat: 47.4,
+                lon: 9.4,
+                dates: vec![Value {
+                    date: "2023-11-20T12:00:00Z".to_string(),
+                    value: 6.2
+                }]
+            }
+        );
+
+        let result = meteomatics_connector
+            .query_time_series_as::<Document>(
+                vdt,
+                Parameters::try_from("precip_1h:mm").unwrap(),
+                locations,
+                None,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(ConnectorError::Parse {
+                format: Format::JSON,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn split_query_exceeding_request_limits() {
+        println!("\n##### split_query_exceeding_request_limits:");
+
+        let server = MockServer::start(vec![
+            (
+                "t_2m:C/47.41,9.35/",
+                MockResponse::csv("validdate;t_2m:C\n2023-11-20T00:00:00Z;3.4\n"),
+            ),
+            (
+                "precip_1h:mm/47.41,9.35/",
+                MockResponse::csv("validdate;precip_1h:mm\n2023-11-20T00:00:00Z;0.1\n"),
+            ),
+            (
+                "t_2m:C/47.51,8.74/",
+                MockResponse::csv("validdate;t_2m:C\n2023-11-20T00:00:00Z;4.2\n"),
+            ),
+            (
+                "precip_1h:mm/47.51,8.74/",
+                MockResponse::csv("validdate;precip_1h:mm\n2023-11-20T00:00:00Z;0.2\n"),
+            ),
+        ])
+        .await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap()
+                .with_request_limits(RequestLimits {
+                    max_parameters: Some(1),
+                    max_points: Some(1),
+                    max_time_steps: None,
+                    max_data_points: None,
+                });
+
+        let vdt = ValidDateTime::single(VDTOffset::Utc("2023-11-20T00:00:00Z".parse().unwrap()));
+        let locations: Locations = Locations::Points(vec![
+            Point::new(47.41, 9.35).unwrap(),
+            Point::new(47.51, 8.74).unwrap(),
+        ]);
+        let response = meteomatics_connector
+            .query_time_series(
+                vdt,
+                Parameters::try_from("t_2m:C,precip_1h:mm").unwrap(),
+                locations,
+                None,
+            )
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response.response_body);
+
+        assert_eq!(server.requests().len(), 4);
+        assert_eq!(
+            response.response_body.response_headers,
+            vec!["validdate", "t_2m:C", "precip_1h:mm"]
+        );
+        let split = response.response_body.split_by_location();
+        assert_eq!(split.len(), 2);
+        assert_eq!(
+            split[1].0,
+            Some(crate::connector_response::ResponseLocation::Coordinate((
+                47.51, 8.74
+            )))
+        );
+        assert_eq!(split[1].1.column("precip_1h:mm"), Some(vec![0.2]));
+        assert_eq!(split[0].1.column("t_2m:C"), Some(vec![3.4]));
+    }
+
+    #[tokio::test]
+    async fn split_query_into_uneven_parameter_chunks() {
+        println!("\n##### split_query_into_uneven_parameter_chunks:");
+
+        let server = MockServer::start(vec![
+            (
+                "t_2m:C,precip_1h:mm/",
+                MockResponse::csv(
+                    "validdate;t_2m:C;precip_1h:mm\n\
+                     2023-11-20T00:00:00Z;3.4;0.0\n\
+                     2023-11-20T01:00:00Z;3.1;0.2\n",
+                ),
+            ),
+            (
+                "wind_speed_10m:ms/",
+                MockResponse::csv(
+                    "validdate;wind_speed_10m:ms\n\
+                     2023-11-20T00:00:00Z;4.5\n\
+                     2023-11-20T01:00:00Z;5.0\n",
+                ),
+            ),
+        ])
+        .await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap()
+                .with_request_limits(RequestLimits {
+                    max_parameters: Some(2),
+                    max_points: None,
+                    max_time_steps: None,
+                    max_data_points: None,
+                });
+
+        let vdt = ValidDateTimeBuilder::default()
+            .start_date_time(VDTOffset::Utc("2023-11-20T00:00:00Z".parse().unwrap()))
+            .end_date_time(VDTOffset::Utc("2023-11-20T01:00:00Z".parse().unwrap()))
+            .time_step(PeriodTime::Hours(1))
+            .build()
+            .unwrap();
+        let response = meteomatics_connector
+            .query_time_series(
+                vdt,
+                Parameters::try_from("t_2m:C,precip_1h:mm,wind_speed_10m:ms").unwrap(),
+                Locations::Points(vec![Point::new(47.41, 9.35).unwrap()]),
+                None,
+            )
+            .await
+            .unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response.response_body);
+
+        assert_eq!(server.requests().len(), 2);
+        assert_eq!(
+            response.response_body.response_headers,
+            vec!["validdate", "t_2m:C", "precip_1h:mm", "wind_speed_10m:ms"]
+        );
+        assert_eq!(response.response_body.response_records.len(), 2);
+        assert_eq!(
+            response.response_body.response_records[1].1,
+            vec![3.1.into(), 0.2.into(), 5.0.into()]
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_parameter_before_request() {
+        println!("\n##### reject_invalid_parameter_before_request:");
+
+        let server = MockServer::start(vec![]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+
+        let (vdt, _) = time_series_query();
+        let parameters = Parameters {
+            p_values: vec![P {
+                k: "t 2m",
+                v: Some("C"),
+                source: None,
+            }],
+        };
+        let locations: Locations =
+            Locations::Coordinates(Coordinates::from(["47.419708", "9.358478"]));
+        let result = meteomatics_connector
+            .query_time_series(vdt, parameters, locations, None)
+            .await;
+        println!(">>>>>>>>>> result: {:?}", result);
+
+        assert!(matches!(result, Err(ConnectorError::InvalidQuery(_))));
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn query_owned_spec_in_spawned_task() {
+        println!("\n##### query_owned_spec_in_spawned_task:");
+
+        let server = MockServer::start(vec![("t_2m:C", MockResponse::csv(TIME_SERIES_CSV))]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+
+        let (vdt, parameters) = time_series_query();
+        let coordinates = ["47.419708".to_string(), "9.358478".to_string()];
+        let owned_spec = OwnedQuerySpec {
+            vdt,
+            parameters: OwnedParameters::from(&parameters),
+            locations: OwnedLocations::Coordinates(coordinates.to_vec()),
+            optionals: None,
+            format: Format::CSV,
+        };
+
+        let handle = tokio::spawn(async move {
+            let query_spec = owned_spec.as_query_spec();
+            meteomatics_connector
+                .query_time_series(
+                    query_spec.vdt,
+                    query_spec.parameters,
+                    query_spec.locations,
+                    query_spec.optionals,
+                )
+                .await
+        });
+        let response = handle.await.unwrap().unwrap();
+        println!(">>>>>>>>>> ResponseBody:\n{}", response.response_body);
+
+        assert!(server.requests()[0]
+            .path
+            .ends_with("/t_2m:C,precip_1h:mm/47.419708,9.358478/csv"));
+        assert_eq!(response.http_status_code, "200");
+    }
+
+    #[tokio::test]
+    async fn query_time_series_unauthorized_from_mock_server() {
+        println!("\n##### query_time_series_unauthorized_from_mock_server:");
+
+        let server =
+            MockServer::start(vec![("t_2m:C", MockResponse::json(401, UNAUTHORIZED_JSON))]).await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("username".to_string(), "wrong".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+
+        let (vdt, parameters) = time_series_query();
+        let locations: Locations =
+            Locations::Coordinates(Coordinates::from(["47.419708", "9.358478"]));
+        let result = meteomatics_connector
+            .query_time_series(vdt, parameters, locations, None)
+            .await;
+        println!(">>>>>>>>>> result: {:?}", result);
+
+        match result {
+            Err(ref connector_error @ ConnectorError::Auth { status, ref detail }) => {
+                assert_eq!(status, 401);
+                assert_eq!(detail.message, "Unauthorized: wrong username or password.");
+                assert_eq!(detail.body, UNAUTHORIZED_JSON);
+                assert!(!connector_error.is_retryable());
+            }
+            _ => panic!("Expected an Auth error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn ping_api() {
+        println!("\n##### ping_api:");
+
+        let healthy = MockServer::start(vec![(
+            "user_stats_json",
+            MockResponse::json(200, r#"{"user statistics":{}}"#),
+        )])
+        .await;
+        let unauthorized = MockServer::start(vec![(
+            "user_stats_json",
+            MockResponse::json(401, UNAUTHORIZED_JSON),
+        )])
+        .await;
+        let connector = |base_url: &str| {
+            MeteomaticsConnector::new("username".to_string(), "password".to_string(), 10)
+                .with_base_url(base_url)
+                .unwrap()
+        };
+
+        let health = connector(healthy.base_url()).ping().await;
+        println!(">>>>>>>>>> health: {:?}", health);
+        assert!(health.is_healthy());
+        assert!(healthy.requests()[0].path.ends_with("/user_stats_json"));
+
+        match connector(unauthorized.base_url()).ping().await {
+            Health::AuthFailed { status, detail } => {
+                assert_eq!(status, 401);
+                assert_eq!(detail.body, UNAUTHORIZED_JSON);
+            }
+            health => panic!("Expected an auth failure, got {:?}", health),
+        }
+
+        let health = connector("http://127.0.0.1:1").ping().await;
+        println!(">>>>>>>>>> health: {:?}", health);
+        assert!(matches!(health, Health::Unreachable(_)));
+    }
+
+    #[tokio::test]
+    async fn rotate_credentials_of_clones() {
+        println!("\n##### rotate_credentials_of_clones:");
+
+        let server = MockServer::start(vec![(
+            "user_stats_json",
+            MockResponse::json(200, r#"{"user statistics":{}}"#),
+        )])
+        .await;
+        let meteomatics_connector =
+            MeteomaticsConnector::new("old".to_string(), "password".to_string(), 10)
+                .with_base_url(server.base_url())
+                .unwrap();
+        let clone = meteomatics_connector.clone();
+
+        assert!(meteomatics_connector.ping().await.is_healthy());
+        meteomatics_connector.rotate_credentials("new".to_string(), "secret".to_string());
+        assert!(clone.ping().await.is_healthy());
+        println!(">>>>>>>>>> clone: {:?}", clone);
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Basic b2xkOnBhc3N3b3Jk")
+        );
+        assert_eq!(
+            requests[1].header("authorization"),
+            Some("Basic bmV3OnNlY3JldA==")
+        );
+        assert!(!format!("{:?}", clone).contains("secret"));
     }
 }
@@ -0,0 +1,17 @@
+//! Hand-written responses in the formats of the Meteomatics API. They are not recorded from the
+//! API, so they only cover the parts of a response the parsers rely on.
+
+/// Two parameters at a single point, hourly, with a `-999` missing value in the last record.
+pub const TIME_SERIES_CSV: &str = include_str!("fixtures/time_series.csv");
+
+/// One parameter at two points, hourly.
+pub const MULTI_POINT_CSV: &str = include_str!("fixtures/multi_point.csv");
+
+/// A 2x2 grid of `t_2m:C`.
+pub const GRID_CSV: &str = include_str!("fixtures/grid.csv");
+
+/// The body of a `401 Unauthorized` response.
+pub const UNAUTHORIZED_JSON: &str = include_str!("fixtures/unauthorized.json");
+
+/// A self-signed root certificate, PEM-encoded.
+pub const ROOT_CERTIFICATE_PEM: &str = include_str!("fixtures/root_certificate.pem");
@@ -0,0 +1,205 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// A canned HTTP response.
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Pause between the head and the body, e.g. to trigger a read timeout.
+    pub body_delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, content_type: &str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: vec![("content-type".to_string(), content_type.to_string())],
+            body: body.into(),
+            body_delay: None,
+        }
+    }
+
+    /// A `200 OK` response with a `text/csv` body.
+    pub fn csv(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, "text/csv", body)
+    }
+
+    /// A response with an `application/json` body, e.g. an API error.
+    pub fn json(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self::new(status, "application/json", body)
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_body_delay(mut self, body_delay: Duration) -> Self {
+        self.body_delay = Some(body_delay);
+        self
+    }
+}
+
+/// A request received by the `MockServer`. Header names are lower-cased.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockRequest {
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// HTTP server on a local port answering each request with the first route whose pattern is
+/// contained in the request path, or `404 Not Found` if none matches. Stops when dropped.
+pub struct MockServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub async fn start(routes: Vec<(&str, MockResponse)>) -> Self {
+        let routes: Arc<Vec<(String, MockResponse)>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|(pattern, response)| (pattern.to_string(), response))
+                .collect(),
+        );
+        let requests = Arc::new(Mutex::new(vec![]));
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Local port must be available");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn({
+            let requests = requests.clone();
+            async move {
+                while let Ok((socket, _)) = listener.accept().await {
+                    tokio::spawn(serve(socket, routes.clone(), requests.clone()));
+                }
+            }
+        });
+
+        Self {
+            base_url,
+            requests,
+            handle,
+        }
+    }
+
+    /// The URL to pass to `with_base_url`.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The requests received so far, in order of arrival.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn serve(
+    mut socket: TcpStream,
+    routes: Arc<Vec<(String, MockResponse)>>,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+) {
+    let mut buffer = vec![];
+    let mut chunk = [0u8; 4096];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        match socket.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    }
+
+    let head = String::from_utf8_lossy(&buffer);
+    let mut lines = head.lines();
+    let path = lines
+        .next()
+        .and_then(|request_line| request_line.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .to_string();
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let response = routes
+        .iter()
+        .find(|(pattern, _)| path.contains(pattern.as_str()))
+        .map(|(_, response)| response.clone())
+        .unwrap_or_else(|| MockResponse::new(404, "text/plain", "Not Found"));
+    requests.lock().unwrap().push(MockRequest { path, headers });
+
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (name, value) in response.headers.iter() {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str(&format!(
+        "content-length: {}\r\nconnection: close\r\n\r\n",
+        response.body.len()
+    ));
+    let _ = socket.write_all(head.as_bytes()).await;
+    if let Some(body_delay) = response.body_delay {
+        tokio::time::sleep(body_delay).await;
+    }
+    let _ = socket.write_all(&response.body).await;
+    let _ = socket.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test_support::{MockResponse, MockServer, GRID_CSV};
+
+    #[tokio::test]
+    async fn serve_matching_route() {
+        println!("\n##### serve_matching_route:");
+
+        let server = MockServer::start(vec![("t_2m:C", MockResponse::csv(GRID_CSV))]).await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!(
+                "{}/2023-11-20T12:00:00Z/t_2m:C/csv",
+                server.base_url()
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), GRID_CSV);
+
+        let response = client
+            .get(format!("{}/other", server.base_url()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+
+        let requests = server.requests();
+        println!(">>>>>>>>>> requests: {:?}", requests);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].path, "/other");
+        assert!(requests[0].header("Host").is_some());
+    }
+}
@@ -0,0 +1,8 @@
+//! Hand-written fixtures and a local mock server, so that the request to parse pipeline can be
+//! exercised without network access or credentials. Enabled by the `test-support` feature.
+
+mod fixtures;
+mod mock_server;
+
+pub use fixtures::*;
+pub use mock_server::{MockRequest, MockResponse, MockServer};